@@ -0,0 +1,117 @@
+/**
+ * Typed wrappers for the plugin's Tauri commands, for in-app dev tooling and tests.
+ *
+ * Each command needs its permission in the app's capabilities; the plugin's default
+ * permission set only covers the read-only ones (see permissions/default.toml).
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ScreenPosition {
+    x: number;
+    y: number;
+}
+
+export interface ScreenSize {
+    width: number;
+    height: number;
+}
+
+export interface WebviewInfo {
+    label: string;
+    windowLabel: string;
+    url: string | null;
+    position: ScreenPosition | null;
+    size: ScreenSize | null;
+}
+
+export interface WindowInfo {
+    label: string;
+    // State the platform couldn't report for this window is null
+    title: string | null;
+    isVisible: boolean | null;
+    isFocused: boolean | null;
+    isMaximized: boolean | null;
+    isFullscreen: boolean | null;
+    // Outer frame, including decorations, in physical pixels
+    position: ScreenPosition | null;
+    size: ScreenSize | null;
+    scaleFactor: number | null;
+    monitor: { name: string | null; position: ScreenPosition; size: ScreenSize; scaleFactor: number } | null;
+    webviews: WebviewInfo[];
+}
+
+export type WindowAction =
+    | 'minimize'
+    | 'maximize'
+    | 'unmaximize'
+    | 'close'
+    | 'show'
+    | 'hide'
+    | 'center'
+    | { setTitle: { title: string } }
+    | { setPosition: { x: number; y: number } }
+    | { setSize: { width: number; height: number } }
+    | { setFullscreen: { fullscreen: boolean } };
+
+export interface ElementResponse {
+    value: string | null;
+    success: boolean;
+    error: string | null;
+}
+
+export async function ping(value?: string): Promise<{ value: string | null }> {
+    return invoke('plugin:mcp|ping', { payload: { value } });
+}
+
+export async function listWindows(): Promise<{ windows: WindowInfo[] }> {
+    return invoke('plugin:mcp|list_windows');
+}
+
+export async function controlWindow(
+    windowLabel: string,
+    action: WindowAction
+): Promise<{ success: boolean; message: string | null }> {
+    return invoke('plugin:mcp|control_window', { payload: { windowLabel, action } });
+}
+
+export async function takeScreenshot(
+    windowLabel: string
+): Promise<{ data: string | null; success: boolean; error: string | null }> {
+    // The screenshot request uses snake_case field names
+    return invoke('plugin:mcp|take_screenshot', { payload: { window_label: windowLabel } });
+}
+
+export async function evalJs(
+    windowLabel: string,
+    script: string
+): Promise<{ result: string | null; success: boolean; error: string | null }> {
+    return invoke('plugin:mcp|eval_js', { payload: { windowLabel, script } });
+}
+
+export async function getHtml(windowLabel: string): Promise<{ html: string | null; success: boolean; error: string | null }> {
+    return invoke('plugin:mcp|get_html', { payload: { windowLabel } });
+}
+
+export async function getTitle(windowLabel: string): Promise<{ title: string | null; success: boolean; error: string | null }> {
+    return invoke('plugin:mcp|get_title', { payload: { windowLabel } });
+}
+
+export async function getUrl(windowLabel: string): Promise<{ url: string | null; success: boolean; error: string | null }> {
+    return invoke('plugin:mcp|get_url', { payload: { windowLabel } });
+}
+
+export async function getElementText(windowLabel: string, selector: string): Promise<ElementResponse> {
+    return invoke('plugin:mcp|get_element_text', { payload: { windowLabel, selector } });
+}
+
+export async function setElementValue(windowLabel: string, selector: string, value: string): Promise<ElementResponse> {
+    return invoke('plugin:mcp|set_element_value', { payload: { windowLabel, selector, value } });
+}
+
+export async function clickElement(windowLabel: string, selector: string): Promise<ElementResponse> {
+    return invoke('plugin:mcp|click_element', { payload: { windowLabel, selector } });
+}
+
+export async function typeText(windowLabel: string, text: string): Promise<{ charsTyped: number; durationMs: number }> {
+    return invoke('plugin:mcp|type_text', { payload: { windowLabel, text } });
+}
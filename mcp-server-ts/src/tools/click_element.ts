@@ -0,0 +1,40 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+import { elementSelectorShape } from "./selector.js";
+
+export function registerClickElementTool(server: McpServer) {
+  server.tool(
+    "click_element",
+    "Clicks an element in the Tauri APP SHELL webview by selector. The element is scrolled into view and hit-tested first: if another element (a modal, overlay or sticky header) covers its center, the call fails and names the covering element instead of clicking the wrong thing.\n\nModes: 'dom' (default) dispatches pointerdown/mousedown/pointerup/mouseup/click events to the element at the click point, without focusing the window or moving the OS pointer. 'os' moves the real system pointer to the element's center (translated to screen pixels for the window's position and DPI) and clicks with it, which also triggers native behaviour such as drag handles, focus and hover.\n\nUse this instead of get_element_position + simulate_mouse_movement. Set force=true to click a covered element anyway; use auto_wait_ms to wait for it to become visible, stable, enabled and uncovered.\n\nReturns: JSON with the targeted element, the element hit-tested at its center, the element that actually received the click (for 'os', the element that got the real mousedown, or null if none arrived), and the click position.",
+    {
+      ...elementSelectorShape,
+      mode: z.enum(["dom", "os"]).optional().describe("How to click: 'dom' events (default) or the real 'os' pointer."),
+      button: z.enum(["left", "right", "middle"]).optional().describe("Mouse button to use. Default 'left'."),
+      click_count: z.number().int().min(1).max(3).optional().describe("Number of clicks: 2 for a double click, 3 for a triple click. Default 1."),
+      force: z.boolean().optional().describe("Click even if another element covers the element's center."),
+      auto_wait_ms: z.number().int().min(0).max(120000).optional().describe("Wait up to this many milliseconds for the element to be visible, stable, enabled and uncovered."),
+      window_label: z.string().optional().describe("The label of the webview containing the element. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Click Element",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('click_element', params);
+
+        const result = await socketClient.sendCommand('click_element', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Click element error:', error);
+        return createErrorResponse(`Failed to click element: ${(error as Error).message}`);
+      }
+    },
+  );
+}
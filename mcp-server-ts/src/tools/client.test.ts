@@ -0,0 +1,40 @@
+/**
+ * Unit tests for client.ts
+ *
+ * Tests turning the plugin's error responses into thrown errors.
+ */
+
+import { describe, it, expect } from 'vitest';
+import { SocketCommandError, toSocketError } from './client.js';
+
+describe('toSocketError', () => {
+  it('should keep the code, details and retryability of a structured error', () => {
+    const error = toSocketError({
+      code: 'ambiguous_selector',
+      message: '2 elements match',
+      details: { matchCount: 2 },
+      retryable: false,
+    });
+
+    expect(error).toBeInstanceOf(SocketCommandError);
+    expect(error.code).toBe('ambiguous_selector');
+    expect(error.details).toEqual({ matchCount: 2 });
+    expect(error.retryable).toBe(false);
+    expect(error.message).toBe('ambiguous_selector: 2 elements match\n{"matchCount":2}');
+  });
+
+  it('should leave details out of the message when there are none', () => {
+    const error = toSocketError({ code: 'timeout', message: 'no reply', details: null, retryable: true });
+
+    expect(error.message).toBe('timeout: no reply');
+    expect(error.retryable).toBe(true);
+  });
+
+  it('should accept the plain string errors of older plugins', () => {
+    const error = toSocketError('Window not found: main');
+
+    expect(error.message).toBe('Window not found: main');
+    expect(error.code).toBe('internal');
+    expect(toSocketError(undefined).message).toBe('Command failed without specific error');
+  });
+});
@@ -2,25 +2,62 @@ import * as net from 'net';
 import * as os from 'os';
 import * as fs from 'fs';
 import { logger } from '../logger.js';
+import { pickInstance } from './discovery.js';
 
 // Constants
+// Socket used by plugin versions that predate per-app names and discovery
 const SOCKET_FILENAME = 'tauri-mcp.sock';
 const DEFAULT_SOCKET_PATH = `/private/tmp/${SOCKET_FILENAME}`;
 
 // Connection configuration types
 export interface IpcConfig {
   type: 'ipc';
+  // Without a path, connect to the newest running app found through discovery
   path?: string;
+  // Only discover apps with this application name
+  app?: string;
 }
 
 export interface TcpConfig {
   type: 'tcp';
   host: string;
   port: number;
+  // Shared secret sent in the auth handshake when the plugin is configured with a token
+  token?: string;
 }
 
 export type ConnectionConfig = IpcConfig | TcpConfig;
 
+// A failed command as the plugin reports it: a stable code such as 'window_not_found' or
+// 'timeout', details for that code, and whether sending the request again may succeed
+export class SocketCommandError extends Error {
+  constructor(
+    message: string,
+    readonly code: string,
+    readonly details: unknown = null,
+    readonly retryable = false
+  ) {
+    super(message);
+    this.name = 'SocketCommandError';
+  }
+}
+
+// The response's error as a SocketCommandError. The message leads with the code and ends with
+// the details, so tools that only pass the message on still tell the model what to fix.
+// Plugins before protocol version 2 sent a plain string.
+export function toSocketError(error: unknown): SocketCommandError {
+  if (error && typeof error === 'object') {
+    const { code = 'internal', message = 'Command failed', details = null, retryable = false } =
+      error as { code?: string; message?: string; details?: unknown; retryable?: boolean };
+    const text = details == null ? `${code}: ${message}` : `${code}: ${message}\n${JSON.stringify(details)}`;
+    return new SocketCommandError(text, code, details, retryable);
+  }
+  return new SocketCommandError(
+    typeof error === 'string' && error ? error : 'Command failed without specific error',
+    'internal'
+  );
+}
+
 // Socket client for Tauri IPC/TCP
 export class TauriSocketClient {
   private config: ConnectionConfig;
@@ -31,8 +68,8 @@ export class TauriSocketClient {
   private reconnectAttempts = 0;
 
   constructor(config?: ConnectionConfig) {
-    // Default to IPC with default path
-    this.config = config || { type: 'ipc', path: DEFAULT_SOCKET_PATH };
+    // Default to IPC with a discovered app
+    this.config = config || { type: 'ipc' };
   }
 
   async connect(): Promise<void> {
@@ -51,14 +88,8 @@ export class TauriSocketClient {
         connectionInfo = `TCP ${this.config.host}:${this.config.port}`;
       } else {
         // IPC connection
-        let connectionPath = this.config.path || DEFAULT_SOCKET_PATH;
-        
-        // On Windows, the socket is created as a named pipe in a specific location
-        if (os.platform() === 'win32') {
-          connectionPath = `\\\\.\\pipe\\tmp\\${SOCKET_FILENAME}`;
-          logger.debug(`Using Windows-specific pipe path: ${connectionPath}`);
-        }
-        
+        const connectionPath = this.config.path || discoverSocketPath(this.config.app);
+
         connectionOptions = { path: connectionPath };
         connectionInfo = `IPC ${connectionPath}`;
       }
@@ -74,6 +105,20 @@ export class TauriSocketClient {
         this.client!.on('data', (data) => {
           this.handleData(data);
         });
+
+        // The server rejects every command until the auth handshake succeeds
+        if (this.config.type === 'tcp' && this.config.token) {
+          this.sendCommand('auth', { token: this.config.token })
+            .then(() => {
+              logger.info('Authenticated with Tauri socket server');
+              resolve();
+            })
+            .catch(err => {
+              logger.error('Authentication with Tauri socket server failed:', err);
+              reject(err);
+            });
+          return;
+        }
         
         resolve();
       });
@@ -119,14 +164,21 @@ export class TauriSocketClient {
       try {
         const response = JSON.parse(jsonStr);
         
-        // Process all matching callbacks that might be waiting for this response
-        // Rather than just taking the first one, match based on timestamps (oldest first)
+        // Responses echoing an id we no longer track belong to a request that
+        // already timed out - drop them instead of handing them to another caller
+        if (response.id != null && !this.responseCallbacks.has(response.id)) {
+          logger.debug(`Dropping response for unknown or timed out request ${response.id}`);
+          continue;
+        }
+
+        // Prefer the request id echoed back by the server; fall back to the
+        // oldest pending callback for servers that don't echo ids
         const callbackIds = Array.from(this.responseCallbacks.keys());
         
         if (callbackIds.length > 0) {
           // Sort by timestamp (assuming IDs start with timestamp)
           callbackIds.sort();
-          const callbackId = callbackIds[0];
+          const callbackId = response.id != null ? response.id : callbackIds[0];
           
           const callback = this.responseCallbacks.get(callbackId);
           if (callback) {
@@ -134,10 +186,10 @@ export class TauriSocketClient {
             this.responseCallbacks.delete(callbackId);
             
             if (!response.success) {
-              // If the server indicates failure, reject the promise with the error message
-              const errorMsg = response.error || 'Command failed without specific error';
-              logger.error(`Command failed with error: ${errorMsg}`);
-              callback.reject(new Error(errorMsg));
+              // If the server indicates failure, reject the promise with the structured error
+              const error = toSocketError(response.error);
+              logger.error(`Command failed with error: ${error.message}`);
+              callback.reject(error);
             } else {
               callback.resolve(response.data);
             }
@@ -199,15 +251,16 @@ export class TauriSocketClient {
         finalPayload = payload;
       }
       
+      // Generate a unique ID for this request including timestamp for ordering
+      const requestId = Date.now().toString() + Math.random().toString(36).substring(2);
+      this.responseCallbacks.set(requestId, { resolve, reject });
+
       const request = JSON.stringify({
+        id: requestId,
         command,
         payload: finalPayload
       }) + '\n';
 
-      // Generate a unique ID for this request including timestamp for ordering
-      const requestId = Date.now().toString() + Math.random().toString(36).substring(2);
-      this.responseCallbacks.set(requestId, { resolve, reject });
-
       // Log the request
       logger.debug(`Sending request: ${command} with payload: ${JSON.stringify(finalPayload)}`);
       
@@ -231,6 +284,41 @@ export class TauriSocketClient {
   }
 }
 
+// Socket of the newest running app (matching `app` if given) that listens on IPC,
+// falling back to the fixed path older plugin versions used
+function discoverSocketPath(app?: string): string {
+  const instance = pickInstance(app, undefined, 'ipc');
+  if (instance) {
+    logger.debug(`Discovered ${instance.app} (pid ${instance.pid}) at ${instance.address}`);
+    return instance.address;
+  }
+
+  logger.warn(`No running ${app ? `"${app}" ` : ''}app found through discovery, trying the legacy socket path`);
+  // On Windows, the socket is created as a named pipe in a specific location
+  if (os.platform() === 'win32') {
+    return `\\\\.\\pipe\\tmp\\${SOCKET_FILENAME}`;
+  }
+  return DEFAULT_SOCKET_PATH;
+}
+
+// Auth token from TAURI_MCP_AUTH_TOKEN, or the token file the plugin wrote (TAURI_MCP_AUTH_TOKEN_FILE)
+function readAuthToken(): string | undefined {
+  if (process.env.TAURI_MCP_AUTH_TOKEN) {
+    return process.env.TAURI_MCP_AUTH_TOKEN;
+  }
+
+  const tokenFile = process.env.TAURI_MCP_AUTH_TOKEN_FILE;
+  if (tokenFile) {
+    try {
+      return fs.readFileSync(tokenFile, 'utf8').trim();
+    } catch (error) {
+      logger.error(`Failed to read auth token file ${tokenFile}:`, error);
+    }
+  }
+
+  return undefined;
+}
+
 // Create a singleton instance based on environment variables or defaults
 function createSocketClient(): TauriSocketClient {
   // Check for environment variables to configure connection
@@ -239,20 +327,24 @@ function createSocketClient(): TauriSocketClient {
   if (connectionType === 'tcp') {
     const host = process.env.TAURI_MCP_TCP_HOST || '127.0.0.1';
     const port = parseInt(process.env.TAURI_MCP_TCP_PORT || '9999', 10);
+    const token = readAuthToken();
     
-    logger.debug(`Creating TCP socket client: ${host}:${port}`);
+    logger.debug(`Creating TCP socket client: ${host}:${port}${token ? ' (with auth token)' : ''}`);
     return new TauriSocketClient({
       type: 'tcp',
       host,
-      port
+      port,
+      token
     });
   } else {
     // Default to IPC
     const path = process.env.TAURI_MCP_IPC_PATH;
-    logger.debug(`Creating IPC socket client: ${path || 'default path'}`);
+    const app = process.env.TAURI_MCP_APP;
+    logger.debug(`Creating IPC socket client: ${path || (app ? `discovered "${app}"` : 'discovered app')}`);
     return new TauriSocketClient({
       type: 'ipc',
-      path
+      path,
+      app
     });
   }
 }
@@ -0,0 +1,83 @@
+/**
+ * Unit tests for discovery.ts
+ *
+ * Tests reading the discovery records written by running Tauri apps.
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { listInstances, pickInstance } from './discovery.js';
+
+function writeRecord(dir: string, app: string, pid: number, startedAt: number) {
+  const record = {
+    app,
+    pid,
+    transport: 'ipc',
+    address: `/tmp/tauri-mcp-${app}-${pid}.sock`,
+    protocolVersion: 1,
+    startedAt,
+  };
+  fs.writeFileSync(path.join(dir, `${pid}.json`), JSON.stringify(record));
+}
+
+describe('listInstances', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tauri-mcp-discovery-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should return running instances, newest first', () => {
+    writeRecord(dir, 'Older', process.pid, 1000);
+    writeRecord(dir, 'Newer', process.ppid, 2000);
+
+    const instances = listInstances(dir);
+
+    expect(instances.map(instance => instance.app)).toEqual(['Newer', 'Older']);
+  });
+
+  it('should skip records of exited processes and unreadable files', () => {
+    writeRecord(dir, 'Gone', 2 ** 22 + 1, 1000);
+    fs.writeFileSync(path.join(dir, 'broken.json'), '{');
+
+    expect(listInstances(dir)).toEqual([]);
+  });
+
+  it('should return nothing when the directory does not exist', () => {
+    expect(listInstances(path.join(dir, 'missing'))).toEqual([]);
+  });
+});
+
+describe('pickInstance', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tauri-mcp-discovery-'));
+    writeRecord(dir, 'Editor', process.pid, 1000);
+    writeRecord(dir, 'Viewer', process.ppid, 2000);
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should pick the newest instance by default', () => {
+    expect(pickInstance(undefined, dir)?.app).toBe('Viewer');
+  });
+
+  it('should match the app name case-insensitively', () => {
+    expect(pickInstance('editor', dir)?.app).toBe('Editor');
+    expect(pickInstance('missing', dir)).toBeUndefined();
+  });
+
+  it('should filter by transport', () => {
+    expect(pickInstance(undefined, dir, 'ipc')?.app).toBe('Viewer');
+    expect(pickInstance(undefined, dir, 'tcp')).toBeUndefined();
+  });
+});
@@ -0,0 +1,70 @@
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { logger } from '../logger.js';
+
+// A running app's socket server, as the plugin records it in the discovery directory
+export interface InstanceRecord {
+  app: string;
+  pid: number;
+  transport: 'ipc' | 'tcp';
+  // Socket path (a \\.\pipe\ path on Windows) or host:port
+  address: string;
+  protocolVersion: number;
+  // Milliseconds since the Unix epoch
+  startedAt: number;
+}
+
+// Same rule as the plugin: $XDG_RUNTIME_DIR when set to an absolute path, otherwise the temp directory
+export function discoveryDir(): string {
+  const runtimeDir = process.env.XDG_RUNTIME_DIR;
+  const base = runtimeDir && path.isAbsolute(runtimeDir) ? runtimeDir : os.tmpdir();
+  return path.join(base, 'tauri-mcp');
+}
+
+// Signal 0 checks the process exists without touching it; EPERM means it exists but isn't ours
+function isRunning(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    return (error as NodeJS.ErrnoException).code === 'EPERM';
+  }
+}
+
+// Running instances, newest first. Records left by processes that have exited are skipped.
+export function listInstances(dir: string = discoveryDir()): InstanceRecord[] {
+  let files: string[];
+  try {
+    files = fs.readdirSync(dir).filter(file => file.endsWith('.json'));
+  } catch {
+    return [];
+  }
+
+  const instances: InstanceRecord[] = [];
+  for (const file of files) {
+    try {
+      const record = JSON.parse(fs.readFileSync(path.join(dir, file), 'utf8')) as InstanceRecord;
+      if (isRunning(record.pid)) {
+        instances.push(record);
+      }
+    } catch (error) {
+      logger.debug(`Skipping unreadable discovery record ${file}:`, error);
+    }
+  }
+  return instances.sort((a, b) => b.startedAt - a.startedAt);
+}
+
+// The newest instance, optionally only those whose app name matches (case-insensitive)
+// or that listen on the given transport
+export function pickInstance(
+  app?: string,
+  dir?: string,
+  transport?: InstanceRecord['transport']
+): InstanceRecord | undefined {
+  const wanted = app?.toLowerCase();
+  return listInstances(dir).find(instance =>
+    (!wanted || instance.app.toLowerCase() === wanted) &&
+    (!transport || instance.transport === transport)
+  );
+}
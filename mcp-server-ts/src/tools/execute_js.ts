@@ -7,11 +7,11 @@ import { createErrorResponse, createSuccessResponse, formatResultAsText, logComm
 export function registerExecuteJsTool(server: McpServer) {
   server.tool(
     "execute_js",
-    "Executes JavaScript code in the Tauri app shell webview and RETURNS THE RESULT. Use this for inspecting or manipulating the app shell UI (toolbar, settings panel, navigation sidebar, etc.). The return value is the result of the last expression or resolved promise, serialized as text.\n\nWhen to use this vs Playwright browser_evaluate:\n- Use execute_js for the APP SHELL (Tauri webview): toolbar buttons, settings, navigation, app-level state.\n- Use Playwright browser_evaluate for PREVIEW CONTENT: the document preview is served at localhost:8080 and is directly accessible to Playwright.\n\nReturns: The serialized result of the JavaScript execution (strings, numbers, objects, arrays). Errors are returned as error messages.\n\nExamples:\n- `document.querySelector('.toolbar-btn').textContent` - read a toolbar button label\n- `document.querySelectorAll('.nav-item').length` - count navigation items\n- `getComputedStyle(document.body).backgroundColor` - check app theme color",
+    "Executes JavaScript code in the Tauri app shell webview and RETURNS THE RESULT. Use this for inspecting or manipulating the app shell UI (toolbar, settings panel, navigation sidebar, etc.). The return value is the result of the last expression, with returned Promises awaited, as JSON `{ result, type }`.\n\nWhen to use this vs Playwright browser_evaluate:\n- Use execute_js for the APP SHELL (Tauri webview): toolbar buttons, settings, navigation, app-level state.\n- Use Playwright browser_evaluate for PREVIEW CONTENT: the document preview is served at localhost:8080 and is directly accessible to Playwright.\n\nReturns: The JSON result of the JavaScript execution. Values plain JSON can't hold are tagged objects with a `$type` field: circular references, DOM elements and nodes, Map, Set, Date, Error (with stack), functions, symbols, bigints and NaN/Infinity. Errors and rejected Promises are returned as error messages with the stack.\n\nExamples:\n- `document.querySelector('.toolbar-btn').textContent` - read a toolbar button label\n- `document.querySelectorAll('.nav-item').length` - count navigation items\n- `getComputedStyle(document.body).backgroundColor` - check app theme color",
     {
       code: z.string().describe("Required. The string of JavaScript code to be executed in the target window's webview context. Ensure the code is safe and achieves the intended purpose. Malformed or malicious code can lead to errors or unwanted behavior."),
       window_label: z.string().default("main").describe("The identifier (e.g., visible title or internal label) of the application window where the JavaScript code will be executed. Defaults to 'main' if not specified."),
-      timeout_ms: z.number().int().positive().optional().describe("The maximum time in milliseconds to allow for the JavaScript execution. If the script exceeds this timeout, its execution will be terminated, and an error may be returned."),
+      timeout_ms: z.number().int().positive().optional().describe("The maximum time in milliseconds to wait for the result, including any Promise the code returns. Defaults to 5000. A Promise that has not settled by then produces an error; synchronous code cannot be interrupted."),
     },
     {
       title: "Execute JavaScript Code in Specified Application Window",
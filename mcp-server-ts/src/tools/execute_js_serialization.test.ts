@@ -0,0 +1,210 @@
+/**
+ * Tests for the execute_js result serialization.
+ *
+ * The guest-js handler (guest-js/index.ts) awaits returned Promises and converts the
+ * result to JSON with serializeJsValue() before sending it to the Rust side, which
+ * passes it through as `data.result`. This test validates that exact algorithm.
+ */
+
+import { describe, it, expect } from 'vitest';
+
+const MAX_SERIALIZE_DEPTH = 20;
+const MAX_NODE_PREVIEW = 300;
+
+/**
+ * Mirrors serializeJsValue() in guest-js/index.ts.
+ * If the guest-js implementation changes, update this copy to match so the tests stay meaningful.
+ */
+function serializeJsValue(value: any, ancestors: object[] = [], depth = 0): any {
+    switch (typeof value) {
+        case 'undefined':
+            return null;
+        case 'string':
+        case 'boolean':
+            return value;
+        case 'number':
+            return Number.isFinite(value) ? value : { $type: 'number', value: String(value) };
+        case 'bigint':
+            return { $type: 'bigint', value: value.toString() };
+        case 'symbol':
+            return { $type: 'symbol', description: value.description ?? null };
+        case 'function':
+            return { $type: 'function', name: value.name || null };
+    }
+    if (value === null) {
+        return null;
+    }
+    if (ancestors.includes(value)) {
+        return { $type: 'circular' };
+    }
+    if (depth >= MAX_SERIALIZE_DEPTH) {
+        return { $type: 'maxDepth' };
+    }
+
+    const inner = [...ancestors, value];
+    const serialize = (v: any) => serializeJsValue(v, inner, depth + 1);
+
+    if (value instanceof Error) {
+        const error: Record<string, any> = {
+            $type: 'error',
+            name: value.name,
+            message: value.message,
+            stack: value.stack ?? null
+        };
+        if ('cause' in value) {
+            error.cause = serialize((value as any).cause);
+        }
+        return error;
+    }
+    if (typeof Element !== 'undefined' && value instanceof Element) {
+        return {
+            $type: 'element',
+            tagName: value.tagName.toLowerCase(),
+            id: value.id || null,
+            className: typeof value.className === 'string' ? value.className || null : null,
+            outerHTML: value.outerHTML.substring(0, MAX_NODE_PREVIEW)
+        };
+    }
+    if (typeof Document !== 'undefined' && value instanceof Document) {
+        return { $type: 'document', url: value.URL, title: value.title };
+    }
+    if (typeof Node !== 'undefined' && value instanceof Node) {
+        return {
+            $type: 'node',
+            nodeName: value.nodeName,
+            textContent: value.textContent?.substring(0, MAX_NODE_PREVIEW) ?? null
+        };
+    }
+    if (typeof Window !== 'undefined' && value instanceof Window) {
+        return { $type: 'window', location: value.location?.href ?? null };
+    }
+    if (value instanceof Map) {
+        return { $type: 'map', entries: Array.from(value.entries(), ([k, v]) => [serialize(k), serialize(v)]) };
+    }
+    if (value instanceof Set) {
+        return { $type: 'set', values: Array.from(value.values(), serialize) };
+    }
+    if (value instanceof Date) {
+        return { $type: 'date', value: Number.isNaN(value.getTime()) ? null : value.toISOString() };
+    }
+    if (value instanceof RegExp) {
+        return { $type: 'regexp', value: String(value) };
+    }
+    if (value instanceof Promise) {
+        return { $type: 'promise' };
+    }
+    if (ArrayBuffer.isView(value) && !(value instanceof DataView)) {
+        return { $type: value.constructor.name, values: Array.from(value as any, serialize) };
+    }
+    if (Array.isArray(value)) {
+        return value.map(serialize);
+    }
+    if (typeof value.toJSON === 'function') {
+        return serialize(value.toJSON());
+    }
+
+    const result: Record<string, any> = {};
+    for (const key of Object.keys(value)) {
+        try {
+            result[key] = serialize(value[key]);
+        } catch (error) {
+            // Throwing getters are reported in place
+            result[key] = serialize(error);
+        }
+    }
+    return result;
+}
+
+describe('execute_js result serialization', () => {
+  describe('primitive values', () => {
+    it('should keep strings, booleans and finite numbers', () => {
+      expect(serializeJsValue('hello')).toBe('hello');
+      expect(serializeJsValue('')).toBe('');
+      expect(serializeJsValue(true)).toBe(true);
+      expect(serializeJsValue(0)).toBe(0);
+      expect(serializeJsValue(42)).toBe(42);
+    });
+
+    it('should map undefined and null to null', () => {
+      expect(serializeJsValue(undefined)).toBeNull();
+      expect(serializeJsValue(null)).toBeNull();
+    });
+
+    it('should tag values JSON cannot represent', () => {
+      expect(serializeJsValue(NaN)).toEqual({ $type: 'number', value: 'NaN' });
+      expect(serializeJsValue(-Infinity)).toEqual({ $type: 'number', value: '-Infinity' });
+      expect(serializeJsValue(10n)).toEqual({ $type: 'bigint', value: '10' });
+      expect(serializeJsValue(Symbol('s'))).toEqual({ $type: 'symbol', description: 's' });
+      expect(serializeJsValue(function load() {})).toEqual({ $type: 'function', name: 'load' });
+    });
+  });
+
+  describe('objects', () => {
+    it('should keep plain objects and arrays', () => {
+      expect(serializeJsValue({ a: 1, nested: { deep: [1, 2] } })).toEqual({ a: 1, nested: { deep: [1, 2] } });
+      expect(serializeJsValue([])).toEqual([]);
+    });
+
+    it('should replace cycles instead of throwing', () => {
+      const state: any = { name: 'store' };
+      state.self = state;
+      expect(serializeJsValue(state)).toEqual({ name: 'store', self: { $type: 'circular' } });
+    });
+
+    it('should serialize shared but acyclic references twice', () => {
+      const shared = { id: 1 };
+      expect(serializeJsValue([shared, shared])).toEqual([{ id: 1 }, { id: 1 }]);
+    });
+
+    it('should serialize Map and Set contents', () => {
+      expect(serializeJsValue(new Map([['k', new Set([1])]]))).toEqual({
+        $type: 'map',
+        entries: [['k', { $type: 'set', values: [1] }]],
+      });
+    });
+
+    it('should serialize Errors with stack and cause', () => {
+      const error = serializeJsValue(new TypeError('bad', { cause: 'root' }));
+      expect(error.$type).toBe('error');
+      expect(error.name).toBe('TypeError');
+      expect(error.message).toBe('bad');
+      expect(typeof error.stack).toBe('string');
+      expect(error.cause).toBe('root');
+    });
+
+    it('should serialize Dates and honour toJSON', () => {
+      expect(serializeJsValue(new Date(0))).toEqual({ $type: 'date', value: '1970-01-01T00:00:00.000Z' });
+      expect(serializeJsValue({ toJSON: () => 'custom' })).toBe('custom');
+    });
+
+    it('should report throwing getters in place', () => {
+      const value = Object.defineProperty({}, 'broken', {
+        enumerable: true,
+        get() { throw new Error('nope'); },
+      });
+      expect(serializeJsValue(value).broken.message).toBe('nope');
+    });
+
+    it('should stop at the maximum depth', () => {
+      let value: any = {};
+      for (let i = 0; i < MAX_SERIALIZE_DEPTH + 5; i++) value = { child: value };
+      let serialized = serializeJsValue(value);
+      for (let i = 0; i < MAX_SERIALIZE_DEPTH; i++) serialized = serialized.child;
+      expect(serialized).toEqual({ $type: 'maxDepth' });
+    });
+  });
+
+  describe('consistency with Rust side expectations', () => {
+    it('should always produce JSON-serializable output', () => {
+      const cyclic: any = [];
+      cyclic.push(cyclic);
+      const testValues = [
+        undefined, null, 0, '', 'text', true, {}, [], NaN, Infinity, 1n,
+        new Map([[{}, []]]), new Set(['a']), new Error('e'), cyclic, new Uint8Array([1, 2]),
+      ];
+      for (const val of testValues) {
+        expect(() => JSON.stringify(serializeJsValue(val))).not.toThrow();
+      }
+    });
+  });
+});
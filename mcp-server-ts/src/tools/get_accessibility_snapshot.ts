@@ -0,0 +1,36 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerGetAccessibilitySnapshotTool(server: McpServer) {
+  server.tool(
+    "get_accessibility_snapshot",
+    "Returns a compact accessibility tree of the Tauri APP SHELL webview, like Playwright's browser_snapshot: each node has a role (button, link, textbox, heading, ...), accessible name, value and states (checked, disabled, expanded, selected, focused, ...) plus its bounding box. Hidden elements are dropped and generic div/span containers are flattened, so the result is far smaller than get_dom.\n\nEvery element node has a `ref` (e.g. \"e12\"). Pass it to get_element_position or send_text_to_element with selector_type 'ref' to click or type into exactly that element. Refs stay the same for an element across snapshots; take a new snapshot if a ref is reported as gone.\n\nWhen to use this vs get_dom:\n- Use get_accessibility_snapshot to understand what is on screen and to pick elements to act on.\n- Use get_dom only when you need raw markup, attributes or styles.\n\nReturns: JSON `{ url, tree, nodeCount }`. `tree` is the document node with nested `children`; bounds are CSS pixels relative to the viewport.",
+    {
+      window_label: z.string().optional().describe("The label of the webview to snapshot. Defaults to the plugin's default target (usually 'main')."),
+      root: z.string().optional().describe("CSS selector of the subtree to snapshot, e.g. '#sidebar' or 'dialog[open]'. Snapshots the whole body when omitted."),
+      interactive_only: z.boolean().optional().describe("Only keep interactive elements (buttons, links, inputs, checkboxes, tabs, options, ...), dropping text and structural nodes. Useful on large pages."),
+      max_depth: z.number().int().positive().optional().describe("Maximum nesting depth of the returned tree."),
+    },
+    {
+      title: "Get Accessibility Snapshot of Application Window",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('get_accessibility_snapshot', params);
+
+        const result = await socketClient.sendCommand('get_accessibility_snapshot', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Accessibility snapshot error:', error);
+        return createErrorResponse(`Failed to get accessibility snapshot: ${(error as Error).message}`);
+      }
+    },
+  );
+}
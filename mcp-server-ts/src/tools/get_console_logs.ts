@@ -0,0 +1,38 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerGetConsoleLogsTool(server: McpServer) {
+  server.tool(
+    "get_console_logs",
+    "Reads what the Tauri app shell webview has written to its console: console.log/info/warn/error/debug calls, uncaught errors and unhandled Promise rejections. Output is buffered from page load (the most recent 1000 entries per webview), so this also shows what happened before you connected.\n\nUse this first when something in the app misbehaves, after an action to check it produced no errors, or with `clear: true` to reset the buffer before reproducing a problem.\n\nReturns: JSON `{ entries, buffered, dropped }`. Entries are oldest first, each with level, timestamp (epoch ms), source (console, uncaught or unhandledrejection), message text, args as JSON, and a stack for warnings and errors. `buffered` counts entries before filtering; `dropped` counts entries evicted because the buffer was full.",
+    {
+      window_label: z.string().optional().describe("The label of the webview whose console is read. Defaults to the plugin's default target (usually 'main')."),
+      levels: z.array(z.enum(["debug", "log", "info", "warn", "error"])).optional().describe("Only return entries with these levels, e.g. [\"warn\", \"error\"]. Uncaught errors and unhandled rejections have level 'error'. Returns every level when omitted."),
+      since: z.number().int().nonnegative().optional().describe("Only return entries logged at or after this time, in milliseconds since the Unix epoch. Use the timestamp of the last entry you saw to fetch only newer output."),
+      pattern: z.string().optional().describe("Only return entries whose message contains this text (case-insensitive)."),
+      limit: z.number().int().positive().optional().describe("Return at most this many entries, keeping the most recent."),
+      clear: z.boolean().optional().describe("Empty the buffer after reading it, so the next call only shows new output. Clears every entry, not just the ones returned."),
+    },
+    {
+      title: "Read Console Logs and Uncaught Errors from Application Window",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('get_console_logs', params);
+
+        const result = await socketClient.sendCommand('get_console_logs', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Get console logs error:', error);
+        return createErrorResponse(`Failed to get console logs: ${(error as Error).message}`);
+      }
+    },
+  );
+}
@@ -6,9 +6,15 @@ import { socketClient } from "./client.js";
 export function registerGetDomTool(server: McpServer) {
   server.tool(
     "get_dom",
-    "Retrieves the full HTML DOM of the Tauri APP SHELL webview as a string. This returns the outer application chrome (toolbar, sidebar, settings, navigation), NOT the preview/document content.\n\nWhen to use this vs Playwright browser_snapshot:\n- Use get_dom for the APP SHELL: to inspect toolbar state, navigation structure, settings panels, modal dialogs in the Tauri webview.\n- Use Playwright browser_snapshot on localhost:8080 for PREVIEW CONTENT: to inspect the rendered document/article being previewed.\n\nReturns: The complete HTML string of the app shell webview's document. This can be large; consider using execute_js with a targeted querySelector if you only need a specific element.",
+    "Retrieves the full HTML DOM of the Tauri APP SHELL webview as a string. This returns the outer application chrome (toolbar, sidebar, settings, navigation), NOT the preview/document content.\n\nWhen to use this vs Playwright browser_snapshot:\n- Use get_dom for the APP SHELL: to inspect toolbar state, navigation structure, settings panels, modal dialogs in the Tauri webview.\n- Use Playwright browser_snapshot on localhost:8080 for PREVIEW CONTENT: to inspect the rendered document/article being previewed.\n\nNarrowing the result: set `root` to a CSS selector to return one element, `strip` to drop scripts, styles, SVG contents or noisy attributes, `visible_only` to drop hidden elements, and `max_depth` to cut off deep nesting. For large documents set `max_bytes`; the HTML then comes back in pages and each page ends with the cursor to pass back for the next one. get_accessibility_snapshot is usually a more compact way to understand a page.\n\nReturns: The HTML string of the app shell webview's document (or of the root element). Without any options this is the complete document, which can be large.",
     {
       window_label: z.string().default("main").describe("The identifier (e.g., visible title or internal label) of the application window from which to retrieve the DOM content. Defaults to 'main' if not specified."),
+      root: z.string().optional().describe("CSS selector of the element to return instead of the whole document, e.g. '#sidebar'."),
+      max_depth: z.number().int().nonnegative().optional().describe("Element nesting depth below the root to include. Deeper children are replaced by a comment saying how many were omitted."),
+      strip: z.array(z.enum(["scripts", "styles", "svg", "attributes"])).optional().describe("Content to remove: 'scripts' (script/noscript), 'styles' (style elements, stylesheets and style attributes), 'svg' (SVG contents) and 'attributes' (all but id, class, name, type, role, aria-*, href, src, alt, title, placeholder, value, for and data-testid)."),
+      visible_only: z.boolean().optional().describe("Drop elements that are not rendered (display: none, visibility: hidden, or the hidden attribute)."),
+      max_bytes: z.number().int().positive().optional().describe("Maximum bytes of HTML per page (at least 1024). When the HTML is longer, the response ends with a cursor for the next page."),
+      cursor: z.string().optional().describe("The cursor from the previous page. Continues the same snapshot of the page; other filter options are ignored."),
     },
     {
       title: "Retrieve HTML DOM Content from Application Window",
@@ -17,14 +23,30 @@ export function registerGetDomTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ window_label }) => {
+    async ({ window_label, ...options }) => {
       try {
         logger.debug(`Getting DOM with params: ${JSON.stringify({
-          window_label
+          window_label,
+          ...options
         })}`);
         
-        // The server expects just a string, not an object
-        const result = await socketClient.sendCommand('get_dom', window_label);
+        // A bare label returns the full HTML string; any option returns a page object
+        const hasOptions = Object.values(options).some(value => value !== undefined);
+        const result = await socketClient.sendCommand('get_dom', hasOptions ? { window_label, ...options } : window_label);
+
+        if (hasOptions && result && typeof result === 'object' && typeof result.html === 'string') {
+          const trailer = result.nextCursor
+            ? `\n\n<!-- bytes ${result.offset}-${result.offset + Buffer.byteLength(result.html)} of ${result.totalBytes}; next page cursor: ${result.nextCursor} -->`
+            : '';
+          return {
+            content: [
+              {
+                type: "text",
+                text: result.html + trailer,
+              },
+            ],
+          };
+        }
         
         logger.debug(`Got DOM result type: ${typeof result}, length: ${
           typeof result === 'string' ? result.length : 'unknown'
@@ -2,6 +2,7 @@ import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
 import { z } from "zod";
 import { logger } from '../logger.js';
 import { socketClient } from "./client.js";
+import { elementSelectorShape } from "./selector.js";
 import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
 
 // Helper function to format element info text
@@ -14,12 +15,12 @@ ${clickInfo}`;
 export function registerGetElementPositionTool(server: McpServer) {
   server.tool(
     "get_element_position",
-    "Finds an HTML element in the Tauri APP SHELL webview by ID, class, tag name, or text content, and returns its absolute screen coordinates. Optionally clicks the element. Use this to locate app shell UI elements (toolbar buttons, sidebar links, settings controls) for subsequent mouse_movement or to click them directly.\n\nWhen to use this vs Playwright browser_click/browser_snapshot:\n- Use get_element_position for APP SHELL elements in the Tauri webview that Playwright cannot reach.\n- Use Playwright browser_snapshot + browser_click on localhost:8080 for PREVIEW CONTENT elements. Playwright provides ref-based element targeting which is more reliable than coordinate-based approaches.\n\nReturns: The element's tag, id, classes, and raw screen coordinates (x, y) for use with simulate_mouse_movement. If should_click=true, also reports whether the click succeeded.",
+    "Finds an HTML element in the Tauri APP SHELL webview by ID, class, tag name, text content, CSS, XPath, ARIA role, test id, or a ref from get_accessibility_snapshot (optionally inside iframes and shadow roots), and returns the coordinates of its center, measured from the webview's top-left corner in CSS pixels. Optionally clicks the element. Use this to locate app shell UI elements (toolbar buttons, sidebar links, settings controls) for subsequent mouse_movement or to click them directly.\n\nWhen to use this vs Playwright browser_click/browser_snapshot:\n- Use get_element_position for APP SHELL elements in the Tauri webview that Playwright cannot reach.\n- Use Playwright browser_snapshot + browser_click on localhost:8080 for PREVIEW CONTENT elements. Playwright provides ref-based element targeting which is more reliable than coordinate-based approaches.\n\nIf the selector matches several elements the call fails and lists the candidates; pass nth or a more specific selector.\n\nReturns: The element's tag, id, classes, and webview coordinates (x, y) for use with simulate_mouse_movement or pointer_action. If should_click=true, also reports whether the click succeeded.",
     {
-      selector_type: z.enum(["id", "class", "tag", "text"]).describe("The type of selector to use: 'id', 'class', 'tag', or 'text'."),
-      selector_value: z.string().describe("The value to search for based on the selector type."),
-      window_label: z.string().default("main").describe("The identifier of the application window to search in. Defaults to 'main' if not specified."),
+      ...elementSelectorShape,
+      window_label: z.string().optional().describe("The label of the webview to search in. Defaults to the plugin's default target (usually 'main')."),
       should_click: z.boolean().default(false).describe("Whether to click the element once found. Default is false."),
+      auto_wait_ms: z.number().int().min(0).max(120000).optional().describe("Retry for up to this many milliseconds until the element is visible, stable, enabled and (when clicking) not covered by another element. Without it the lookup fails right away if the element isn't there yet."),
     },
     {
       title: "Find Element and Get Position",
@@ -28,13 +29,13 @@ export function registerGetElementPositionTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ selector_type, selector_value, window_label, should_click }) => {
+    async ({ window_label, should_click, auto_wait_ms, ...selector }) => {
       try {
         const payload = {
-          selector_type,
-          selector_value,
+          ...selector,
           window_label,
-          should_click
+          should_click,
+          auto_wait_ms
         };
         
         logCommandParams('get_element_position', payload);
@@ -4,13 +4,21 @@ import { registerTakeScreenshotTool } from "./take_screenshot.js";
 import { registerCaptureScreenshotTool } from "./capture_screenshot.js";
 import { registerExecuteJsTool } from "./execute_js.js";
 import { registerGetDomTool } from "./get_dom.js";
+import { registerGetAccessibilitySnapshotTool } from "./get_accessibility_snapshot.js";
+import { registerGetConsoleLogsTool } from "./get_console_logs.js";
+import { registerClearNetworkRequestsTool, registerGetNetworkRequestsTool } from "./network_requests.js";
 import { registerIframeRpcTool } from "./iframe_rpc.js";
 import { registerManageWindowTool } from "./manage_window.js";
-import { registerManageLocalStorageTool } from "./manage_local_storage.js";
+import { registerListWindowsTool } from "./list_windows.js";
+import { registerManageStorageTool } from "./manage_storage.js";
 import { registerTextInputTool } from "./text_input.js";
+import { registerPressKeysTool } from "./press_keys.js";
 import { registerMouseMovementTool } from "./mouse_movement.js";
+import { registerPointerActionTool } from "./pointer_action.js";
 import { registerGetElementPositionTool } from "./get_element_position.js";
 import { registerSendTextToElementTool } from "./send_text_to_element.js";
+import { registerClickElementTool } from "./click_element.js";
+import { registerWaitForTool } from "./wait_for.js";
 import { socketClient } from "./client.js";
 
 // Re-export the socket client for direct use
@@ -22,13 +30,22 @@ export function registerAllTools(server: McpServer) {
   registerCaptureScreenshotTool(server);  // JS-based screenshot (no permissions/focus needed)
   registerExecuteJsTool(server);
   registerGetDomTool(server);
+  registerGetAccessibilitySnapshotTool(server);
+  registerGetConsoleLogsTool(server);
+  registerGetNetworkRequestsTool(server);
+  registerClearNetworkRequestsTool(server);
   registerIframeRpcTool(server);
   registerManageWindowTool(server);
-  registerManageLocalStorageTool(server);
+  registerListWindowsTool(server);
+  registerManageStorageTool(server);
   registerTextInputTool(server);
+  registerPressKeysTool(server);
   registerMouseMovementTool(server);
+  registerPointerActionTool(server);
   registerGetElementPositionTool(server);
   registerSendTextToElementTool(server);
+  registerClickElementTool(server);
+  registerWaitForTool(server);
 }
 
 // Function to initialize socket connection (can be awaited before registering tools)
@@ -0,0 +1,28 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse } from "./response-helpers.js";
+
+export function registerListWindowsTool(server: McpServer) {
+  server.tool(
+    "list_windows",
+    "Lists every native window of the Tauri app and the webviews each one hosts. Call this first to discover the labels that window_label parameters of the other tools accept, especially in multi-webview apps where one window contains several webviews.\n\nReturns: JSON with a `windows` array. Each window has its label, title, isVisible, isFocused, isMaximized, isFullscreen, position and size (outer frame in physical pixels), scaleFactor (null when the platform can't report one of these for that window), the monitor it is on, and a `webviews` array with each child webview's label, windowLabel (its parent), current url, and position/size relative to the window.",
+    {},
+    {
+      title: "List Application Windows and Webviews",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async () => {
+      try {
+        const result = await socketClient.sendCommand('list_windows', {});
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('List windows error:', error);
+        return createErrorResponse(`Failed to list windows: ${(error as Error).message}`);
+      }
+    },
+  );
+}
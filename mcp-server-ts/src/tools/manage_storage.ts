@@ -0,0 +1,47 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerManageStorageTool(server: McpServer) {
+  server.tool(
+    "manage_storage",
+    "Reads and modifies persisted state in the Tauri app shell webview: localStorage, sessionStorage, cookies and IndexedDB. This is unique to tauri-mcp -- Playwright cannot access the Tauri webview's storage directly (it can only access storage on localhost:8080 for preview content).\n\nUse this to inspect or modify user preferences, theme settings, session tokens, cached data or offline databases.\n\nStores and actions:\n - local_storage / session_storage (default local_storage): get (one key, or every item without a key), set, remove, clear, keys.\n - cookies: the same actions on document.cookie; 'cookie' sets path, domain, max_age, secure and same_site. get and keys also list the webview's native cookies for the current URL, including HttpOnly ones.\n - indexed_db: databases (names and versions), object_stores (key paths, indexes and record counts of 'database'), and get, keys, set, remove, clear on 'object_store' of 'database'. Values are parsed as JSON when possible; get and keys return at most 'limit' records (default 100).\n\nReturns: JSON with the value, items, keys or records read; writes return null or a short confirmation.",
+    {
+      store: z.enum(["local_storage", "session_storage", "cookies", "indexed_db"]).optional().describe("The store to operate on. Default 'local_storage'."),
+      action: z.enum(["get", "set", "remove", "clear", "keys", "databases", "object_stores"]).describe("Required. The operation to perform. 'databases' and 'object_stores' are only available for indexed_db."),
+      key: z.string().optional().describe("The item key, cookie name or IndexedDB record key. Required for 'set' (except IndexedDB stores with a keyPath) and 'remove'; optional for 'get'."),
+      value: z.string().optional().describe("The value to write. Required for 'set'. IndexedDB values are stored as parsed JSON when valid JSON."),
+      database: z.string().optional().describe("The IndexedDB database name. Required for every indexed_db action except 'databases'."),
+      object_store: z.string().optional().describe("The IndexedDB object store name. Required for indexed_db get, set, remove, clear and keys."),
+      cookie: z.object({
+        path: z.string().optional(),
+        domain: z.string().optional(),
+        max_age: z.number().int().optional(),
+        secure: z.boolean().optional(),
+        same_site: z.enum(["strict", "lax", "none"]).optional(),
+      }).optional().describe("Cookie attributes for set, remove and clear on the cookies store."),
+      limit: z.number().int().min(1).max(10000).optional().describe("Maximum IndexedDB records or keys to return. Default 100."),
+      window_label: z.string().optional().describe("The label of the webview whose storage is managed. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Manage Webview Storage, Cookies and IndexedDB",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('manage_storage', params);
+
+        const result = await socketClient.sendCommand('manage_storage', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Storage management error:', error);
+        return createErrorResponse(`Failed to manage storage: ${(error as Error).message}`);
+      }
+    },
+  );
+}
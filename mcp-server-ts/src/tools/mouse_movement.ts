@@ -6,13 +6,15 @@ import { socketClient } from "./client.js";
 export function registerMouseMovementTool(server: McpServer) {
   server.tool(
     "simulate_mouse_movement",
-    "Simulates OS-level mouse cursor movement to specified screen coordinates, with optional click. Moves the actual system cursor, which can trigger hover effects and focus changes in the Tauri app shell.\n\nWhen to use this vs Playwright browser_click:\n- Use simulate_mouse_movement for interacting with APP SHELL elements in the Tauri webview (toolbar buttons, sidebar items, settings controls). Coordinates are absolute screen pixels -- use get_element_position first to find the target.\n- Use Playwright browser_click on localhost:8080 for PREVIEW CONTENT interactions. Playwright targets elements by selector/ref, which is more reliable than coordinate-based clicking.\n\nReturns: A confirmation message describing the movement (and click if performed).",
+    "Simulates OS-level mouse cursor movement to a point in a Tauri webview, with optional click. Moves the actual system cursor, which can trigger hover effects and focus changes in the Tauri app shell.\n\nWhen to use this vs Playwright browser_click:\n- Use simulate_mouse_movement for interacting with APP SHELL elements in the Tauri webview (toolbar buttons, sidebar items, settings controls). Coordinates are measured from the webview's top-left corner, in CSS pixels by default -- use get_element_position first to find the target. Pass window_label to target a secondary window, dialog or child webview.\n- Use Playwright browser_click on localhost:8080 for PREVIEW CONTENT interactions. Playwright targets elements by selector/ref, which is more reliable than coordinate-based clicking.\n\nReturns: A confirmation message describing the movement (and click if performed).",
     {
-      x: z.number().int().describe("Required. The target X-coordinate for the mouse cursor, in screen pixels."),
-      y: z.number().int().describe("Required. The target Y-coordinate for the mouse cursor, in screen pixels."),
-      relative: z.boolean().optional().describe("If true, the x and y coordinates are treated as offsets relative to the mouse cursor's current position. If false (default), x and y are absolute screen coordinates."),
+      x: z.number().int().describe("Required. The target X-coordinate for the mouse cursor, from the webview's left edge."),
+      y: z.number().int().describe("Required. The target Y-coordinate for the mouse cursor, from the webview's top edge."),
+      unit: z.enum(["logical", "physical"]).optional().describe("Unit of x and y: 'logical' CSS pixels, as get_element_position reports them (default), or 'physical' device pixels."),
+      relative: z.boolean().optional().describe("If true, the x and y coordinates are treated as offsets in screen pixels from the mouse cursor's current position. If false (default), x and y are webview coordinates."),
       click: z.boolean().optional().describe("If true, performs a mouse click at the target coordinates after movement. Default is false."),
       button: z.enum(["left", "right", "middle"]).optional().describe("Specifies which mouse button to click. Options are 'left', 'right', or 'middle'. Default is 'left'."),
+      window_label: z.string().optional().describe("The label of the window or webview the coordinates are relative to. Defaults to the plugin's default target (usually 'main')."),
     },
     {
       title: "Simulate Mouse Cursor Movement",
@@ -21,7 +23,7 @@ export function registerMouseMovementTool(server: McpServer) {
       idempotentHint: false,
       openWorldHint: false,
     },
-    async ({ x, y, relative, click, button }) => {
+    async ({ x, y, unit, relative, click, button, window_label }) => {
       try {
         // X and Y are required by the Zod schema, but let's validate they're numbers
         if (typeof x !== 'number' || typeof y !== 'number') {
@@ -41,7 +43,9 @@ export function registerMouseMovementTool(server: McpServer) {
           y,
           relative,
           click,
-          button
+          button,
+          unit,
+          window_label
         })}`);
         
         await socketClient.sendCommand('simulate_mouse_movement', {
@@ -49,7 +53,9 @@ export function registerMouseMovementTool(server: McpServer) {
           y,
           relative,
           click,
-          button
+          button,
+          unit,
+          window_label
         });
         
         const actionText = click 
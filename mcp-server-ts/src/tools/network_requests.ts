@@ -0,0 +1,65 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerGetNetworkRequestsTool(server: McpServer) {
+  server.tool(
+    "get_network_requests",
+    "Lists the network requests the Tauri app shell webview has made: fetch and XMLHttpRequest calls plus Tauri `invoke` commands to the Rust backend. Requests are recorded from page load (the most recent 500 per webview). Invokes that fall back to the postMessage IPC (Android, or when a CSP blocks the ipc: protocol) are not recorded.\n\nUse this to see what the frontend sent to the backend and what came back: perform a UI action, then call this with `since` set to the time before the action, or with `failed_only: true` to find errors.\n\nReturns: JSON `{ requests, buffered, dropped }`. Requests are oldest first, each with id, kind (fetch, xhr or invoke), method (INVOKE for invokes), url (the command name for invokes), requestBody, status, ok, responseBody (bodies truncated to 2000 characters), startTime (epoch ms), durationMs and error. `ok` and `status` are null while a request is still pending.",
+    {
+      window_label: z.string().optional().describe("The label of the webview whose requests are listed. Defaults to the plugin's default target (usually 'main')."),
+      kinds: z.array(z.enum(["fetch", "xhr", "invoke"])).optional().describe("Only return these kinds of request. Returns every kind when omitted."),
+      method: z.string().optional().describe("Only return requests with this HTTP method, e.g. 'POST' (case-insensitive). Invokes use 'INVOKE'."),
+      url_pattern: z.string().optional().describe("Only return requests whose URL, or command name for invokes, contains this text (case-insensitive)."),
+      failed_only: z.boolean().optional().describe("Only return requests that failed: network errors, rejected invokes and non-2xx responses."),
+      since: z.number().int().nonnegative().optional().describe("Only return requests started at or after this time, in milliseconds since the Unix epoch."),
+      limit: z.number().int().positive().optional().describe("Return at most this many requests, keeping the most recent."),
+    },
+    {
+      title: "Inspect Network Requests and Tauri Invokes from Application Window",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('get_network_requests', params);
+
+        const result = await socketClient.sendCommand('get_network_requests', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Get network requests error:', error);
+        return createErrorResponse(`Failed to get network requests: ${(error as Error).message}`);
+      }
+    },
+  );
+}
+
+export function registerClearNetworkRequestsTool(server: McpServer) {
+  server.tool(
+    "clear_network_requests",
+    "Empties the recorded network requests of a Tauri webview, so the next get_network_requests call only shows requests made afterwards. Use it right before reproducing a problem to isolate the backend calls one UI action makes.\n\nReturns: JSON `{ cleared }` with the number of records removed.",
+    {
+      window_label: z.string().optional().describe("The label of the webview whose records are cleared. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Clear Recorded Network Requests in Application Window",
+      readOnlyHint: false,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async ({ window_label }) => {
+      try {
+        const result = await socketClient.sendCommand('clear_network_requests', { window_label });
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Clear network requests error:', error);
+        return createErrorResponse(`Failed to clear network requests: ${(error as Error).message}`);
+      }
+    },
+  );
+}
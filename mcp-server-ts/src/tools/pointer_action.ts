@@ -0,0 +1,48 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+import { elementSelectorShape } from "./selector.js";
+
+export function registerPointerActionTool(server: McpServer) {
+  server.tool(
+    "pointer_action",
+    "Performs a pointer action with the real system mouse in the Tauri APP SHELL: move, hover, click, double_click, triple_click, down, up, drag or scroll. Each target is either webview coordinates (x and y from the webview's top-left corner, in CSS pixels or device pixels with unit='physical') or an element selector; element targets are scrolled into view and the pointer goes to their center.\n\nActions: 'hover' rests on the target for hold_ms (default 300) so hover menus and tooltips open. 'click' with hold_ms is a press-and-hold. 'down' and 'up' press and release the button as separate steps, at the current pointer position when no target is given. 'drag' presses at the target, moves to to_x/to_y or to_selector in `steps` intermediate moves, then releases. 'scroll' turns the wheel by delta_y (positive down) and delta_x (positive right) notches, over the target if one is given.\n\nThe target window is focused first. Use press_keys for keyboard input and get_element_position to inspect an element without moving the pointer.\n\nReturns: JSON with the action, the final pointer position in screen pixels, the duration, and the element(s) targeted.",
+    {
+      action: z.enum(["move", "hover", "click", "double_click", "triple_click", "down", "up", "drag", "scroll"]).describe("What to do with the pointer."),
+      x: z.number().optional().describe("Target x from the webview's left edge. Give x and y, or selector."),
+      y: z.number().optional().describe("Target y from the webview's top edge."),
+      selector: z.object(elementSelectorShape).optional().describe("Element whose center is the target."),
+      to_x: z.number().optional().describe("End x of a drag. Give to_x and to_y, or to_selector."),
+      to_y: z.number().optional().describe("End y of a drag."),
+      to_selector: z.object(elementSelectorShape).optional().describe("Element whose center is the end of a drag."),
+      unit: z.enum(["logical", "physical"]).optional().describe("Unit of x, y, to_x and to_y: 'logical' CSS pixels (default) or 'physical' device pixels."),
+      button: z.enum(["left", "right", "middle"]).optional().describe("Mouse button to use. Default 'left'."),
+      steps: z.number().int().min(1).max(500).optional().describe("Number of intermediate moves of a drag. Default 10."),
+      delta_x: z.number().int().optional().describe("Horizontal scroll in wheel notches, positive to the right (scroll only)."),
+      delta_y: z.number().int().optional().describe("Vertical scroll in wheel notches, positive down (scroll only)."),
+      hold_ms: z.number().int().min(0).optional().describe("How long to hold the button for click and before moving in drag (default 100), or to rest for hover (default 300), in milliseconds."),
+      auto_wait_ms: z.number().int().min(0).max(120000).optional().describe("Wait up to this many milliseconds for selector targets to be visible, stable and enabled."),
+      window_label: z.string().optional().describe("The label of the window or webview the coordinates and selectors refer to. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Pointer Action",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('pointer_action', params);
+
+        const result = await socketClient.sendCommand('pointer_action', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Pointer action error:', error);
+        return createErrorResponse(`Failed to perform pointer action: ${(error as Error).message}`);
+      }
+    },
+  );
+}
@@ -0,0 +1,39 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+import { elementSelectorShape } from "./selector.js";
+
+export function registerPressKeysTool(server: McpServer) {
+  server.tool(
+    "press_keys",
+    "Presses keys and keyboard shortcuts in the Tauri APP SHELL: Enter, Escape, Tab, arrow keys, or chords like 'Mod+S' and 'Ctrl+Shift+P'. Takes a sequence of chords pressed one after another; each chord's modifiers go down in order, the key is held briefly, then everything is released in reverse.\n\nChord syntax: modifiers joined with '+' before the key. Modifiers: Ctrl, Shift, Alt (Option), Meta (Cmd/Win/Super) and Mod (Cmd on macOS, Ctrl elsewhere; prefer it for app shortcuts). Keys: single characters, Enter, Escape, Tab, Backspace, Delete, Space, ArrowUp/ArrowDown/ArrowLeft/ArrowRight, Home, End, PageUp, PageDown, F1-F12. Use 'Ctrl++' or 'Ctrl+Plus' for the plus key.\n\nBy default keys are pressed at the OS level (the target window is focused first). With dom=true, KeyboardEvents are dispatched straight to an element (the focused one, or `selector`) without OS focus; app key handlers see them, but the browser performs no default action such as inserting text.\n\nUse simulate_text_input or send_text_to_element to type text; use press_keys for everything that isn't plain text.\n\nReturns: JSON with the chords pressed, the mode, the duration, and for dom=true the target element and which keydowns were cancelled by the page.",
+    {
+      keys: z.array(z.string()).min(1).describe("Chords to press in order, e.g. [\"Mod+Shift+P\", \"Enter\"] or [\"ArrowDown\", \"ArrowDown\", \"Escape\"]."),
+      hold_ms: z.number().int().min(0).optional().describe("How long each chord is held down, in milliseconds. Default 30."),
+      delay_ms: z.number().int().min(0).optional().describe("Pause between chords, in milliseconds. Default 50."),
+      dom: z.boolean().optional().describe("Dispatch DOM KeyboardEvents instead of OS key presses. Works without window focus but triggers no default browser actions."),
+      selector: z.object(elementSelectorShape).optional().describe("Element that receives the DOM events (only with dom=true). Defaults to the focused element."),
+      window_label: z.string().optional().describe("The label of the window (or webview, with dom=true) that receives the keys. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Press Keys and Keyboard Shortcuts",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('press_keys', params);
+
+        const result = await socketClient.sendCommand('press_keys', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Key press error:', error);
+        return createErrorResponse(`Failed to press keys: ${(error as Error).message}`);
+      }
+    },
+  );
+}
@@ -0,0 +1,25 @@
+import { z } from "zod";
+
+const SELECTOR_TYPES = ["id", "class", "tag", "text", "css", "xpath", "role", "test_id", "ref"] as const;
+
+// Selector types are case-insensitive, as in the plugin: "ID", "XPath" and "testId" are accepted
+function normalizeSelectorType(value: unknown): unknown {
+  if (typeof value !== "string") {
+    return value;
+  }
+  const name = value.toLowerCase().replace(/[_-]/g, "");
+  return name === "testid" ? "test_id" : name;
+}
+
+/**
+ * Selector fields shared by every tool that targets an element. The plugin validates them
+ * and resolves them with the webview's selector engine.
+ */
+export const elementSelectorShape = {
+  selector_type: z.preprocess(normalizeSelectorType, z.enum(SELECTOR_TYPES)).describe("How selector_value is matched: 'id', 'class', 'tag', 'text' (visible text, placeholder, title or aria-label), 'css', 'xpath', 'role' (ARIA role, narrowed with name), 'test_id' (data-testid) or 'ref' (an element ref from get_accessibility_snapshot, e.g. 'e12')."),
+  selector_value: z.string().describe("The value to search for based on the selector type."),
+  name: z.string().optional().describe("Accessible name the element must have. Only for the 'role' selector type."),
+  exact: z.boolean().optional().describe("Match text and accessible names exactly instead of as case-insensitive substrings. Only for the 'text' and 'role' selector types."),
+  nth: z.number().int().min(0).optional().describe("Zero-based index of the match to use when the selector matches several elements. Without it an ambiguous selector fails and lists the candidates."),
+  within: z.array(z.string()).optional().describe("CSS selectors of iframes, shadow hosts or containers to search inside, outermost first. Each must match exactly one element."),
+};
@@ -2,17 +2,18 @@ import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
 import { z } from "zod";
 import { logger } from '../logger.js';
 import { socketClient } from "./client.js";
+import { elementSelectorShape } from "./selector.js";
 
 export function registerSendTextToElementTool(server: McpServer) {
   server.tool(
     "send_text_to_element",
-    "Finds an HTML element in the Tauri APP SHELL webview by selector and types text into it character-by-character. Works with input, textarea, and contentEditable elements. Unlike simulate_text_input, this tool handles element targeting -- you do not need to focus the element first.\n\nWhen to use this vs Playwright browser_type:\n- Use send_text_to_element for typing into APP SHELL elements (toolbar search boxes, settings fields) inside the Tauri webview.\n- Use Playwright browser_type on localhost:8080 for typing into PREVIEW CONTENT elements. Playwright handles React state updates properly, whereas this tool may only update the DOM without triggering React's synthetic event system.\n\nLimitation: In React-based UIs, this tool updates the visible DOM text but may NOT trigger React state updates. The visual change appears but the application state might not reflect it. For React inputs in the app shell, consider using execute_js to dispatch proper React-compatible input events.\n\nReturns: A confirmation with the target element's tag and id, plus the text that was sent.",
+    "Finds an HTML element in the Tauri APP SHELL webview by selector (or a ref from get_accessibility_snapshot) and types text into it character-by-character. Works with input, textarea, and contentEditable elements. Unlike simulate_text_input, this tool handles element targeting -- you do not need to focus the element first.\n\nWhen to use this vs Playwright browser_type:\n- Use send_text_to_element for typing into APP SHELL elements (toolbar search boxes, settings fields) inside the Tauri webview.\n- Use Playwright browser_type on localhost:8080 for typing into PREVIEW CONTENT elements. Playwright handles React state updates properly, whereas this tool may only update the DOM without triggering React's synthetic event system.\n\nLimitation: In React-based UIs, this tool updates the visible DOM text but may NOT trigger React state updates. The visual change appears but the application state might not reflect it. For React inputs in the app shell, consider using execute_js to dispatch proper React-compatible input events.\n\nReturns: A confirmation with the target element's tag and id, plus the text that was sent.",
     {
-      selector_type: z.enum(["id", "class", "tag", "text"]).describe("The type of selector to use: 'id', 'class', 'tag', or 'text'."),
-      selector_value: z.string().describe("The value to search for based on the selector type."),
+      ...elementSelectorShape,
       text: z.string().describe("The text to input into the element."),
-      window_label: z.string().default("main").describe("The identifier of the application window to search in. Defaults to 'main' if not specified."),
+      window_label: z.string().optional().describe("The label of the webview to search in. Defaults to the plugin's default target (usually 'main')."),
       delay_ms: z.number().default(20).describe("The delay between keystrokes in milliseconds (for realistic typing simulation). Default is 20ms."),
+      auto_wait_ms: z.number().int().min(0).max(120000).optional().describe("Retry for up to this many milliseconds until the element is visible, stable, enabled and editable. Without it the lookup fails right away if the element isn't there yet."),
     },
     {
       title: "Send Text to Element",
@@ -21,23 +22,23 @@ export function registerSendTextToElementTool(server: McpServer) {
       idempotentHint: false, // Text input can have side effects
       openWorldHint: false,
     },
-    async ({ selector_type, selector_value, text, window_label, delay_ms }) => {
+    async ({ text, window_label, delay_ms, auto_wait_ms, ...selector }) => {
       try {
         logger.debug(`Sending text to element with params: ${JSON.stringify({
-          selector_type,
-          selector_value,
+          ...selector,
           text,
           window_label,
-          delay_ms
+          delay_ms,
+          auto_wait_ms
         })}`);
         
         // Create the payload object
         const payload = {
-          selector_type,
-          selector_value,
+          ...selector,
           text,
           window_label,
-          delay_ms
+          delay_ms,
+          auto_wait_ms
         };
         
         const result = await socketClient.sendCommand('send_text_to_element', payload);
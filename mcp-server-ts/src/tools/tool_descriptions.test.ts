@@ -45,18 +45,27 @@ const allToolNames = [
   'capture_screenshot',
   'execute_js',
   'get_dom',
+  'get_accessibility_snapshot',
+  'get_console_logs',
+  'get_network_requests',
+  'clear_network_requests',
   'iframe_rpc',
   'manage_window',
-  'manage_local_storage',
+  'list_windows',
+  'manage_storage',
   'simulate_text_input',
+  'press_keys',
   'simulate_mouse_movement',
+  'pointer_action',
   'get_element_position',
   'send_text_to_element',
+  'click_element',
+  'wait_for',
 ];
 
 describe('tool registration completeness', () => {
-  it('should register all 11 tools', () => {
-    expect(registeredTools).toHaveLength(11);
+  it('should register all 20 tools', () => {
+    expect(registeredTools).toHaveLength(20);
   });
 
   it.each(allToolNames)('should register %s', (toolName) => {
@@ -99,7 +108,7 @@ describe('tool description quality', () => {
 
 describe('tool metadata', () => {
   it('read-only tools should be marked correctly', () => {
-    const readOnlyTools = ['get_dom', 'capture_screenshot', 'take_screenshot'];
+    const readOnlyTools = ['get_dom', 'capture_screenshot', 'take_screenshot', 'list_windows', 'get_network_requests', 'get_accessibility_snapshot', 'wait_for'];
     for (const toolName of readOnlyTools) {
       const tool = registeredTools.find(t => t.name === toolName);
       expect(tool, `${toolName} should be registered`).toBeDefined();
@@ -108,7 +117,7 @@ describe('tool metadata', () => {
   });
 
   it('mutating tools should not be marked read-only', () => {
-    const mutatingTools = ['execute_js', 'simulate_text_input', 'simulate_mouse_movement', 'send_text_to_element'];
+    const mutatingTools = ['execute_js', 'simulate_text_input', 'press_keys', 'simulate_mouse_movement', 'pointer_action', 'send_text_to_element', 'click_element'];
     for (const toolName of mutatingTools) {
       const tool = registeredTools.find(t => t.name === toolName);
       expect(tool, `${toolName} should be registered`).toBeDefined();
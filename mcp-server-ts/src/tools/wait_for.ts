@@ -0,0 +1,43 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { logger } from '../logger.js';
+import { socketClient } from "./client.js";
+import { createErrorResponse, createSuccessResponse, logCommandParams } from "./response-helpers.js";
+import { elementSelectorShape } from "./selector.js";
+
+export function registerWaitForTool(server: McpServer) {
+  server.tool(
+    "wait_for",
+    "Waits inside the Tauri APP SHELL webview until a condition holds, polling instead of failing right away. Use it after an action that triggers rendering, navigation or loading, before the next get_element_position, send_text_to_element or snapshot.\n\nConditions:\n- 'visible' / 'hidden' / 'enabled': the element described by `selector` (same fields as get_element_position) is rendered, gone or hidden, or visible and not disabled.\n- 'text': `text` appears on the page, or inside `selector` when given.\n- 'url': the page URL contains `url` (or matches it as a regular expression with url_regex).\n- 'predicate': the JavaScript expression or function body in `predicate` returns a truthy value (Promises are awaited).\n- 'network_idle': no fetch, XHR or invoke call has been in flight for idle_ms.\n\nElement commands can also wait on their own: pass auto_wait_ms to get_element_position or send_text_to_element.\n\nReturns: JSON `{ condition, elapsedMs, value }` once the condition holds. On timeout the error explains what was last observed (e.g. 'element is disabled', '2 request(s) in flight').",
+    {
+      condition: z.enum(["visible", "hidden", "enabled", "text", "url", "predicate", "network_idle"]).describe("What to wait for."),
+      selector: z.object(elementSelectorShape).optional().describe("The element to watch. Required for 'visible', 'hidden' and 'enabled'; scopes 'text' to the element."),
+      text: z.string().optional().describe("Text to wait for, for the 'text' condition (case-insensitive substring)."),
+      url: z.string().optional().describe("URL fragment to wait for, for the 'url' condition."),
+      url_regex: z.boolean().optional().describe("Treat url as a JavaScript regular expression."),
+      predicate: z.string().optional().describe("JavaScript expression or function body for the 'predicate' condition, e.g. \"document.querySelectorAll('.row').length >= 10\"."),
+      idle_ms: z.number().int().min(0).optional().describe("How long the network must stay quiet for 'network_idle'. Default 500."),
+      timeout_ms: z.number().int().min(0).max(120000).optional().describe("Give up after this many milliseconds. Default 5000, at most 120000."),
+      poll_interval_ms: z.number().int().min(0).optional().describe("How often the condition is checked, in milliseconds. Default 100."),
+      window_label: z.string().optional().describe("The label of the webview to watch. Defaults to the plugin's default target (usually 'main')."),
+    },
+    {
+      title: "Wait for Condition in Application Window",
+      readOnlyHint: true,
+      destructiveHint: false,
+      idempotentHint: true,
+      openWorldHint: false,
+    },
+    async (params) => {
+      try {
+        logCommandParams('wait_for', params);
+
+        const result = await socketClient.sendCommand('wait_for', params);
+        return createSuccessResponse(JSON.stringify(result, null, 2));
+      } catch (error) {
+        logger.error('Wait error:', error);
+        return createErrorResponse(`Wait failed: ${(error as Error).message}`);
+      }
+    },
+  );
+}
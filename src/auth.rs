@@ -0,0 +1,274 @@
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufRead, AsyncWrite, AsyncWriteExt};
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::{SocketResponse, read_line};
+use crate::PluginConfig;
+
+/// Longest handshake line accepted before the connection is dropped
+pub const MAX_HANDSHAKE_BYTES: usize = 4096;
+/// How long a new connection may take to send its handshake line before it is dropped
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shared-secret and peer-address checks for the TCP transports
+#[derive(Clone, Debug, Default)]
+pub struct TcpAuth {
+    token: Option<String>,
+    allowed_peers: Vec<IpAddr>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthRequest {
+    #[serde(default)]
+    id: Option<Value>,
+    command: String,
+    #[serde(default)]
+    payload: Value,
+}
+
+impl TcpAuth {
+    /// Resolve the token from the configuration, generating one when only a token file is
+    /// configured. The token is written to the token file (0600 on Unix) when one is set.
+    pub fn from_config(config: &PluginConfig) -> crate::Result<Self> {
+        let token = match (&config.auth_token, &config.auth_token_file) {
+            (Some(token), _) => Some(token.clone()),
+            (None, Some(_)) => Some(generate_token()?),
+            (None, None) => None,
+        };
+
+        if let (Some(token), Some(path)) = (&token, &config.auth_token_file) {
+            write_token_file(path, token)?;
+            info!("[TAURI_MCP] Wrote TCP auth token to {}", path.display());
+        }
+
+        Ok(TcpAuth {
+            token,
+            allowed_peers: config.allowed_peers.clone(),
+        })
+    }
+
+    pub fn token_required(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// An empty allowlist accepts any peer
+    pub fn peer_allowed(&self, addr: &SocketAddr) -> bool {
+        let ip = addr.ip().to_canonical();
+        self.allowed_peers.is_empty() || self.allowed_peers.iter().any(|allowed| allowed.to_canonical() == ip)
+    }
+
+    pub fn check_token(&self, presented: &str) -> bool {
+        match &self.token {
+            Some(token) => constant_time_eq(token.as_bytes(), presented.as_bytes()),
+            None => true,
+        }
+    }
+
+    /// Check an HTTP `Authorization: Bearer <token>` header
+    pub fn check_bearer(&self, header: Option<&str>) -> bool {
+        if !self.token_required() {
+            return true;
+        }
+        header
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|presented| self.check_token(presented.trim()))
+    }
+
+    /// Run the auth handshake on a fresh connection.
+    ///
+    /// The first line must be `{"command": "auth", "payload": {"token": "..."}}`. Any other
+    /// request, or a wrong token, gets an error response and `Ok(false)`; the caller then
+    /// closes the connection, as it does when no line arrives within `HANDSHAKE_TIMEOUT`.
+    /// Only that line is consumed from `reader`, and it is never logged.
+    pub async fn handshake<B, W>(&self, reader: &mut B, writer: &mut W) -> std::io::Result<bool>
+    where
+        B: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        if !self.token_required() {
+            return Ok(true);
+        }
+
+        let Some(line) = read_handshake(reader).await else {
+            return Ok(false);
+        };
+
+        let (authenticated, response) = self.check_handshake(&line);
+        let json = serde_json::to_string(&response).map_err(std::io::Error::other)? + "\n";
+        writer.write_all(json.as_bytes()).await?;
+        writer.flush().await?;
+
+        Ok(authenticated)
+    }
+
+    /// Check the first line of a connection, returning whether it authenticated and the
+    /// response to send. Used by `handshake` and by the socket server, which reads
+    /// the line itself; the line is never logged.
+    pub fn check_handshake(&self, line: &str) -> (bool, SocketResponse) {
+        let request = serde_json::from_str::<AuthRequest>(line).ok();
+        let id = request.as_ref().and_then(|r| r.id.clone());
+        let authenticated = request.as_ref().is_some_and(|r| {
+            r.command == commands::AUTH
+                && r.payload
+                    .get("token")
+                    .and_then(Value::as_str)
+                    .is_some_and(|token| self.check_token(token))
+        });
+
+        let response = if authenticated {
+            SocketResponse {
+                success: true,
+                data: Some(json!({ "authenticated": true })),
+                error: None,
+                id,
+            }
+        } else {
+            let error = match &request {
+                Some(r) if r.command == commands::AUTH => "Authentication failed: invalid token".to_string(),
+                _ => "Authentication required: send the auth command first".to_string(),
+            };
+            warn!("[TAURI_MCP] Rejected connection: {}", error);
+            SocketResponse {
+                success: false,
+                data: None,
+                error: Some(ErrorInfo::new(ErrorCode::Unauthorized, error)),
+                id,
+            }
+        };
+        (authenticated, response)
+    }
+}
+
+/// Generate a random 256-bit token, hex encoded
+pub fn generate_token() -> crate::Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| Error::PluginInit(format!("Failed to generate auth token: {}", e)))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn write_token_file(path: &Path, token: &str) -> crate::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| Error::Io(format!("Failed to write auth token file {}: {}", path.display(), e)))?;
+
+    // The mode only applies to newly created files, so tighten an existing one too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(token.as_bytes())?;
+    Ok(())
+}
+
+/// Read a connection's handshake line. `None` when the client closed the connection, sent
+/// more than `MAX_HANDSHAKE_BYTES` or sent nothing within `HANDSHAKE_TIMEOUT`.
+pub async fn read_handshake<B: AsyncBufRead + Unpin>(reader: &mut B) -> Option<String> {
+    read_handshake_within(reader, HANDSHAKE_TIMEOUT).await
+}
+
+async fn read_handshake_within<B: AsyncBufRead + Unpin>(reader: &mut B, timeout: Duration) -> Option<String> {
+    match tokio::time::timeout(timeout, read_line(reader, MAX_HANDSHAKE_BYTES)).await {
+        Ok(line) => line.ok().flatten(),
+        Err(_) => {
+            warn!("[TAURI_MCP] Rejected connection: no auth handshake within {:?}", timeout);
+            None
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth() -> TcpAuth {
+        TcpAuth {
+            token: Some("secret".to_string()),
+            allowed_peers: vec!["127.0.0.1".parse().unwrap()],
+        }
+    }
+
+    fn handshake(input: &str) -> (bool, String, Vec<u8>) {
+        let mut reader = input.as_bytes();
+        let mut output = Vec::new();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let ok = runtime.block_on(auth().handshake(&mut reader, &mut output)).unwrap();
+        (ok, String::from_utf8(output).unwrap(), reader.to_vec())
+    }
+
+    #[test]
+    fn test_handshake_accepts_token_and_leaves_following_requests() {
+        let (ok, reply, rest) = handshake(
+            "{\"id\":1,\"command\":\"auth\",\"payload\":{\"token\":\"secret\"}}\n{\"command\":\"ping\",\"payload\":{}}\n",
+        );
+        assert!(ok);
+        assert!(reply.contains("\"success\":true"));
+        assert!(reply.contains("\"id\":1"));
+        assert_eq!(rest, b"{\"command\":\"ping\",\"payload\":{}}\n");
+    }
+
+    #[test]
+    fn test_handshake_rejects_bad_token_and_other_commands() {
+        let (ok, reply, _) = handshake("{\"command\":\"auth\",\"payload\":{\"token\":\"wrong\"}}\n");
+        assert!(!ok);
+        assert!(reply.contains("invalid token"));
+
+        let (ok, reply, _) = handshake("{\"command\":\"execute_js\",\"payload\":{\"code\":\"1\"}}\n");
+        assert!(!ok);
+        assert!(reply.contains("Authentication required"));
+    }
+
+    #[test]
+    fn test_handshake_gives_up_on_silent_clients() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        // The client end stays open but never writes
+        let (_client, server) = tokio::io::duplex(64);
+        let mut reader = tokio::io::BufReader::new(server);
+
+        let line = runtime.block_on(read_handshake_within(&mut reader, Duration::from_millis(10)));
+        assert!(line.is_none());
+    }
+
+    #[test]
+    fn test_peer_allowlist_and_bearer() {
+        let auth = auth();
+        assert!(auth.peer_allowed(&"127.0.0.1:5000".parse().unwrap()));
+        assert!(auth.peer_allowed(&"[::ffff:127.0.0.1]:5000".parse().unwrap()));
+        assert!(!auth.peer_allowed(&"10.0.0.2:5000".parse().unwrap()));
+        assert!(TcpAuth::default().peer_allowed(&"10.0.0.2:5000".parse().unwrap()));
+
+        assert!(auth.check_bearer(Some("Bearer secret")));
+        assert!(!auth.check_bearer(Some("Bearer nope")));
+        assert!(!auth.check_bearer(None));
+    }
+
+    #[test]
+    fn test_generated_tokens_differ() {
+        let first = generate_token().unwrap();
+        assert_eq!(first.len(), 64);
+        assert_ne!(first, generate_token().unwrap());
+    }
+}
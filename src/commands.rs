@@ -1 +1,212 @@
+// Tauri commands for driving the plugin from the app's own frontend over IPC.
+// Each one reuses the socket handlers (or the `TauriMcp` methods behind them), so behaviour
+// matches the socket protocol. Access is governed by the plugin's capability permissions
+// (`mcp:allow-eval-js`, ...) rather than the socket `CommandPolicy`.
 
+use serde_json::{Value, json};
+use tauri::{AppHandle, Runtime, command};
+
+use crate::desktop::{resolve_webview, resolve_window};
+use crate::error::{Error, Result};
+use crate::models::*;
+use crate::selector::ElementSelector;
+use crate::socket_server::SocketResponse;
+use crate::tools;
+use crate::TauriMcpExt;
+
+#[command]
+pub(crate) async fn ping<R: Runtime>(app: AppHandle<R>, payload: PingRequest) -> Result<PingResponse> {
+    app.tauri_mcp().ping(payload)
+}
+
+#[command]
+pub(crate) async fn take_screenshot<R: Runtime>(
+    app: AppHandle<R>,
+    payload: ScreenshotRequest,
+) -> Result<ScreenshotResponse> {
+    app.tauri_mcp().take_screenshot_async(payload).await
+}
+
+#[command]
+pub(crate) async fn control_window<R: Runtime>(
+    app: AppHandle<R>,
+    payload: WindowControlRequest,
+) -> Result<WindowControlResponse> {
+    app.tauri_mcp().control_window(payload)
+}
+
+#[command]
+pub(crate) async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<WindowListResponse> {
+    app.tauri_mcp().list_windows()
+}
+
+#[command]
+pub(crate) async fn eval_js<R: Runtime>(app: AppHandle<R>, payload: EvalJsRequest) -> Result<EvalJsResponse> {
+    let response = tools::handle_execute_js(
+        &app,
+        json!({ "window_label": payload.window_label, "code": payload.script }),
+    )
+    .await?;
+
+    Ok(match into_data(response) {
+        Ok(data) => EvalJsResponse {
+            result: result_text(&data),
+            success: true,
+            error: None,
+        },
+        Err(error) => EvalJsResponse {
+            result: None,
+            success: false,
+            error: Some(error),
+        },
+    })
+}
+
+#[command]
+pub(crate) async fn get_html<R: Runtime>(app: AppHandle<R>, payload: WebviewInfoRequest) -> Result<HtmlResponse> {
+    let response = tools::handle_get_dom(&app, json!({ "window_label": payload.window_label })).await?;
+
+    Ok(match into_data(response) {
+        Ok(data) => HtmlResponse {
+            html: data.as_str().map(str::to_string),
+            success: true,
+            error: None,
+        },
+        Err(error) => HtmlResponse {
+            html: None,
+            success: false,
+            error: Some(error),
+        },
+    })
+}
+
+#[command]
+pub(crate) async fn get_url<R: Runtime>(app: AppHandle<R>, payload: WebviewInfoRequest) -> Result<UrlResponse> {
+    let (_, webview) = resolve_webview(&app, &payload.window_label)?;
+
+    Ok(match webview.url() {
+        Ok(url) => UrlResponse {
+            url: Some(url.to_string()),
+            success: true,
+            error: None,
+        },
+        Err(e) => UrlResponse {
+            url: None,
+            success: false,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[command]
+pub(crate) async fn get_title<R: Runtime>(app: AppHandle<R>, payload: WebviewInfoRequest) -> Result<TitleResponse> {
+    let window = resolve_window(&app, &payload.window_label)?;
+
+    Ok(match window.title() {
+        Ok(title) => TitleResponse {
+            title: Some(title),
+            success: true,
+            error: None,
+        },
+        Err(e) => TitleResponse {
+            title: None,
+            success: false,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[command]
+pub(crate) async fn get_element_text<R: Runtime>(app: AppHandle<R>, payload: ElementRequest) -> Result<ElementResponse> {
+    let script = element_script(&payload.selector, "return el.innerText ?? el.textContent ?? '';");
+    run_element_script(&app, payload.window_label, script).await
+}
+
+#[command]
+pub(crate) async fn set_element_value<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetElementValueRequest,
+) -> Result<ElementResponse> {
+    let value = serde_json::to_string(&payload.value)
+        .map_err(|e| Error::Anyhow(format!("Failed to encode value: {}", e)))?;
+    // Go through the prototype's setter so frameworks that track the value (React) see the change
+    let body = format!(
+        "const setter = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(el), 'value')?.set;
+         if (setter) {{ setter.call(el, {value}); }} else {{ el.value = {value}; }}
+         el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+         el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+         return el.value;"
+    );
+    let script = element_script(&payload.selector, &body);
+    run_element_script(&app, payload.window_label, script).await
+}
+
+#[command]
+pub(crate) async fn click_element<R: Runtime>(app: AppHandle<R>, payload: ElementRequest) -> Result<ElementResponse> {
+    let script = element_script(
+        &payload.selector,
+        "el.scrollIntoView({ block: 'center', inline: 'center' }); el.click(); return el.tagName.toLowerCase();",
+    );
+    run_element_script(&app, payload.window_label, script).await
+}
+
+#[command]
+pub(crate) async fn type_text<R: Runtime>(app: AppHandle<R>, payload: TypeTextRequest) -> Result<TextInputResponse> {
+    // Keystrokes go to the focused window, so bring the target forward first
+    resolve_window(&app, &payload.window_label)?.set_focus()?;
+
+    app.tauri_mcp()
+        .simulate_text_input_async(TextInputRequest {
+            text: payload.text,
+            delay_ms: None,
+            initial_delay_ms: None,
+        })
+        .await
+}
+
+// Split a socket handler response into its data or error message
+fn into_data(response: SocketResponse) -> std::result::Result<Value, String> {
+    if response.success {
+        Ok(response.data.unwrap_or(Value::Null))
+    } else {
+        Err(response.error.map_or_else(|| "Unknown error".to_string(), |error| error.message))
+    }
+}
+
+// The execute_js result as text: strings as-is, other values as JSON
+fn result_text(data: &Value) -> Option<String> {
+    match data.get("result")? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+// Wrap `body` so it runs with `el` bound to the element the CSS `selector` resolves to,
+// using the guest script's selector engine (so ambiguous selectors fail instead of picking one)
+fn element_script(selector: &str, body: &str) -> String {
+    let selector = ElementSelector::css(selector).to_js();
+    format!(
+        "(() => {{
+            const el = window.__TAURI_MCP__.resolveElement({selector});
+            {body}
+        }})()"
+    )
+}
+
+async fn run_element_script<R: Runtime>(app: &AppHandle<R>, window_label: String, script: String) -> Result<ElementResponse> {
+    let response = tools::handle_execute_js(app, json!({ "window_label": window_label, "code": script })).await?;
+
+    Ok(match into_data(response) {
+        Ok(data) => ElementResponse {
+            value: result_text(&data),
+            success: true,
+            error: None,
+        },
+        Err(error) => ElementResponse {
+            value: None,
+            success: false,
+            error: Some(error),
+        },
+    })
+}
@@ -1,149 +1,105 @@
-use crate::error::Error;
+use crate::auth::TcpAuth;
+use crate::error::{Error, ErrorCode, ErrorInfo};
 use crate::models::*;
 use crate::shared::{
     McpInterface, MouseMovementParams, MouseMovementResult, ScreenshotParams,
     ScreenshotResult as SharedScreenshotResult, TextInputParams, TextInputResult,
     WindowManagerParams, WindowManagerResult,
 };
+use crate::mcp::McpServer;
 use crate::socket_server::SocketServer;
-use crate::tools::mouse_movement;
-use crate::{PluginConfig, Result};
-use enigo::{Enigo, Keyboard, Settings};
+use crate::targets::TargetMap;
+use crate::tools::{self, mouse_movement};
+use crate::{PluginConfig, Result, SocketType};
+use enigo::Keyboard;
 use serde::de::DeserializeOwned;
+use serde_json::json;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, Runtime, plugin::PluginApi};
-use log::info;
+use log::{debug, info};
 
-// ----- Multi-Webview Architecture Support -----
+// ----- Target Resolution -----
 
-/// Resolves a window label to a WebviewWindow, with fallback for multi-webview architectures.
-///
-/// For apps like moss that use Window + child Webviews (Tauri unstable feature):
-/// - First tries get_webview_window (for legacy single-webview-per-window apps)
-/// - Then tries get_webview (for multi-webview apps using Window + Webview children)
-/// - When requesting "main", also tries "preview" as a fallback (moss convention)
-///
-/// Returns the WebviewWindow handle for window operations.
-pub fn resolve_window<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<tauri::WebviewWindow<R>> {
-    // First try direct WebviewWindow lookup (for legacy single-webview apps)
-    if let Some(ww) = app.get_webview_window(label) {
-        eprintln!("[TAURI_MCP] Found WebviewWindow '{}'", label);
-        return Ok(ww);
-    }
-
-    // For moss-style multi-webview: if requesting "main", try "preview"
-    if label == "main" {
-        if let Some(ww) = app.get_webview_window("preview") {
-            eprintln!("[TAURI_MCP] Resolved window 'main' to WebviewWindow 'preview'");
-            return Ok(ww);
-        }
-    }
-
-    // List all available for debugging
-    let ww_list: Vec<String> = app.webview_windows().keys().cloned().collect();
-    let webview_list: Vec<String> = app.webviews().keys().cloned().collect();
-    let window_list: Vec<String> = app.windows().keys().cloned().collect();
-    eprintln!("[TAURI_MCP] Window '{}' not found.", label);
-    eprintln!("[TAURI_MCP]   WebviewWindows: {:?}", ww_list);
-    eprintln!("[TAURI_MCP]   Webviews: {:?}", webview_list);
-    eprintln!("[TAURI_MCP]   Windows: {:?}", window_list);
+// The app's configured target map, or the default one before the plugin is set up
+fn targets<R: Runtime>(app: &AppHandle<R>) -> TargetMap {
+    app.try_state::<TargetMap>().map(|t| t.inner().clone()).unwrap_or_default()
+}
 
-    Err(Error::WindowNotFound(label.to_string()))
+/// The requested window label, or the configured default target when none was given
+pub fn target_label<R: Runtime>(app: &AppHandle<R>, label: Option<&str>) -> String {
+    targets(app).label_or_default(label)
 }
 
-/// Resolves a label to a Webview for DOM/JS operations.
-/// This function returns the webview label to use for emit_to().
+/// Resolves a label to the native window for window operations.
 ///
-/// For multi-webview apps (like moss), the webview might have a different label
-/// than the window (e.g., window "main" contains webview "preview").
-pub fn resolve_webview<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<(String, tauri::Webview<R>)> {
-    // Try to get the webview directly by label
-    if let Some(wv) = app.get_webview(label) {
-        eprintln!("[TAURI_MCP] Found Webview '{}'", label);
-        return Ok((label.to_string(), wv));
-    }
-
-    // For moss-style multi-webview: if requesting "main", try "preview"
-    if label == "main" {
-        if let Some(wv) = app.get_webview("preview") {
-            eprintln!("[TAURI_MCP] Resolved webview 'main' to 'preview' (multi-webview architecture)");
-            return Ok(("preview".to_string(), wv));
+/// Each candidate from the [`TargetMap`] (the label, then its aliases) may name a window
+/// or a webview; a webview resolves to the window hosting it. This covers both
+/// single-webview `WebviewWindow`s and windows with several child webviews.
+pub fn resolve_window<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<tauri::Window<R>> {
+    let targets = targets(app);
+    for candidate in targets.candidates(label) {
+        if let Some(window) = app.get_window(candidate) {
+            debug!("[TAURI_MCP] Resolved window '{}' to '{}'", label, candidate);
+            return Ok(window);
+        }
+        if let Some(webview) = app.get_webview(candidate) {
+            debug!("[TAURI_MCP] Resolved window '{}' to the window hosting webview '{}'", label, candidate);
+            return Ok(webview.window());
         }
     }
 
-    // List all available for debugging
-    let webview_list: Vec<String> = app.webviews().keys().cloned().collect();
-    eprintln!("[TAURI_MCP] Webview '{}' not found. Available: {:?}", label, webview_list);
-
-    Err(Error::WindowNotFound(format!("Webview '{}' not found", label)))
+    Err(not_found(app, label, &targets))
 }
 
-/// Resolves a window for screenshot functionality.
-/// For screenshots, we need either a WebviewWindow or a Window to get the title.
-/// xcap finds windows by title, so we need the title to capture.
+/// Resolves a label to a webview for DOM/JS operations, returning the label to use for `emit_to()`.
 ///
-/// This function tries:
-/// 1. get_webview_window (legacy single-webview apps)
-/// 2. get_webview_window("preview") if "main" requested (moss convention)
-/// 3. get_window (multi-webview apps) - returns the Window which we can use for title
-pub fn resolve_webview_window<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<tauri::WebviewWindow<R>> {
-    // First try direct WebviewWindow lookup
-    if let Some(ww) = app.get_webview_window(label) {
-        eprintln!("[TAURI_MCP] Screenshot: Found WebviewWindow '{}'", label);
-        return Ok(ww);
-    }
-
-    // For moss-style multi-webview: if requesting "main", try "preview"
-    if label == "main" {
-        if let Some(ww) = app.get_webview_window("preview") {
-            eprintln!("[TAURI_MCP] Screenshot: Resolved 'main' to WebviewWindow 'preview'");
-            return Ok(ww);
+/// Each candidate from the [`TargetMap`] may name a webview, or a window hosting exactly one
+/// webview. A window with several webviews is ambiguous, so target one of them by label
+/// (see `list_windows`) or configure an alias.
+pub fn resolve_webview<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<(String, tauri::Webview<R>)> {
+    let targets = targets(app);
+    for candidate in targets.candidates(label) {
+        if let Some(webview) = app.get_webview(candidate) {
+            debug!("[TAURI_MCP] Resolved webview '{}' to '{}'", label, candidate);
+            return Ok((candidate.to_string(), webview));
+        }
+        if let Some(window) = app.get_window(candidate)
+            && let [webview] = window.webviews().as_slice()
+        {
+            debug!("[TAURI_MCP] Resolved webview '{}' to '{}' in window '{}'", label, webview.label(), candidate);
+            return Ok((webview.label().to_string(), webview.clone()));
         }
     }
 
-    // List available for debugging
-    let ww_list: Vec<String> = app.webview_windows().keys().cloned().collect();
-    let window_list: Vec<String> = app.windows().keys().cloned().collect();
-    eprintln!("[TAURI_MCP] Screenshot: WebviewWindow '{}' not found.", label);
-    eprintln!("[TAURI_MCP]   Available WebviewWindows: {:?}", ww_list);
-    eprintln!("[TAURI_MCP]   Available Windows: {:?}", window_list);
-
-    Err(Error::WindowNotFound(label.to_string()))
+    Err(not_found(app, label, &targets))
 }
 
-/// Gets the window title for screenshot matching, supporting multi-webview architecture.
-/// Returns the title that xcap should search for.
-pub fn get_screenshot_window_title<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<String> {
-    // Try to get a WebviewWindow first
-    if let Some(ww) = app.get_webview_window(label) {
-        return ww.title().map_err(|e| Error::WindowOperationFailed(e.to_string()));
-    }
-
-    // For multi-webview: try to get the Window directly
-    if let Some(w) = app.get_window(label) {
-        return w.title().map_err(|e| Error::WindowOperationFailed(e.to_string()));
-    }
-
-    // If "main" not found, try "preview" (moss convention)
-    if label == "main" {
-        if let Some(ww) = app.get_webview_window("preview") {
-            return ww.title().map_err(|e| Error::WindowOperationFailed(e.to_string()));
-        }
-        if let Some(w) = app.get_window("preview") {
-            return w.title().map_err(|e| Error::WindowOperationFailed(e.to_string()));
-        }
-    }
-
-    Err(Error::WindowNotFound(label.to_string()))
+// Name what was tried and what exists, so the caller can pick a valid label
+fn not_found<R: Runtime>(app: &AppHandle<R>, label: &str, targets: &TargetMap) -> Error {
+    let mut windows: Vec<String> = app.windows().into_keys().collect();
+    let mut webviews: Vec<String> = app.webviews().into_keys().collect();
+    windows.sort();
+    webviews.sort();
+    let tried = targets.candidates(label);
+    let message = format!(
+        "Window not found: '{}' (tried {:?}; windows: {:?}; webviews: {:?})",
+        label, tried, windows, webviews
+    );
+    Error::Command(ErrorInfo::new(ErrorCode::WindowNotFound, message).with_details(json!({
+        "label": label,
+        "tried": tried,
+        "windows": windows,
+        "webviews": webviews
+    })))
 }
 
 // ----- Screenshot Utilities -----
 
 /// Helper structure to hold window for screenshot functions
 pub struct ScreenshotContext<R: Runtime> {
-    pub window: tauri::WebviewWindow<R>,
+    pub window: tauri::Window<R>,
 }
 
 /// Create a success response with data
@@ -155,15 +111,6 @@ pub fn create_success_response(data_url: String) -> ScreenshotResponse {
     }
 }
 
-/// Create an error response
-pub fn create_error_response(error_msg: String) -> ScreenshotResponse {
-    ScreenshotResponse {
-        data: None,
-        success: false,
-        error: Some(error_msg),
-    }
-}
-
 // ----- TauriMcp Implementation -----
 
 pub fn init<R: Runtime, C: DeserializeOwned>(
@@ -171,8 +118,29 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
     _api: PluginApi<R, C>,
     config: &PluginConfig,
 ) -> crate::Result<TauriMcp<R>> {
-    let socket_server = if config.start_socket_server {
-        let mut server = SocketServer::new(app.clone(), config.socket_type.clone());
+    // Resolved once so the socket and MCP servers share a generated token
+    let auth = TcpAuth::from_config(config)?;
+
+    // Managed before the servers start so the dispatcher can enforce it from the first request
+    app.manage(config.command_policy.clone());
+    app.manage(config.targets.clone());
+
+    // Names the default sockets and the discovery record
+    let socket_app_name = if config.application_name.is_empty() {
+        app.package_info().name.clone()
+    } else {
+        config.application_name.clone()
+    };
+
+    // Created even when not started, so the app can start it later with `start_server`
+    let mut socket_server = SocketServer::new(app.clone(), socket_app_name.clone(), config.socket_type.clone(), auth.clone())
+        .max_concurrent_commands(config.max_concurrent_commands);
+    if config.start_socket_server {
+        socket_server.start()?;
+    }
+
+    let mcp_server = if let Some(transport) = &config.mcp_transport {
+        let mut server = McpServer::new(app.clone(), socket_app_name, transport.clone(), auth);
         server.start()?;
         Some(Arc::new(Mutex::new(server)))
     } else {
@@ -181,7 +149,8 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
 
     Ok(TauriMcp {
         app: app.clone(),
-        socket_server,
+        socket_server: Arc::new(Mutex::new(socket_server)),
+        mcp_server,
         application_name: config.application_name.clone(),
     })
 }
@@ -189,11 +158,45 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
 /// Access to the tauri-mcp APIs.
 pub struct TauriMcp<R: Runtime> {
     app: AppHandle<R>,
-    socket_server: Option<Arc<Mutex<SocketServer<R>>>>,
+    socket_server: Arc<Mutex<SocketServer<R>>>,
+    mcp_server: Option<Arc<Mutex<McpServer<R>>>>,
     application_name: String,
 }
 
 impl<R: Runtime> TauriMcp<R> {
+    /// Start the socket server if it isn't running, e.g. when the user turns agent access on.
+    pub fn start_server(&self) -> crate::Result<()> {
+        self.socket_server()?.start()
+    }
+
+    /// Stop the socket server: stop accepting, let in-flight commands finish (up to 5 seconds),
+    /// close every client connection and remove the socket file. Resolves once that is done.
+    pub async fn stop_server(&self) -> crate::Result<()> {
+        let drain = self.socket_server()?.stop();
+        drain.wait().await;
+        Ok(())
+    }
+
+    /// Stop the socket server and start it again on `socket_type`.
+    pub async fn restart_server(&self, socket_type: SocketType) -> crate::Result<()> {
+        let drain = self.socket_server()?.stop();
+        drain.wait().await;
+        let mut server = self.socket_server()?;
+        server.set_socket_type(socket_type);
+        server.start()
+    }
+
+    /// Whether the socket server is running, where it listens, and its current load.
+    pub fn server_status(&self) -> crate::Result<SocketServerStatus> {
+        Ok(self.socket_server()?.status())
+    }
+
+    fn socket_server(&self) -> crate::Result<std::sync::MutexGuard<'_, SocketServer<R>>> {
+        self.socket_server
+            .lock()
+            .map_err(|_| Error::Anyhow("Socket server state is poisoned".to_string()))
+    }
+
     pub fn ping(&self, payload: PingRequest) -> crate::Result<PingResponse> {
         Ok(PingResponse {
             value: payload.value,
@@ -205,43 +208,35 @@ impl<R: Runtime> TauriMcp<R> {
         &self,
         payload: ScreenshotRequest,
     ) -> crate::Result<ScreenshotResponse> {
-        let window_label = payload.window_label.clone();
-
-        // For multi-webview architectures, we need to get the window title differently
-        // First, get the window title for xcap to find the window
-        let window_title = get_screenshot_window_title(&self.app, &window_label)?;
-        eprintln!("[TAURI_MCP] Screenshot: Got window title '{}' for label '{}'", window_title, window_label);
-
-        // Try to get a WebviewWindow for the context (may fail for multi-webview)
-        // The context is used for additional operations but xcap finds by app_name anyway
-        let window_context = if let Some(ww) = self.app.get_webview_window(&window_label) {
-            Some(ScreenshotContext { window: ww })
-        } else if let Some(ww) = self.app.get_webview_window("preview") {
-            Some(ScreenshotContext { window: ww })
-        } else {
-            None
-        };
-
         // Create shared parameters struct from the request
         let params = ScreenshotParams {
-            window_label: Some(window_label),
+            window_label: Some(payload.window_label.clone()),
             quality: None,
             max_width: None,
             max_size_mb: None,
             application_name: Some(self.application_name.clone()),
         };
 
-        info!("[TAURI_MCP] Taking screenshot with default parameters");
+        let window = match resolve_window(&self.app, &payload.window_label) {
+            Ok(window) => window,
+            // The default target may live in a window Tauri has no handle for; find it by app name
+            // instead, but keep the label error if that fails too
+            Err(not_found)
+                if not_found.code() == ErrorCode::WindowNotFound
+                    && payload.window_label == target_label(&self.app, None) =>
+            {
+                info!("[TAURI_MCP] Screenshot: '{}' not resolved, searching by app name", payload.window_label);
+                return crate::platform::current::take_screenshot_by_app_name(params, self.application_name.clone())
+                    .await
+                    .map_err(|_| not_found);
+            }
+            Err(e) => return Err(e),
+        };
+
+        info!("[TAURI_MCP] Taking screenshot of window '{}' with default parameters", window.label());
 
         // Use platform-specific implementation to capture the window
-        if let Some(ctx) = window_context {
-            crate::platform::current::take_screenshot(params, ctx).await
-        } else {
-            // For multi-webview without a WebviewWindow, we need to use app_name search only
-            // xcap will find the window by application_name
-            eprintln!("[TAURI_MCP] Screenshot: No WebviewWindow available, using app_name search");
-            crate::platform::current::take_screenshot_by_app_name(params, self.application_name.clone()).await
-        }
+        crate::platform::current::take_screenshot(params, ScreenshotContext { window }).await
     }
 
     // Add async method to perform window operations
@@ -249,9 +244,7 @@ impl<R: Runtime> TauriMcp<R> {
         &self,
         params: WindowManagerRequest,
     ) -> Result<WindowManagerResponse> {
-        let window_label = params.window_label.unwrap_or_else(|| "main".to_string());
-
-        // Use resolve_window to support both single and multi-webview architectures
+        let window_label = target_label(&self.app, params.window_label.as_deref());
         let window = resolve_window(&self.app, &window_label)?;
 
         // Execute the requested operation
@@ -357,6 +350,84 @@ impl<R: Runtime> TauriMcp<R> {
         }
     }
 
+    // Typed window control for the `control_window` Tauri command
+    pub fn control_window(&self, request: WindowControlRequest) -> Result<WindowControlResponse> {
+        let window = resolve_window(&self.app, &request.window_label)?;
+
+        match &request.action {
+            WindowAction::Minimize => window.minimize()?,
+            WindowAction::Maximize => window.maximize()?,
+            WindowAction::Unmaximize => window.unmaximize()?,
+            WindowAction::Close => window.close()?,
+            WindowAction::Show => window.show()?,
+            WindowAction::Hide => window.hide()?,
+            WindowAction::SetTitle { title } => window.set_title(title)?,
+            WindowAction::SetPosition { x, y } => {
+                window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x: *x, y: *y }))?
+            }
+            WindowAction::SetSize { width, height } => window.set_size(tauri::Size::Logical(tauri::LogicalSize {
+                width: *width,
+                height: *height,
+            }))?,
+            WindowAction::SetFullscreen { fullscreen } => window.set_fullscreen(*fullscreen)?,
+            WindowAction::Center => window.center()?,
+        }
+
+        Ok(WindowControlResponse {
+            success: true,
+            message: Some(format!("Applied {:?} to window '{}'", request.action, window.label())),
+        })
+    }
+
+    // State and geometry of every window and the webviews it hosts, sorted by label.
+    // Anything the platform can't report for a window (e.g. geometry of a hidden window, or
+    // state of one that is closing) is left empty rather than failing the whole listing.
+    pub fn list_windows(&self) -> Result<WindowListResponse> {
+        let mut windows = self
+            .app
+            .windows()
+            .into_values()
+            .map(|window| {
+                let mut webviews: Vec<WebviewInfo> = window
+                    .webviews()
+                    .into_iter()
+                    .map(|webview| WebviewInfo {
+                        label: webview.label().to_string(),
+                        window_label: window.label().to_string(),
+                        url: webview.url().ok().map(|url| url.to_string()),
+                        position: webview.position().ok().map(Into::into),
+                        size: webview.size().ok().map(Into::into),
+                    })
+                    .collect();
+                webviews.sort_by(|a, b| a.label.cmp(&b.label));
+
+                let monitor = window.current_monitor().ok().flatten().map(|monitor| MonitorInfo {
+                    name: monitor.name().cloned(),
+                    position: (*monitor.position()).into(),
+                    size: (*monitor.size()).into(),
+                    scale_factor: monitor.scale_factor(),
+                });
+
+                WindowInfo {
+                    label: window.label().to_string(),
+                    title: window.title().ok(),
+                    is_visible: window.is_visible().ok(),
+                    is_focused: window.is_focused().ok(),
+                    is_maximized: window.is_maximized().ok(),
+                    is_fullscreen: window.is_fullscreen().ok(),
+                    position: window.outer_position().ok().map(Into::into),
+                    size: window.outer_size().ok().map(Into::into),
+                    scale_factor: window.scale_factor().ok(),
+                    monitor,
+                    webviews,
+                }
+            })
+            .collect::<Vec<_>>();
+        windows.sort_by(|a, b| a.label.cmp(&b.label));
+
+        Ok(WindowListResponse { windows })
+    }
+
     // Text input simulation
     pub async fn simulate_text_input_async(
         &self,
@@ -366,38 +437,41 @@ impl<R: Runtime> TauriMcp<R> {
         let delay_ms = params.delay_ms.unwrap_or(20);
         let initial_delay_ms = params.initial_delay_ms.unwrap_or(500);
 
-        // Create Enigo instance with the latest API
-        let mut enigo = Enigo::new(&Settings::default())
-            .map_err(|e| Error::Anyhow(format!("Failed to initialize Enigo: {}", e)))?;
+        // Typing sleeps between characters, so keep it off the async workers
+        tools::run_blocking(move || {
+            let mut enigo = tools::start_enigo()?;
 
-        // Initial delay before typing
-        if initial_delay_ms > 0 {
-            thread::sleep(Duration::from_millis(initial_delay_ms));
-        }
+            // Initial delay before typing
+            if initial_delay_ms > 0 {
+                thread::sleep(Duration::from_millis(initial_delay_ms));
+            }
 
-        let start_time = Instant::now();
+            let start_time = Instant::now();
 
-        // Use the text method from the Keyboard trait
-        if delay_ms == 0 {
-            // Fast typing (all at once)
-            Keyboard::text(&mut enigo, &text)
-                .map_err(|e| Error::Anyhow(format!("Failed to simulate text input: {}", e)))?;
-        } else {
-            // Slow typing with configurable delay
-            for c in text.chars() {
-                Keyboard::text(&mut enigo, &c.to_string())
-                    .map_err(|e| Error::Anyhow(format!("Failed to simulate text input: {}", e)))?;
+            // Use the text method from the Keyboard trait
+            if delay_ms == 0 {
+                // Fast typing (all at once)
+                Keyboard::text(&mut enigo, &text)
+                    .map_err(|e| tools::os_error(format!("Failed to simulate text input: {}", e)))?;
+            } else {
+                // Slow typing with configurable delay
+                for c in text.chars() {
+                    Keyboard::text(&mut enigo, &c.to_string())
+                        .map_err(|e| tools::os_error(format!("Failed to simulate text input: {}", e)))?;
 
-                thread::sleep(Duration::from_millis(delay_ms));
+                    thread::sleep(Duration::from_millis(delay_ms));
+                }
             }
-        }
 
-        let duration_ms = start_time.elapsed().as_millis() as u64;
+            let duration_ms = start_time.elapsed().as_millis() as u64;
 
-        Ok(TextInputResponse {
-            chars_typed: text.chars().count() as u32,
-            duration_ms,
+            Ok(TextInputResponse {
+                chars_typed: text.chars().count() as u32,
+                duration_ms,
+            })
         })
+        .await
+        .map_err(Error::Command)
     }
 
     // Mouse movement simulation
@@ -410,11 +484,15 @@ impl<R: Runtime> TauriMcp<R> {
 }
 
 impl<R: Runtime> Drop for TauriMcp<R> {
+    // Only signals shutdown; the tasks finish on the runtime without holding up the caller
     fn drop(&mut self) {
-        if let Some(server) = &self.socket_server {
-            if let Ok(server) = server.lock() {
-                let _ = server.stop();
-            }
+        if let Ok(mut server) = self.socket_server.lock() {
+            let _ = server.stop();
+        }
+        if let Some(server) = &self.mcp_server
+            && let Ok(mut server) = server.lock()
+        {
+            let _ = server.stop();
         }
     }
 }
@@ -426,7 +504,7 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
         params: ScreenshotParams,
     ) -> std::result::Result<SharedScreenshotResult, String> {
         // Create a ScreenshotRequest from our interface params
-        let window_label = params.window_label.unwrap_or_else(|| "main".to_string());
+        let window_label = target_label(&self.app, params.window_label.as_deref());
 
         let request = ScreenshotRequest { window_label };
         match futures::executor::block_on(self.take_screenshot_async(request)) {
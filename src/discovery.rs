@@ -0,0 +1,336 @@
+use interprocess::local_socket::{GenericFilePath, Stream as IpcStream, ToFsName, prelude::*};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+
+/// Version of the socket protocol, advertised in discovery records and by `describe`.
+/// Version 2 made `error` in responses an object with a code instead of a string.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+// Directory under the runtime directory that holds one record per running instance
+const DISCOVERY_DIR: &str = "tauri-mcp";
+// Longest app name kept in a socket file name; Unix socket paths are limited to about 100 bytes
+const MAX_SLUG_LEN: usize = 32;
+// How long a TCP liveness probe waits for the connection
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// A running app's socket server, as recorded in [`discovery_dir`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceRecord {
+    /// `PluginConfig::application_name` of the app
+    pub app: String,
+    pub pid: u32,
+    /// "ipc" or "tcp"
+    pub transport: String,
+    /// Socket path (a `\\.\pipe\` path on Windows) or host:port
+    pub address: String,
+    pub protocol_version: u32,
+    /// When the server started, in milliseconds since the Unix epoch
+    pub started_at: u64,
+}
+
+impl InstanceRecord {
+    pub(crate) fn new(app: &str, transport: &str, address: String) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        InstanceRecord {
+            app: app.to_string(),
+            pid: std::process::id(),
+            transport: transport.to_string(),
+            address,
+            protocol_version: PROTOCOL_VERSION,
+            started_at,
+        }
+    }
+
+    // Whether a server still answers at the recorded address
+    fn is_alive(&self) -> bool {
+        match self.transport.as_str() {
+            "ipc" => probe_ipc(&self.address),
+            "tcp" => probe_tcp(&self.address),
+            _ => false,
+        }
+    }
+
+    /// Write this process's record (0600 on Unix), replacing any earlier one
+    pub(crate) fn write(&self) -> crate::Result<PathBuf> {
+        write_record(&discovery_dir(), self)
+    }
+}
+
+/// `$XDG_RUNTIME_DIR` when set, otherwise the temp directory
+pub(crate) fn runtime_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        // The spec says to ignore relative values
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Where running instances write their [`InstanceRecord`]s: `tauri-mcp` in [`runtime_dir`]
+pub fn discovery_dir() -> PathBuf {
+    runtime_dir().join(DISCOVERY_DIR)
+}
+
+/// Running instances, newest first. Records whose server no longer answers are removed.
+pub fn list_instances() -> Vec<InstanceRecord> {
+    read_records(&discovery_dir())
+}
+
+/// Default IPC socket for an app: `tauri-mcp-<app>-<pid>.sock` in the runtime directory,
+/// or a named pipe of that name on Windows
+pub(crate) fn default_socket_path(application_name: &str) -> PathBuf {
+    socket_file(format!("tauri-mcp-{}-{}.sock", app_slug(application_name), std::process::id()))
+}
+
+/// Default IPC path for the embedded MCP server, kept apart from the socket server's
+pub(crate) fn default_mcp_socket_path(application_name: &str) -> PathBuf {
+    socket_file(format!("tauri-mcp-server-{}-{}.sock", app_slug(application_name), std::process::id()))
+}
+
+// A named pipe on Windows, a file in the runtime directory elsewhere
+fn socket_file(file_name: String) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        PathBuf::from(file_name)
+    } else {
+        runtime_dir().join(file_name)
+    }
+}
+
+/// The address clients connect to for an IPC socket path
+pub(crate) fn ipc_address(path: &Path) -> String {
+    if cfg!(target_os = "windows") {
+        format!(r"\\.\pipe\{}", path.display())
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Remove a socket file left behind by a process that exited without cleaning up. Fails if
+/// a server still accepts on it, or if the path is something other than a socket.
+pub(crate) fn clear_stale_socket(path: &Path) -> crate::Result<()> {
+    // Named pipes go away with their server; only Unix socket files linger
+    if cfg!(target_os = "windows") {
+        return Ok(());
+    }
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !is_socket(&metadata) {
+        return Err(Error::Io(format!("{} exists and is not a socket", path.display())));
+    }
+    if probe_ipc(&path.to_string_lossy()) {
+        return Err(Error::Io(format!(
+            "Socket {} is in use by another running instance",
+            path.display()
+        )));
+    }
+
+    warn!("[TAURI_MCP] Removing stale socket left by an earlier run: {}", path.display());
+    std::fs::remove_file(path)
+        .map_err(|e| Error::Io(format!("Failed to remove stale socket {}: {}", path.display(), e)))
+}
+
+/// Restrict a freshly bound socket file to the current user
+pub(crate) fn restrict_socket(path: &Path) -> crate::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| Error::Io(format!("Failed to set permissions on {}: {}", path.display(), e)))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Remove this process's record, if it wrote one
+pub(crate) fn remove_record() {
+    let _ = std::fs::remove_file(record_path(&discovery_dir(), std::process::id()));
+}
+
+fn record_path(dir: &Path, pid: u32) -> PathBuf {
+    dir.join(format!("{}.json", pid))
+}
+
+fn write_record(dir: &Path, record: &InstanceRecord) -> crate::Result<PathBuf> {
+    create_private_dir(dir)?;
+    let path = record_path(dir, record.pid);
+    let json = serde_json::to_vec_pretty(record).map_err(|e| Error::Io(e.to_string()))?;
+
+    // Write aside and rename, so readers never see a partial record
+    let temp_path = path.with_extension("json.tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&temp_path)
+        .map_err(|e| Error::Io(format!("Failed to write discovery record {}: {}", temp_path.display(), e)))?;
+    file.write_all(&json)?;
+    std::fs::rename(&temp_path, &path)?;
+    Ok(path)
+}
+
+fn read_records(dir: &Path) -> Vec<InstanceRecord> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut records: Vec<InstanceRecord> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let record = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<InstanceRecord>(&bytes).ok());
+            match record {
+                Some(record) if record.is_alive() => Some(record),
+                // Left by a process that crashed, or unreadable
+                _ => {
+                    let _ = std::fs::remove_file(&path);
+                    None
+                }
+            }
+        })
+        .collect();
+    records.sort_by_key(|record| std::cmp::Reverse(record.started_at));
+    records
+}
+
+fn create_private_dir(dir: &Path) -> crate::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .map_err(|e| Error::Io(format!("Failed to create discovery directory {}: {}", dir.display(), e)))
+}
+
+// Lowercase ASCII letters and digits, with runs of anything else collapsed to one dash
+fn app_slug(application_name: &str) -> String {
+    let mut slug = String::new();
+    for c in application_name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "app".to_string() } else { slug.to_string() }
+}
+
+#[cfg(unix)]
+fn is_socket(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    metadata.file_type().is_socket()
+}
+
+#[cfg(not(unix))]
+fn is_socket(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+fn probe_ipc(address: &str) -> bool {
+    address
+        .to_fs_name::<GenericFilePath>()
+        .and_then(IpcStream::connect)
+        .is_ok()
+}
+
+fn probe_tcp(address: &str) -> bool {
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_slug() {
+        assert_eq!(app_slug("My App"), "my-app");
+        assert_eq!(app_slug("  Tauri / MCP  Demo!"), "tauri-mcp-demo");
+        assert_eq!(app_slug(""), "app");
+        assert_eq!(app_slug("日本語"), "app");
+        assert_eq!(app_slug(&"x".repeat(100)).len(), MAX_SLUG_LEN);
+
+        let path = default_socket_path("My App");
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(file_name, format!("tauri-mcp-my-app-{}.sock", std::process::id()));
+    }
+
+    #[test]
+    fn test_records_round_trip_and_prune_dead_servers() {
+        let dir = std::env::temp_dir().join(format!("tauri-mcp-discovery-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = InstanceRecord::new("live", "tcp", listener.local_addr().unwrap().to_string());
+        write_record(&dir, &live).unwrap();
+
+        let mut dead = InstanceRecord::new("dead", "ipc", dir.join("missing.sock").display().to_string());
+        dead.pid += 1;
+        write_record(&dir, &dead).unwrap();
+
+        assert_eq!(read_records(&dir), vec![live.clone()]);
+        assert!(!record_path(&dir, dead.pid).exists());
+        assert!(record_path(&dir, live.pid).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clear_stale_socket() {
+        use std::os::unix::net::UnixListener;
+
+        let dir = std::env::temp_dir().join(format!("tauri-mcp-stale-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.sock");
+
+        // Nothing there
+        assert!(clear_stale_socket(&path).is_ok());
+
+        // Live server keeps its socket
+        let listener = UnixListener::bind(&path).unwrap();
+        assert!(clear_stale_socket(&path).is_err());
+        assert!(path.exists());
+
+        // Dropping a std listener leaves the file behind, like a crash
+        drop(listener);
+        assert!(clear_stale_socket(&path).is_ok());
+        assert!(!path.exists());
+
+        // Never removes something that isn't a socket
+        std::fs::write(&path, "data").unwrap();
+        assert!(clear_stale_socket(&path).is_err());
+        assert!(path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,6 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use thiserror::Error as ThisError;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -23,6 +25,38 @@ pub enum Error {
 
     #[error("Tauri error: {0}")]
     TauriError(String),
+
+    /// A failure that already carries its code and details
+    #[error("{}", .0.message)]
+    Command(ErrorInfo),
+}
+
+impl Error {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Command(ErrorInfo::new(code, message))
+    }
+
+    /// A payload that parsed but failed the command's own checks
+    pub fn invalid_payload(message: impl Into<String>) -> Self {
+        Self::Command(ErrorInfo::new(ErrorCode::InvalidPayload, message))
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::WindowNotFound(_) => ErrorCode::WindowNotFound,
+            Self::WindowOperationFailed(_) | Self::Io(_) => ErrorCode::OsError,
+            Self::PluginInit(_) | Self::Anyhow(_) | Self::TauriError(_) => ErrorCode::Internal,
+            Self::Command(info) => info.code,
+        }
+    }
+
+    /// The error as clients receive it
+    pub fn into_info(self) -> ErrorInfo {
+        match self {
+            Self::Command(info) => info,
+            other => ErrorInfo::new(other.code(), other.to_string()),
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -42,3 +76,138 @@ impl From<tauri::Error> for Error {
         Self::TauriError(error.to_string())
     }
 }
+
+impl From<ErrorInfo> for Error {
+    fn from(info: ErrorInfo) -> Self {
+        Self::Command(info)
+    }
+}
+
+/// Stable, machine-readable category of a failed request. New codes may be added;
+/// clients should treat unknown ones like `internal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request line was not a JSON request, or was too long
+    InvalidRequest,
+    /// The payload doesn't fit the command; `details.path` names the field when known
+    InvalidPayload,
+    UnknownCommand,
+    /// The TCP auth handshake failed
+    Unauthorized,
+    /// The command policy refused the command or one of its arguments
+    PermissionDenied,
+    /// No window or webview has the label; `details` lists the ones that exist
+    WindowNotFound,
+    /// No element matched the selector
+    ElementNotFound,
+    /// Several elements matched the selector; `details.candidates` describes them
+    AmbiguousSelector,
+    /// The element exists but can't be used right now, e.g. another element covers it
+    ElementNotInteractable,
+    /// JavaScript threw or rejected; `details.stack` has the stack when the webview reports one
+    JsException,
+    /// No answer, or the awaited condition didn't hold, in time
+    Timeout,
+    /// The webview couldn't be reached or gave an unusable answer
+    WebviewError,
+    /// The operation isn't available on this OS or session, e.g. no X11 display
+    UnsupportedPlatform,
+    /// An OS operation such as window capture, window control or input failed
+    OsError,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same request may succeed if sent again later
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::ElementNotFound | Self::ElementNotInteractable | Self::Timeout | Self::WebviewError
+        )
+    }
+
+    /// The code for a `details.code` reported by the guest script
+    pub fn from_guest(code: &str) -> Option<Self> {
+        match code {
+            // An element inside a cross-origin frame can't be reached from the page
+            "element_not_found" | "cross_origin_frame" => Some(Self::ElementNotFound),
+            "ambiguous_selector" => Some(Self::AmbiguousSelector),
+            "invalid_selector" => Some(Self::InvalidPayload),
+            "element_covered" => Some(Self::ElementNotInteractable),
+            "timeout" => Some(Self::Timeout),
+            _ => None,
+        }
+    }
+}
+
+/// A failed request, as sent in `SocketResponse::error`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorInfo {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Structured context for the code (null when there is none)
+    pub details: Option<Value>,
+    pub retryable: bool,
+}
+
+impl ErrorInfo {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ErrorInfo {
+            code,
+            message: message.into(),
+            details: None,
+            retryable: code.retryable(),
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details).filter(|details| !details.is_null());
+        self
+    }
+
+    /// A failure reported by the guest script as `{ error, details }`, classified by `details.code`
+    pub fn from_guest(response: &Value, fallback: ErrorCode, default_message: &str) -> Self {
+        let message = response["error"].as_str().unwrap_or(default_message);
+        let details = response.get("details").cloned().unwrap_or(Value::Null);
+        let code = details["code"].as_str().and_then(ErrorCode::from_guest).unwrap_or(fallback);
+        ErrorInfo::new(code, message).with_details(details)
+    }
+
+    /// A JavaScript exception, with the stack in `details` when there is one
+    pub fn js_exception(message: impl Into<String>, stack: Option<&str>) -> Self {
+        let info = ErrorInfo::new(ErrorCode::JsException, message);
+        match stack {
+            Some(stack) => info.with_details(json!({ "stack": stack })),
+            None => info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_info_shape() {
+        let info = Error::WindowNotFound("'main'".to_string()).into_info();
+        assert_eq!(info.code, ErrorCode::WindowNotFound);
+        assert_eq!(info.message, "Window not found: 'main'");
+
+        let value = serde_json::to_value(ErrorInfo::new(ErrorCode::Timeout, "no reply")).unwrap();
+        assert_eq!(value, json!({ "code": "timeout", "message": "no reply", "details": null, "retryable": true }));
+
+        let guest = json!({
+            "success": false,
+            "error": "2 elements match",
+            "details": { "code": "ambiguous_selector", "matchCount": 2, "candidates": [] }
+        });
+        let info = ErrorInfo::from_guest(&guest, ErrorCode::WebviewError, "failed");
+        assert_eq!(info.code, ErrorCode::AmbiguousSelector);
+        assert!(!info.retryable);
+        assert_eq!(info.details.unwrap()["matchCount"], 2);
+
+        let info = ErrorInfo::from_guest(&json!({ "success": false }), ErrorCode::WebviewError, "failed");
+        assert_eq!((info.code, info.message.as_str(), info.details), (ErrorCode::WebviewError, "failed", None));
+    }
+}
@@ -11,16 +11,25 @@ mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+mod auth;
 mod commands;
+mod discovery;
 mod error;
+mod mcp;
 mod models;
+mod policy;
+mod selector;
 pub mod shared;
 mod socket_server;
+mod targets;
 mod tools;
 // Platform-specific module
 mod platform;
 
-pub use error::{Error, Result};
+pub use discovery::{InstanceRecord, PROTOCOL_VERSION, discovery_dir, list_instances};
+pub use error::{Error, ErrorCode, ErrorInfo, Result};
+pub use policy::CommandPolicy;
+pub use targets::TargetMap;
 pub use shared::{
     McpInterface, ScreenshotParams, ScreenshotResult, WindowManagerParams, WindowManagerResult,
 };
@@ -64,15 +73,50 @@ impl Default for SocketType {
     }
 }
 
+/// Transport for the embedded MCP server
+#[derive(Clone, Debug)]
+pub enum McpTransport {
+    /// Newline-delimited JSON-RPC on the process's stdin/stdout
+    Stdio,
+    /// Newline-delimited JSON-RPC on an IPC or TCP socket.
+    /// An IPC socket without a path uses `tauri-mcp-server-<app>-<pid>.sock` in the runtime
+    /// directory, or a named pipe of that name on Windows.
+    Socket(SocketType),
+    /// MCP streamable-HTTP transport, served at `/mcp`
+    Http {
+        /// Host to bind to (e.g., "127.0.0.1")
+        host: String,
+        /// Port to bind to
+        port: u16,
+    },
+}
+
 /// Plugin configuration options.
 #[derive(Default)]
 pub struct PluginConfig {
-    /// Application name (used for default socket naming)
+    /// Application name, used for the default socket name and in discovery records
+    /// (the package name when empty)
     pub application_name: String,
     /// Socket configuration
     pub socket_type: SocketType,
     /// Whether to start the socket server automatically. Default is true.
     pub start_socket_server: bool,
+    /// Transport for the embedded MCP server. `None` (the default) disables it.
+    pub mcp_transport: Option<McpTransport>,
+    /// Shared secret TCP clients must present in the `auth` handshake before any other request
+    pub auth_token: Option<String>,
+    /// File the auth token is written to (0600 on Unix). When set without `auth_token`,
+    /// a random token is generated at startup.
+    pub auth_token_file: Option<std::path::PathBuf>,
+    /// Peer addresses allowed to connect over TCP. Empty (the default) allows any peer.
+    pub allowed_peers: Vec<std::net::IpAddr>,
+    /// Which commands clients may run. Default allows all.
+    pub command_policy: CommandPolicy,
+    /// How requested window labels map to the app's real window and webview labels
+    pub targets: TargetMap,
+    /// Most socket commands that run at once across all clients; further requests wait
+    /// for a slot. `None` (the default) allows 16.
+    pub max_concurrent_commands: Option<usize>,
 }
 
 impl PluginConfig {
@@ -82,6 +126,13 @@ impl PluginConfig {
             application_name,
             socket_type: SocketType::default(),
             start_socket_server: true,
+            mcp_transport: None,
+            auth_token: None,
+            auth_token_file: None,
+            allowed_peers: Vec::new(),
+            command_policy: CommandPolicy::default(),
+            targets: TargetMap::default(),
+            max_concurrent_commands: None,
         }
     }
 
@@ -102,6 +153,60 @@ impl PluginConfig {
         self.start_socket_server = start;
         self
     }
+
+    /// Require TCP clients to authenticate with this shared secret.
+    pub fn auth_token(mut self, token: String) -> Self {
+        self.auth_token = Some(token);
+        self
+    }
+
+    /// Write the auth token to this file, generating a random token if none was set.
+    pub fn auth_token_file(mut self, path: std::path::PathBuf) -> Self {
+        self.auth_token_file = Some(path);
+        self
+    }
+
+    /// Only accept TCP connections from this peer address (may be called repeatedly).
+    pub fn allow_peer(mut self, addr: std::net::IpAddr) -> Self {
+        self.allowed_peers.push(addr);
+        self
+    }
+
+    /// Restrict which commands clients may run, e.g. `CommandPolicy::read_only()`.
+    pub fn command_policy(mut self, policy: CommandPolicy) -> Self {
+        self.command_policy = policy;
+        self
+    }
+
+    /// Window label used when a request doesn't name one. Default is `"main"`.
+    pub fn default_target(mut self, label: impl Into<String>) -> Self {
+        self.targets = self.targets.default_target(label);
+        self
+    }
+
+    /// When no window or webview is labelled `label`, try `targets` in order,
+    /// e.g. `.label_alias("main", ["preview"])` for an app whose content lives in a "preview" webview.
+    pub fn label_alias<I, S>(mut self, label: impl Into<String>, targets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.targets = self.targets.alias(label, targets);
+        self
+    }
+
+    /// Limit how many socket commands run at once across all clients (at least 1).
+    pub fn max_concurrent_commands(mut self, limit: usize) -> Self {
+        self.max_concurrent_commands = Some(limit.max(1));
+        self
+    }
+
+    /// Serve the Model Context Protocol directly from the app on the given transport,
+    /// so agents can connect without the Node bridge.
+    pub fn mcp_server(mut self, transport: McpTransport) -> Self {
+        self.mcp_transport = Some(transport);
+        self
+    }
 }
 
 /// Initializes the plugin.
@@ -124,12 +229,8 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
                     path.display()
                 );
             } else {
-                let default_path = std::env::temp_dir().join("tauri-mcp.sock");
-                eprintln!("[TAURI_MCP] Socket server will use default IPC path: {}", default_path.display());
-                info!(
-                    "[TAURI_MCP] Socket server will use default IPC path: {}",
-                    default_path.display()
-                );
+                eprintln!("[TAURI_MCP] Socket server will use the default per-app IPC path");
+                info!("[TAURI_MCP] Socket server will use the default per-app IPC path");
             }
         }
         SocketType::Tcp { host, port } => {
@@ -149,10 +250,27 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
         info!("[TAURI_MCP] Socket server auto-start is disabled");
     }
 
-    Builder::new("tauri-mcp")
+    if let Some(transport) = &config.mcp_transport {
+        eprintln!("[TAURI_MCP] Embedded MCP server will use: {:?}", transport);
+        info!("[TAURI_MCP] Embedded MCP server will use: {:?}", transport);
+    }
+
+    // Must match the permission prefix tauri-build derives from the crate name (`mcp:allow-*`)
+    Builder::new("mcp")
         .js_init_script(include_str!("scripts/init.iife.js").to_string())
         .invoke_handler(tauri::generate_handler![
-        // Server Commands
+            commands::click_element,
+            commands::control_window,
+            commands::eval_js,
+            commands::get_element_text,
+            commands::get_html,
+            commands::get_title,
+            commands::get_url,
+            commands::list_windows,
+            commands::ping,
+            commands::set_element_value,
+            commands::take_screenshot,
+            commands::type_text,
         ])
         .setup(move |app, api| {
             eprintln!("[TAURI_MCP] Plugin setup started");
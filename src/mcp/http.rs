@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::socket_server::read_line;
+
+/// Path the streamable-HTTP endpoint is served at
+pub const MCP_PATH: &str = "/mcp";
+
+// Refuse request bodies larger than this
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+// Longest request or header line accepted
+const MAX_LINE_BYTES: usize = 8 * 1024;
+// Most headers accepted on one request
+const MAX_HEADERS: usize = 100;
+
+/// The request line and headers of an HTTP/1.1 request. The body is read separately with
+/// [`read_body`], so a request can be refused before it is read.
+#[derive(Debug)]
+pub struct RequestHead {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+}
+
+impl RequestHead {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// Whether the client asked to close the connection after this request
+    pub fn wants_close(&self) -> bool {
+        self.header("connection")
+            .is_some_and(|v| v.eq_ignore_ascii_case("close"))
+    }
+}
+
+/// Read the request line and headers. Returns `Ok(None)` when the client closed the connection.
+pub async fn read_head<B: AsyncBufRead + Unpin>(reader: &mut B) -> io::Result<Option<RequestHead>> {
+    let Some(request_line) = read_line(reader, MAX_LINE_BYTES).await? else {
+        return Ok(None);
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Malformed request line"));
+    };
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    let method = method.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let Some(line) = read_line(reader, MAX_LINE_BYTES).await? else {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed in headers"));
+        };
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Too many headers"));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(Some(RequestHead { method, path, headers }))
+}
+
+/// Read the body announced by `head`'s `Content-Length`
+pub async fn read_body<B: AsyncBufRead + Unpin>(reader: &mut B, head: &RequestHead) -> io::Result<Vec<u8>> {
+    let length = match head.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Request body too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+/// Write a response with an optional JSON body
+pub async fn write_response<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    status: u16,
+    reason: &str,
+    json_body: Option<&str>,
+    close: bool,
+) -> io::Result<()> {
+    let body = json_body.unwrap_or("");
+    let mut head = format!("HTTP/1.1 {} {}\r\nContent-Length: {}\r\n", status, reason, body.len());
+    if json_body.is_some() {
+        head.push_str("Content-Type: application/json\r\n");
+    }
+    if status == 405 {
+        head.push_str("Allow: POST\r\n");
+    }
+    if status == 401 {
+        head.push_str("WWW-Authenticate: Bearer\r\n");
+    }
+    head.push_str(if close { "Connection: close\r\n\r\n" } else { "Connection: keep-alive\r\n\r\n" });
+
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.flush().await
+}
+
+/// Browsers must not be able to reach the server from arbitrary pages (DNS rebinding),
+/// so requests carrying an `Origin` header are only accepted from loopback origins.
+pub fn is_allowed_origin(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+
+    let host = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    let host = if host.starts_with('[') {
+        host.split(']').next().map(|h| format!("{}]", h)).unwrap_or_default()
+    } else {
+        host.split(':').next().unwrap_or("").to_string()
+    };
+
+    matches!(host.as_str(), "localhost" | "tauri.localhost" | "127.0.0.1" | "[::1]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn test_read_request_with_body() {
+        let raw = "POST /mcp?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+        let mut reader = raw.as_bytes();
+
+        let head = block_on(read_head(&mut reader)).unwrap().unwrap();
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, MCP_PATH);
+        assert!(head.wants_close());
+        // The head is read without touching the body
+        assert_eq!(reader, b"{}");
+
+        assert_eq!(block_on(read_body(&mut reader, &head)).unwrap(), b"{}");
+        assert!(block_on(read_head(&mut reader)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_oversized_body_is_refused_unread() {
+        let raw = format!("POST /mcp HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_BYTES + 1);
+        let mut reader = raw.as_bytes();
+
+        let head = block_on(read_head(&mut reader)).unwrap().unwrap();
+        let error = block_on(read_body(&mut reader, &head)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_origin_check() {
+        assert!(is_allowed_origin(None));
+        assert!(is_allowed_origin(Some("http://localhost:1420")));
+        assert!(is_allowed_origin(Some("http://[::1]:8080")));
+        assert!(!is_allowed_origin(Some("https://evil.example")));
+        assert!(!is_allowed_origin(Some("http://localhost.evil.example")));
+    }
+}
@@ -0,0 +1,338 @@
+use interprocess::local_socket::ListenerOptions;
+use interprocess::local_socket::tokio::{Listener as IpcListener, prelude::*};
+use log::{error, info, warn};
+use serde_json::Value;
+use std::net::TcpListener as StdTcpListener;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+
+use crate::auth::TcpAuth;
+use crate::error::Error;
+use crate::discovery;
+use crate::policy::CommandPolicy;
+use crate::socket_server::{read_line, socket_name, warn_if_exposed};
+use crate::{McpTransport, SocketType};
+
+mod http;
+mod protocol;
+pub(crate) mod tools;
+
+// Longest JSON-RPC message accepted on the line-based transports
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+// Pause after a failed accept so a persistent error doesn't spin
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Embedded MCP server: answers `initialize`, `tools/list` and `tools/call` directly from the app,
+/// so agents can connect without the Node bridge. Each tool runs a `tools::handle_command` command.
+///
+/// Like the socket server, every transport runs as tasks on Tauri's shared tokio runtime.
+pub struct McpServer<R: Runtime> {
+    app: AppHandle<R>,
+    application_name: String,
+    transport: McpTransport,
+    auth: TcpAuth,
+    // Set while running; sending `true` stops the listener, every client and the stdio reader
+    shutdown: Option<watch::Sender<bool>>,
+}
+
+enum McpListener {
+    Ipc(IpcListener),
+    Tcp(TcpListener),
+    Http(TcpListener),
+}
+
+enum McpConnection {
+    Ipc(interprocess::local_socket::tokio::Stream),
+    Tcp(TcpStream),
+    Http(TcpStream),
+}
+
+impl<R: Runtime> McpServer<R> {
+    pub fn new(app: AppHandle<R>, application_name: String, transport: McpTransport, auth: TcpAuth) -> Self {
+        McpServer {
+            app,
+            application_name,
+            transport,
+            auth,
+            shutdown: None,
+        }
+    }
+
+    /// Start serving on the configured transport. Does nothing if the server is already running.
+    pub fn start(&mut self) -> crate::Result<()> {
+        if self.shutdown.is_some() {
+            return Ok(());
+        }
+        info!("[TAURI_MCP] Starting embedded MCP server on {:?}", self.transport);
+
+        // Tokio listeners register with the runtime that polls them
+        let runtime = tauri::async_runtime::handle();
+        let _guard = runtime.inner().enter();
+
+        let (shutdown_tx, shutdown) = watch::channel(false);
+        let app = self.app.clone();
+        let auth = self.auth.clone();
+
+        let (listener, socket_file) = match &self.transport {
+            McpTransport::Stdio => {
+                tauri::async_runtime::spawn(async move {
+                    let stdin = BufReader::new(tokio::io::stdin());
+                    if let Err(e) = serve_lines(&app, stdin, tokio::io::stdout(), shutdown).await {
+                        error!("[TAURI_MCP] MCP stdio transport stopped: {}", e);
+                    }
+                });
+                self.shutdown = Some(shutdown_tx);
+                info!("[TAURI_MCP] Embedded MCP server started");
+                return Ok(());
+            }
+            McpTransport::Socket(SocketType::Ipc { path }) => {
+                let path = path
+                    .clone()
+                    .unwrap_or_else(|| discovery::default_mcp_socket_path(&self.application_name));
+                discovery::clear_stale_socket(&path)?;
+                let listener = ListenerOptions::new()
+                    .name(socket_name(&path)?)
+                    .create_tokio()
+                    .map_err(|e| Error::Io(format!("Failed to create MCP socket {}: {}", path.display(), e)))?;
+                discovery::restrict_socket(&path)?;
+                info!("[TAURI_MCP] MCP server listening on {}", discovery::ipc_address(&path));
+
+                // Dropping the listener reclaims the socket file; remove it ourselves if that didn't happen
+                let socket_file = (!cfg!(target_os = "windows")).then_some(path);
+                (McpListener::Ipc(listener), socket_file)
+            }
+            McpTransport::Socket(SocketType::Tcp { host, port }) => {
+                (McpListener::Tcp(bind_tcp(host, *port, &auth)?), None)
+            }
+            McpTransport::Http { host, port } => (McpListener::Http(bind_tcp(host, *port, &auth)?), None),
+        };
+
+        tauri::async_runtime::spawn(accept_loop(listener, app, auth, shutdown, socket_file));
+        self.shutdown = Some(shutdown_tx);
+        info!("[TAURI_MCP] Embedded MCP server started");
+        Ok(())
+    }
+
+    /// Stop accepting clients and close every connection, including the stdio transport.
+    /// Returns at once; a client in the middle of a tool call is closed when the call finishes.
+    pub fn stop(&mut self) -> crate::Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            info!("[TAURI_MCP] Stopping embedded MCP server");
+            let _ = shutdown.send(true);
+        }
+        Ok(())
+    }
+}
+
+// Bind synchronously so errors reach the caller, then hand the socket to tokio
+fn bind_tcp(host: &str, port: u16, auth: &TcpAuth) -> crate::Result<TcpListener> {
+    let addr = format!("{}:{}", host, port);
+    StdTcpListener::bind(&addr)
+        .and_then(|listener| {
+            warn_if_exposed(&listener, auth);
+            listener.set_nonblocking(true)?;
+            TcpListener::from_std(listener)
+        })
+        .map_err(|e| Error::Io(format!("Failed to bind MCP server to {}: {}", addr, e)))
+}
+
+// Accept connections until shutdown is signalled, serving each on its own task. Returns once
+// the listener is closed and every connection has closed.
+async fn accept_loop<R: Runtime>(
+    listener: McpListener,
+    app: AppHandle<R>,
+    auth: TcpAuth,
+    mut shutdown: watch::Receiver<bool>,
+    socket_file: Option<PathBuf>,
+) {
+    let mut clients = JoinSet::new();
+    loop {
+        let accepted = tokio::select! {
+            _ = shutdown.changed() => break,
+            accepted = accept(&listener, &auth) => accepted,
+        };
+        // Forget clients that have already gone
+        while clients.try_join_next().is_some() {}
+
+        match accepted {
+            Ok(Some(connection)) => {
+                let app = app.clone();
+                let auth = auth.clone();
+                let shutdown = shutdown.clone();
+                clients.spawn(async move {
+                    let result = match connection {
+                        McpConnection::Ipc(stream) => serve_stream(&app, stream, None, shutdown).await,
+                        McpConnection::Tcp(stream) => serve_stream(&app, stream, Some(&auth), shutdown).await,
+                        McpConnection::Http(stream) => serve_http(&app, &auth, stream, shutdown).await,
+                    };
+                    if let Err(e) = result {
+                        info!("[TAURI_MCP] MCP client disconnected: {}", e);
+                    }
+                });
+            }
+            // Rejected peer
+            Ok(None) => {}
+            Err(e) => {
+                error!("[TAURI_MCP] Error accepting MCP connection: {}", e);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+            }
+        }
+    }
+
+    drop(listener);
+    if let Some(path) = socket_file
+        && path.exists()
+    {
+        let _ = std::fs::remove_file(&path);
+    }
+    info!("[TAURI_MCP] MCP listener stopped, closing {} connection(s)", clients.len());
+    while clients.join_next().await.is_some() {}
+}
+
+async fn accept(listener: &McpListener, auth: &TcpAuth) -> std::io::Result<Option<McpConnection>> {
+    let (stream, addr) = match listener {
+        McpListener::Ipc(listener) => {
+            let stream = listener.accept().await?;
+            info!("[TAURI_MCP] Accepted new MCP IPC connection");
+            return Ok(Some(McpConnection::Ipc(stream)));
+        }
+        McpListener::Tcp(listener) | McpListener::Http(listener) => listener.accept().await?,
+    };
+    if !auth.peer_allowed(&addr) {
+        warn!("[TAURI_MCP] Rejected MCP connection from disallowed peer: {}", addr);
+        return Ok(None);
+    }
+    info!("[TAURI_MCP] Accepted new MCP connection from: {}", addr);
+    Ok(Some(match listener {
+        McpListener::Http(_) => McpConnection::Http(stream),
+        _ => McpConnection::Tcp(stream),
+    }))
+}
+
+// Run one message through the protocol, executing tool calls against the app
+async fn handle<R: Runtime>(app: &AppHandle<R>, message: &str) -> Option<Value> {
+    let allow_all = CommandPolicy::default();
+    let policy = app.try_state::<CommandPolicy>();
+    let policy = policy.as_ref().map_or(&allow_all, |policy| policy.inner());
+    protocol::handle_message(message, policy, |tool, arguments| async move {
+        tools::call_tool(app, tool, arguments).await
+    })
+    .await
+}
+
+// A socket connection; TCP clients (`auth` set) run the same `auth` handshake line as the
+// plugin socket before any JSON-RPC
+async fn serve_stream<R: Runtime, S: AsyncRead + AsyncWrite>(
+    app: &AppHandle<R>,
+    stream: S,
+    auth: Option<&TcpAuth>,
+    shutdown: watch::Receiver<bool>,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    if let Some(auth) = auth
+        && !auth.handshake(&mut reader, &mut writer).await?
+    {
+        info!("[TAURI_MCP] Closing unauthenticated MCP connection");
+        return Ok(());
+    }
+
+    serve_lines(app, reader, writer, shutdown).await
+}
+
+// Newline-delimited JSON-RPC, used by the stdio and socket transports. Ends when the client
+// disconnects or the server shuts down.
+async fn serve_lines<R: Runtime, B: AsyncBufRead + Unpin, W: AsyncWrite + Unpin>(
+    app: &AppHandle<R>,
+    mut reader: B,
+    mut writer: W,
+    mut shutdown: watch::Receiver<bool>,
+) -> std::io::Result<()> {
+    loop {
+        let line = tokio::select! {
+            _ = shutdown.changed() => return Ok(()),
+            line = read_line(&mut reader, MAX_MESSAGE_BYTES) => line?,
+        };
+        let Some(line) = line else {
+            return Ok(());
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(reply) = handle(app, line.trim()).await {
+            writer.write_all(reply.to_string().as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await?;
+        }
+    }
+}
+
+// MCP streamable-HTTP: JSON-RPC messages are POSTed to /mcp and answered with
+// application/json. No server-initiated SSE stream is offered, so GET gets 405.
+async fn serve_http<R: Runtime>(
+    app: &AppHandle<R>,
+    auth: &TcpAuth,
+    stream: TcpStream,
+    mut shutdown: watch::Receiver<bool>,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        let head = tokio::select! {
+            _ = shutdown.changed() => return Ok(()),
+            head = http::read_head(&mut reader) => head,
+        };
+        let head = match head {
+            Ok(Some(head)) => head,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                let _ = http::write_response(&mut writer, 400, "Bad Request", None, true).await;
+                return Err(e);
+            }
+        };
+        let close = head.wants_close();
+
+        // Refused before the body is read, so an unauthenticated client can't make us buffer
+        // one; the unread body leaves the connection unusable, so it is closed
+        let refusal = if head.path != http::MCP_PATH {
+            Some((404, "Not Found"))
+        } else if !http::is_allowed_origin(head.header("origin")) {
+            Some((403, "Forbidden"))
+        } else if !auth.check_bearer(head.header("authorization")) {
+            Some((401, "Unauthorized"))
+        } else if head.method != "POST" {
+            Some((405, "Method Not Allowed"))
+        } else {
+            None
+        };
+        if let Some((status, reason)) = refusal {
+            return http::write_response(&mut writer, status, reason, None, true).await;
+        }
+
+        let body = match http::read_body(&mut reader, &head).await {
+            Ok(body) => body,
+            Err(e) => {
+                let _ = http::write_response(&mut writer, 400, "Bad Request", None, true).await;
+                return Err(e);
+            }
+        };
+        let body = String::from_utf8_lossy(&body);
+        match handle(app, &body).await {
+            Some(reply) => http::write_response(&mut writer, 200, "OK", Some(&reply.to_string()), close).await?,
+            // Notifications and responses are acknowledged without a body
+            None => http::write_response(&mut writer, 202, "Accepted", None, close).await?,
+        }
+
+        if close {
+            return Ok(());
+        }
+    }
+}
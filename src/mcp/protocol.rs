@@ -0,0 +1,226 @@
+use log::info;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::future::Future;
+
+use super::tools::{TOOLS, ToolDefinition, find_tool};
+use crate::policy::CommandPolicy;
+
+/// Protocol revisions this server understands, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcRequest {
+    jsonrpc: String,
+    /// Absent for notifications
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallToolParams {
+    name: String,
+    #[serde(default)]
+    arguments: Option<Value>,
+}
+
+/// Handle one framed JSON-RPC message (a single request or a batch).
+///
+/// `tools/list` only offers the tools `policy` allows. `call_tool` runs a validated `tools/call`.
+/// Returns the reply to send back, or `None` when the message only contained notifications or responses.
+pub async fn handle_message<F, Fut>(message: &str, policy: &CommandPolicy, call_tool: F) -> Option<Value>
+where
+    F: Fn(&'static ToolDefinition, Value) -> Fut,
+    Fut: Future<Output = Value>,
+{
+    let value: Value = match serde_json::from_str(message) {
+        Ok(value) => value,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e))),
+    };
+
+    match value {
+        Value::Array(batch) => {
+            if batch.is_empty() {
+                return Some(error_response(Value::Null, INVALID_REQUEST, "Empty batch"));
+            }
+
+            let mut replies = Vec::new();
+            for item in batch {
+                if let Some(reply) = handle_single(item, policy, &call_tool).await {
+                    replies.push(reply);
+                }
+            }
+            if replies.is_empty() { None } else { Some(Value::Array(replies)) }
+        }
+        other => handle_single(other, policy, &call_tool).await,
+    }
+}
+
+async fn handle_single<F, Fut>(value: Value, policy: &CommandPolicy, call_tool: &F) -> Option<Value>
+where
+    F: Fn(&'static ToolDefinition, Value) -> Fut,
+    Fut: Future<Output = Value>,
+{
+    // Replies from the client (we never send requests) need no answer
+    if value.get("method").is_none() && (value.get("result").is_some() || value.get("error").is_some()) {
+        return None;
+    }
+
+    let request: JsonRpcRequest = match serde_json::from_value(value.clone()) {
+        Ok(request) => request,
+        Err(e) => {
+            let id = value.get("id").cloned().unwrap_or(Value::Null);
+            return Some(error_response(id, INVALID_REQUEST, &format!("Invalid request: {}", e)));
+        }
+    };
+
+    if request.jsonrpc != "2.0" {
+        let id = request.id.unwrap_or(Value::Null);
+        return Some(error_response(id, INVALID_REQUEST, "Invalid request: jsonrpc must be \"2.0\""));
+    }
+
+    let Some(id) = request.id else {
+        info!("[TAURI_MCP] MCP notification: {}", request.method);
+        return None;
+    };
+
+    info!("[TAURI_MCP] MCP request: {}", request.method);
+    let params = request.params.unwrap_or_else(|| json!({}));
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(initialize_result(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": list_tools(policy) })),
+        "tools/call" => match serde_json::from_value::<CallToolParams>(params) {
+            Ok(params) => match find_tool(&params.name) {
+                Some(tool) => {
+                    let arguments = params.arguments.unwrap_or_else(|| json!({}));
+                    Ok(call_tool(tool, arguments).await)
+                }
+                None => Err((INVALID_PARAMS, format!("Unknown tool: {}", params.name))),
+            },
+            Err(e) => Err((INVALID_PARAMS, format!("Invalid params for tools/call: {}", e))),
+        },
+        method => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+// Tools the policy lets run; denied ones would only fail when called
+fn list_tools(policy: &CommandPolicy) -> Vec<Value> {
+    TOOLS
+        .iter()
+        .filter(|tool| policy.is_command_allowed(tool.name))
+        .map(ToolDefinition::to_json)
+        .collect()
+}
+
+// Agree on the client's protocol version when we support it, otherwise offer our latest
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+        },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tools::error_result;
+
+    fn handle(message: &str) -> Option<Value> {
+        handle_with_policy(message, &CommandPolicy::default())
+    }
+
+    fn handle_with_policy(message: &str, policy: &CommandPolicy) -> Option<Value> {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(handle_message(message, policy, |tool, arguments| async move {
+            json!({ "content": [{ "type": "text", "text": format!("{} {}", tool.name, arguments) }], "isError": false })
+        }))
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version() {
+        let reply = handle(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#).unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(reply["result"]["serverInfo"]["name"], "tauri-plugin-mcp");
+
+        let reply = handle(r#"{"jsonrpc":"2.0","id":2,"method":"initialize","params":{"protocolVersion":"1999-01-01"}}"#).unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], SUPPORTED_PROTOCOL_VERSIONS[0]);
+    }
+
+    #[test]
+    fn test_notifications_get_no_reply() {
+        assert!(handle(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).is_none());
+        assert!(handle(r#"[{"jsonrpc":"2.0","method":"notifications/initialized"}]"#).is_none());
+    }
+
+    #[test]
+    fn test_tools_list_and_call() {
+        let reply = handle(r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#).unwrap();
+        assert_eq!(reply["result"]["tools"].as_array().unwrap().len(), TOOLS.len());
+
+        let reply = handle(r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_dom","arguments":{"window_label":"main"}}}"#).unwrap();
+        assert_eq!(reply["result"]["content"][0]["text"], r#"get_dom {"window_label":"main"}"#);
+
+        let reply = handle(r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"nope"}}"#).unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_tools_list_follows_policy() {
+        let policy = CommandPolicy::read_only();
+        let reply = handle_with_policy(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#, &policy).unwrap();
+        let names: Vec<&str> = reply["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+
+        assert!(names.contains(&"get_dom"));
+        assert!(!names.contains(&"execute_js"));
+        assert!(names.iter().all(|name| policy.is_command_allowed(name)));
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        assert_eq!(handle("{not json").unwrap()["error"]["code"], PARSE_ERROR);
+        assert_eq!(handle(r#"{"jsonrpc":"1.0","id":1,"method":"ping"}"#).unwrap()["error"]["code"], INVALID_REQUEST);
+        assert_eq!(handle(r#"{"jsonrpc":"2.0","id":1,"method":"resources/list"}"#).unwrap()["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(error_result("x")["isError"], true);
+    }
+}
@@ -0,0 +1,502 @@
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::ErrorInfo;
+use crate::models::{
+    MouseMovementRequest, MouseMovementResponse, ScreenshotRequest, ScreenshotResponse, TextInputRequest,
+    TextInputResponse, WindowListRequest, WindowListResponse, WindowManagerRequest, WindowManagerResponse,
+};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools;
+use crate::tools::accessibility::{AccessibilitySnapshot, GetAccessibilitySnapshotRequest};
+use crate::tools::click_element::{ClickElementRequest, ClickElementResponse};
+use crate::tools::console_logs::{GetConsoleLogsRequest, GetConsoleLogsResponse};
+use crate::tools::execute_js::{ExecuteJsRequest, ExecuteJsResponse};
+use crate::tools::iframe_rpc::{IframeRpcRequest, IframeRpcResponse};
+use crate::tools::network_requests::{
+    ClearNetworkRequestsRequest, ClearNetworkRequestsResponse, GetNetworkRequestsRequest, GetNetworkRequestsResponse,
+};
+use crate::tools::pointer_actions::{PointerActionRequest, PointerActionResponse};
+use crate::tools::press_keys::{PressKeysRequest, PressKeysResponse};
+use crate::tools::storage::StorageRequest;
+use crate::tools::wait_for::{WaitForRequest, WaitForResponse};
+use crate::tools::webview::{
+    CaptureScreenshotPayload, GetDomPayload, GetDomResponse, GetElementPositionPayload, GetElementPositionResponse,
+    SendTextToElementPayload, SendTextToElementResponse,
+};
+
+/// An MCP tool backed by one of the socket commands in `tools::handle_command`
+pub struct ToolDefinition {
+    /// Tool name, identical to the socket command it runs
+    pub name: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// JSON Schema generated from the command's payload struct
+    input_schema: fn() -> Value,
+    /// JSON Schema of the response `data`, for commands whose result is a Rust struct
+    result_schema: Option<fn() -> Value>,
+    read_only: bool,
+    destructive: bool,
+    idempotent: bool,
+    /// Whether a successful result carries a data URL to return as an image block
+    returns_image: bool,
+}
+
+impl ToolDefinition {
+    pub fn input_schema(&self) -> Value {
+        (self.input_schema)()
+    }
+
+    pub fn result_schema(&self) -> Option<Value> {
+        self.result_schema.map(|schema| schema())
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The tool as listed by `tools/list`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "title": self.title,
+            "description": self.description,
+            "inputSchema": (self.input_schema)(),
+            "annotations": {
+                "title": self.title,
+                "readOnlyHint": self.read_only,
+                "destructiveHint": self.destructive,
+                "idempotentHint": self.idempotent,
+                "openWorldHint": false,
+            },
+        })
+    }
+}
+
+pub static TOOLS: &[ToolDefinition] = &[
+    ToolDefinition {
+        name: commands::TAKE_SCREENSHOT,
+        title: "Capture Screenshot of a Specific Application Window",
+        description: "Captures a native OS-level screenshot of the application window, including window decorations. \
+            Requires screen capture permission where the platform enforces it (macOS Screen Recording) and a visible window. \
+            Returns a JPEG image.",
+        input_schema: schema::<ScreenshotRequest>,
+        result_schema: Some(schema::<ScreenshotResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: true,
+    },
+    ToolDefinition {
+        name: commands::CAPTURE_SCREENSHOT,
+        title: "Capture Webview Content (No Permissions Required)",
+        description: "Captures the content of a webview by rendering it from JavaScript. Needs no OS permissions and works \
+            while the app is minimized or covered, but complex CSS may render approximately and window decorations are not included. \
+            Returns a JPEG image.",
+        input_schema: schema::<CaptureScreenshotPayload>,
+        result_schema: Some(schema::<ScreenshotResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: true,
+    },
+    ToolDefinition {
+        name: commands::EXECUTE_JS,
+        title: "Execute JavaScript Code in Specified Application Window",
+        description: "Executes JavaScript in a webview and returns the result of the last expression as JSON. \
+            Returned Promises are awaited (subject to timeout_ms); cycles, DOM nodes, Maps, Sets and Errors come back \
+            as objects tagged with a \"$type\" field.",
+        input_schema: schema::<ExecuteJsRequest>,
+        result_schema: Some(schema::<ExecuteJsResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::GET_CONSOLE_LOGS,
+        title: "Read Console Logs and Uncaught Errors from Application Window",
+        description: "Returns what a webview has logged: console.log/info/warn/error/debug calls, uncaught errors and \
+            unhandled Promise rejections, oldest first, each with level, timestamp, message, JSON arguments and stack. \
+            Filter by levels, since (epoch ms) and a case-insensitive text pattern; set clear to empty the buffer so \
+            the next call only shows new output.",
+        input_schema: schema::<GetConsoleLogsRequest>,
+        result_schema: Some(schema::<GetConsoleLogsResponse>),
+        read_only: false,
+        destructive: false,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::GET_NETWORK_REQUESTS,
+        title: "Inspect Network Requests and Tauri Invokes from Application Window",
+        description: "Returns the fetch and XMLHttpRequest calls and Tauri invokes a webview has made, oldest first, with \
+            method, URL (or command name for invokes), request and response bodies (truncated), status, timings and \
+            errors. Filter by kinds, method, url_pattern, failed_only and since (epoch ms) to match backend calls to \
+            the UI action that triggered them.",
+        input_schema: schema::<GetNetworkRequestsRequest>,
+        result_schema: Some(schema::<GetNetworkRequestsResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::CLEAR_NETWORK_REQUESTS,
+        title: "Clear Recorded Network Requests in Application Window",
+        description: "Empties a webview's recorded network requests, so the next get_network_requests call only shows \
+            requests made afterwards. Returns how many records were cleared.",
+        input_schema: schema::<ClearNetworkRequestsRequest>,
+        result_schema: Some(schema::<ClearNetworkRequestsResponse>),
+        read_only: false,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::GET_DOM,
+        title: "Retrieve HTML DOM Content from Application Window",
+        description: "Returns the HTML of a webview's document. Without options this is the full document as a string. \
+            With root, max_depth, strip, visible_only or max_bytes the result is a page object \
+            { html, offset, totalBytes, nextCursor }; pass nextCursor back as cursor to get the next page.",
+        input_schema: schema::<GetDomPayload>,
+        result_schema: Some(schema::<GetDomResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::GET_ACCESSIBILITY_SNAPSHOT,
+        title: "Get Accessibility Snapshot of Application Window",
+        description: "Returns a compact accessibility tree of a webview: roles, accessible names, values, states \
+            (checked, disabled, expanded, focused, ...) and bounding boxes, with hidden elements dropped and generic \
+            containers flattened. Every element has a ref such as \"e12\" that get_element_position and \
+            send_text_to_element accept with selector_type \"ref\". Prefer it over get_dom for understanding a page.",
+        input_schema: schema::<GetAccessibilitySnapshotRequest>,
+        result_schema: Some(schema::<AccessibilitySnapshot>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::IFRAME_RPC,
+        title: "Call RPC Method on Preview Iframe",
+        description: "Calls an application-defined RPC method on the preview iframe through the app shell \
+            ('iframe-rpc' / 'iframe-rpc-response' events) and returns its result.",
+        input_schema: schema::<IframeRpcRequest>,
+        result_schema: Some(schema::<IframeRpcResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::MANAGE_WINDOW,
+        title: "Control Application Window State and Geometry",
+        description: "Changes the state or geometry of a native window: focus, minimize, maximize, unmaximize, close, \
+            show, hide, setPosition, setSize, center or toggleFullscreen.",
+        input_schema: schema::<WindowManagerRequest>,
+        result_schema: Some(schema::<WindowManagerResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::LIST_WINDOWS,
+        title: "List Application Windows and Webviews",
+        description: "Lists every window with its label, title, state (visible, focused, maximized, fullscreen), position, \
+            size, scale factor and monitor, plus the webviews each window hosts with their labels, URLs and bounds. \
+            Use it to find the labels other tools accept.",
+        input_schema: schema::<WindowListRequest>,
+        result_schema: Some(schema::<WindowListResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::MANAGE_STORAGE,
+        title: "Manage Webview Storage, Cookies and IndexedDB",
+        description: "Reads and modifies a webview's localStorage, sessionStorage, cookies or IndexedDB. \
+            Web storage and cookies support get, set, remove, clear and keys; cookie get and keys also list the \
+            webview's native cookies for its URL, including HttpOnly ones. IndexedDB adds databases and \
+            object_stores, and reads or writes records of an object store.",
+        input_schema: schema::<StorageRequest>,
+        // Free-form: the result depends on the store and action (see README)
+        result_schema: None,
+        read_only: false,
+        destructive: true,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::SIMULATE_TEXT_INPUT,
+        title: "Simulate Keyboard Text Input into Focused Field",
+        description: "Types text into the currently focused element using OS-level key simulation. \
+            The target must already have focus; use send_text_to_element to target an element by selector.",
+        input_schema: schema::<TextInputRequest>,
+        result_schema: Some(schema::<TextInputResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::PRESS_KEYS,
+        title: "Press Keys and Shortcuts",
+        description: "Presses a sequence of key chords such as \"Mod+Shift+P\", \"Escape\" or \"ArrowDown\". \
+            Mod is Cmd on macOS and Ctrl elsewhere. OS key presses go to the focused target window; with dom, \
+            KeyboardEvents are dispatched to an element instead, without focus but also without default actions.",
+        input_schema: schema::<PressKeysRequest>,
+        result_schema: Some(schema::<PressKeysResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::SIMULATE_MOUSE_MOVEMENT,
+        title: "Simulate Mouse Cursor Movement",
+        description: "Moves the system cursor to a point in a webview, optionally clicking. Coordinates are measured \
+            from the webview's top-left corner in CSS pixels, or device pixels with unit \"physical\". \
+            Use get_element_position to find the coordinates of an element.",
+        input_schema: schema::<MouseMovementRequest>,
+        result_schema: Some(schema::<MouseMovementResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::POINTER_ACTION,
+        title: "Pointer Action",
+        description: "Moves the system pointer to a point in the window or an element's center and hovers, clicks, \
+            double- or triple-clicks, presses or releases a button, drags to a second target, or turns the scroll wheel. \
+            Element targets are scrolled into view first.",
+        input_schema: schema::<PointerActionRequest>,
+        result_schema: Some(schema::<PointerActionResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::GET_ELEMENT_POSITION,
+        title: "Find Element and Get Position",
+        description: "Finds an element by id, class, tag, text, CSS, XPath, ARIA role, test id or snapshot ref and \
+            returns its tag, id, classes and center in webview coordinates. Optionally clicks it. A selector that matches several \
+            elements fails and lists the candidates unless nth picks one; within searches inside iframes and shadow roots.",
+        input_schema: schema::<GetElementPositionPayload>,
+        result_schema: Some(schema::<GetElementPositionResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: true,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::SEND_TEXT_TO_ELEMENT,
+        title: "Send Text to Element",
+        description: "Finds an input, textarea or contentEditable element by selector and types text into it. \
+            In React UIs the DOM may update without the component state following.",
+        input_schema: schema::<SendTextToElementPayload>,
+        result_schema: Some(schema::<SendTextToElementResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::CLICK_ELEMENT,
+        title: "Click Element",
+        description: "Finds an element by selector, scrolls it into view, checks that nothing covers its center, and clicks it \
+            with DOM events or, with mode \"os\", the real system pointer. Reports the element that received the click.",
+        input_schema: schema::<ClickElementRequest>,
+        result_schema: Some(schema::<ClickElementResponse>),
+        read_only: false,
+        destructive: true,
+        idempotent: false,
+        returns_image: false,
+    },
+    ToolDefinition {
+        name: commands::WAIT_FOR,
+        title: "Wait for Condition",
+        description: "Polls the webview until an element is visible, hidden or enabled, text appears, the URL matches, \
+            a JavaScript predicate returns a truthy value, or the network has been idle for a while. \
+            Fails with the last observed state when the timeout expires.",
+        input_schema: schema::<WaitForRequest>,
+        result_schema: Some(schema::<WaitForResponse>),
+        read_only: true,
+        destructive: false,
+        idempotent: true,
+        returns_image: false,
+    },
+];
+
+/// Look up a tool by name
+pub fn find_tool(name: &str) -> Option<&'static ToolDefinition> {
+    TOOLS.iter().find(|tool| tool.name == name)
+}
+
+/// Run a tool through the socket command handler and convert the outcome into a `CallToolResult`
+pub async fn call_tool<R: Runtime>(app: &AppHandle<R>, tool: &ToolDefinition, arguments: Value) -> Value {
+    let response = match tools::handle_command(app, tool.name, arguments).await {
+        Ok(response) => response,
+        Err(e) => SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.into_info()),
+            id: None,
+        },
+    };
+
+    tool_result(tool, response)
+}
+
+// Convert a socket response into MCP content blocks
+fn tool_result(tool: &ToolDefinition, response: SocketResponse) -> Value {
+    if !response.success {
+        return match response.error {
+            Some(error) => failure_result(&error),
+            None => error_result("Command failed"),
+        };
+    }
+
+    let data = response.data.unwrap_or(Value::Null);
+
+    if tool.returns_image {
+        // Screenshot commands wrap the data URL in a ScreenshotResponse
+        if data.get("success").and_then(Value::as_bool) == Some(false) {
+            return error_result(data.get("error").and_then(Value::as_str).unwrap_or("Screenshot failed"));
+        }
+
+        let data_url = data.get("data").and_then(Value::as_str).or_else(|| data.as_str());
+        return match data_url.and_then(split_data_url) {
+            Some((mime_type, base64)) => json!({
+                "content": [{ "type": "image", "data": base64, "mimeType": mime_type }],
+                "isError": false,
+            }),
+            None => error_result("Failed to extract image data from response"),
+        };
+    }
+
+    let text = match &data {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    };
+
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": false,
+    })
+}
+
+/// A `CallToolResult` reporting a failed tool execution
+pub fn error_result(message: &str) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": message }],
+        "isError": true,
+    })
+}
+
+// The error code leads the message; details follow as JSON so the model can act on them
+fn failure_result(error: &ErrorInfo) -> Value {
+    let code = json!(error.code);
+    let mut content = vec![json!({
+        "type": "text",
+        "text": format!("{}: {}", code.as_str().unwrap_or("internal"), error.message)
+    })];
+    if let Some(details) = &error.details {
+        content.push(json!({ "type": "text", "text": details.to_string() }));
+    }
+    json!({ "content": content, "isError": true })
+}
+
+// Split "data:image/jpeg;base64,...." into its MIME type and base64 payload
+fn split_data_url(data_url: &str) -> Option<(&str, &str)> {
+    let rest = data_url.strip_prefix("data:")?;
+    let (header, data) = rest.split_once(',')?;
+    let mime_type = header.strip_suffix(";base64")?;
+    Some((mime_type, data))
+}
+
+/// Generate an inline JSON Schema for a payload or result struct
+pub(crate) fn schema<T: JsonSchema>() -> Value {
+    let settings = SchemaSettings::draft07().with(|s| {
+        s.inline_subschemas = true;
+        s.option_add_null_type = false;
+    });
+    let root = settings.into_generator().into_root_schema_for::<T>();
+
+    let mut value = serde_json::to_value(root).unwrap_or_else(|_| json!({ "type": "object" }));
+    if let Some(object) = value.as_object_mut() {
+        object.remove("$schema");
+        object.remove("title");
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_tool_schemas_are_objects() {
+        for tool in TOOLS {
+            let schema = (tool.input_schema)();
+            assert_eq!(schema["type"], "object", "schema for {}", tool.name);
+            assert!(find_tool(tool.name).is_some());
+        }
+
+        let schema = (find_tool(commands::EXECUTE_JS).unwrap().input_schema)();
+        assert_eq!(schema["required"], json!(["code"]));
+        assert_eq!(schema["properties"]["code"]["type"], "string");
+
+        // Element commands flatten the shared selector into their own fields
+        let schema = (find_tool(commands::GET_ELEMENT_POSITION).unwrap().input_schema)();
+        assert_eq!(schema["required"], json!(["selector_type", "selector_value"]));
+        assert_eq!(schema["properties"]["selector_type"]["oneOf"].as_array().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_screenshot_result_is_image_block() {
+        let tool = find_tool(commands::TAKE_SCREENSHOT).unwrap();
+        let response = SocketResponse {
+            success: true,
+            data: Some(json!({ "data": "data:image/jpeg;base64,AAAA", "success": true, "error": null })),
+            error: None,
+            id: None,
+        };
+
+        let result = tool_result(tool, response);
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"][0]["type"], "image");
+        assert_eq!(result["content"][0]["mimeType"], "image/jpeg");
+        assert_eq!(result["content"][0]["data"], "AAAA");
+    }
+
+    #[test]
+    fn test_failed_command_is_error_result() {
+        let tool = find_tool(commands::GET_DOM).unwrap();
+        let response = SocketResponse {
+            success: false,
+            data: None,
+            error: Some(
+                ErrorInfo::new(ErrorCode::WindowNotFound, "Webview 'main' not found")
+                    .with_details(json!({ "windows": ["settings"] })),
+            ),
+            id: None,
+        };
+
+        let result = tool_result(tool, response);
+        assert_eq!(result["isError"], true);
+        assert_eq!(result["content"][0]["text"], "window_not_found: Webview 'main' not found");
+        assert_eq!(result["content"][1]["text"], r#"{"windows":["settings"]}"#);
+    }
+}
@@ -1,12 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PingRequest {
     pub value: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PingResponse {
     pub value: Option<String>,
@@ -42,25 +43,86 @@ pub struct WindowControlResponse {
     pub message: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowListRequest {}
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowListResponse {
     pub windows: Vec<WindowInfo>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+/// A window's state. Fields the platform fails to report for a window are `None`.
 pub struct WindowInfo {
     pub label: String,
-    pub title: String,
-    pub is_visible: bool,
-    pub is_focused: bool,
-    pub is_maximized: bool,
-    pub is_fullscreen: bool,
+    pub title: Option<String>,
+    pub is_visible: Option<bool>,
+    pub is_focused: Option<bool>,
+    pub is_maximized: Option<bool>,
+    pub is_fullscreen: Option<bool>,
+    /// Outer position of the window on the desktop, including decorations, in physical pixels
+    pub position: Option<ScreenPosition>,
+    /// Outer size of the window, including decorations, in physical pixels
+    pub size: Option<ScreenSize>,
+    pub scale_factor: Option<f64>,
+    /// Monitor the window is currently on
+    pub monitor: Option<MonitorInfo>,
+    /// Webviews hosted by this window. A plain `WebviewWindow` has one webview with the window's label.
+    pub webviews: Vec<WebviewInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewInfo {
+    pub label: String,
+    /// Label of the window hosting this webview
+    pub window_label: String,
+    pub url: Option<String>,
+    /// Position relative to the window's content area, in physical pixels
+    pub position: Option<ScreenPosition>,
+    pub size: Option<ScreenSize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub position: ScreenPosition,
+    pub size: ScreenSize,
+    pub scale_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub struct ScreenPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub struct ScreenSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<tauri::PhysicalPosition<i32>> for ScreenPosition {
+    fn from(position: tauri::PhysicalPosition<i32>) -> Self {
+        Self {
+            x: position.x,
+            y: position.y,
+        }
+    }
+}
+
+impl From<tauri::PhysicalSize<u32>> for ScreenSize {
+    fn from(size: tauri::PhysicalSize<u32>) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+        }
+    }
 }
 
 // New MCP JavaScript execution models
@@ -112,9 +174,10 @@ pub struct TypeTextRequest {
 }
 
 // Screenshot request - updated to use shared interface
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ScreenshotRequest {
+    /// Label of the window to capture
     pub window_label: String,
 }
 
@@ -130,7 +193,7 @@ impl From<ScreenshotRequest> for crate::shared::ScreenshotParams {
     }
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ScreenshotResponse {
     pub data: Option<String>, // Base64 encoded image
@@ -179,67 +242,104 @@ pub struct HtmlResponse {
     pub error: Option<String>,
 }
 
-// LocalStorage request model
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalStorageRequest {
-    pub action: String,
-    pub key: Option<String>,
-    pub value: Option<String>,
-    pub window_label: Option<String>,
-}
-
 // Window manager request model
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct WindowManagerRequest {
+    /// Label of the window to control (defaults to the configured default target)
     pub window_label: Option<String>,
+    /// One of "focus", "minimize", "maximize", "unmaximize", "close", "show", "hide",
+    /// "setPosition", "setSize", "center" or "toggleFullscreen"
     pub operation: String,
+    /// X coordinate for setPosition
     pub x: Option<i32>,
+    /// Y coordinate for setPosition
     pub y: Option<i32>,
+    /// Width for setSize
     pub width: Option<u32>,
+    /// Height for setSize
     pub height: Option<u32>,
 }
 
 // Window manager response model
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct WindowManagerResponse {
     pub success: bool,
     pub error: Option<String>,
 }
 
 // TextInput request model
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TextInputRequest {
+    /// Text to type into the focused element
     pub text: String,
+    /// Delay between keystrokes in milliseconds
     pub delay_ms: Option<u64>,
+    /// Delay before typing starts in milliseconds
     pub initial_delay_ms: Option<u64>,
 }
 
 // TextInput response model
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TextInputResponse {
     pub chars_typed: u32,
     pub duration_ms: u64,
 }
 
+/// Pixel unit of coordinates given relative to a webview
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateUnit {
+    /// CSS pixels, as the page and get_element_position report them
+    #[default]
+    Logical,
+    /// Device pixels, as in screenshots taken at full resolution
+    Physical,
+}
+
 // Mouse movement request model
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MouseMovementRequest {
+    /// Label of the window or webview the coordinates are relative to (defaults to the configured default target)
+    #[serde(rename = "window_label")]
+    pub window_label: Option<String>,
+    /// Target X coordinate from the webview's left edge, or an offset in screen pixels when relative
     pub x: i32,
+    /// Target Y coordinate from the webview's top edge, or an offset in screen pixels when relative
     pub y: i32,
+    /// Unit of x and y: "logical" CSS pixels (default) or "physical" device pixels
+    pub unit: Option<CoordinateUnit>,
+    /// Treat x and y as offsets from the current cursor position
     pub relative: Option<bool>,
+    /// Click after moving
     pub click: Option<bool>,
+    /// Button to click: "left", "right" or "middle"
     pub button: Option<String>, // "left", "right", or "middle"
 }
 
 // Mouse movement response model
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MouseMovementResponse {
     pub success: bool,
     pub duration_ms: u64,
     pub position: Option<(i32, i32)>,
 }
+
+// Socket server status model
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketServerStatus {
+    /// Whether the server is accepting connections
+    pub running: bool,
+    /// "ipc" or "tcp"
+    pub transport: String,
+    /// Socket path or host:port the server listens on, or would listen on once started
+    pub address: String,
+    /// Clients currently connected
+    pub connections: usize,
+    /// Commands currently running across all clients
+    pub commands_in_flight: usize,
+}
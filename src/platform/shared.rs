@@ -1,4 +1,3 @@
-use crate::desktop::create_error_response;
 use crate::models::ScreenshotResponse;
 use crate::{Error, Result};
 use tauri::Runtime;
@@ -8,20 +7,15 @@ pub async fn handle_screenshot_task<F>(screenshot_fn: F) -> Result<ScreenshotRes
 where
     F: FnOnce() -> Result<ScreenshotResponse> + Send + 'static,
 {
-    // Execute the platform-specific screenshot function in a blocking task
-    let result = tokio::task::spawn_blocking(screenshot_fn)
+    // Execute the platform-specific screenshot function in a blocking task; a failed capture
+    // stays an error, so the socket response carries its code
+    tokio::task::spawn_blocking(screenshot_fn)
         .await
-        .map_err(|e| Error::WindowOperationFailed(format!("Task join error: {}", e)))?;
-
-    // Handle the result consistently across platforms
-    match result {
-        Ok(response) => Ok(response),
-        Err(e) => Ok(create_error_response(format!("{}", e))),
-    }
+        .map_err(|e| Error::WindowOperationFailed(format!("Task join error: {}", e)))?
 }
 
 // Helper function to get window title - used by multiple platforms
-pub fn get_window_title<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Result<String> {
+pub fn get_window_title<R: Runtime>(window: &tauri::Window<R>) -> Result<String> {
     match window.title() {
         Ok(title) => Ok(title),
         Err(e) => Err(Error::WindowOperationFailed(format!(
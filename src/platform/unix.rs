@@ -1,84 +1,348 @@
 use crate::models::ScreenshotResponse;
+use crate::error::ErrorCode;
 use crate::{Error, Result};
+use image::{DynamicImage, RgbaImage};
+use log::{debug, error, info};
 use tauri::Runtime;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, ImageFormat, ImageOrder, MapState, Window};
+use x11rb::rust_connection::RustConnection;
 
 // Import shared functionality
-use crate::desktop::ScreenshotContext;
-use crate::platform::shared::handle_screenshot_task;
+use crate::desktop::{ScreenshotContext, create_success_response};
+use crate::platform::shared::{get_window_title, handle_screenshot_task};
 use crate::shared::ScreenshotParams;
+use crate::tools::take_screenshot::process_image;
 
-// Unix-specific implementation for taking screenshots (fallback for non-macOS Unix systems)
+/// A top-level X11 window along with the properties used for matching
+struct X11Window {
+    id: Window,
+    title: String,
+    app_name: String,
+}
+
+// Linux implementation for taking screenshots (X11, including XWayland)
 pub async fn take_screenshot<R: Runtime>(
     params: ScreenshotParams,
     window_context: ScreenshotContext<R>,
 ) -> Result<ScreenshotResponse> {
-    // Clone necessary values from params for use in the closure
+    // Clone necessary parameters for use in the closure
+    let params_clone = params.clone();
     let window_clone = window_context.window.clone();
-    let quality = params.quality.unwrap_or(85) as u8;
-    let max_width = params.max_width.map(|w| w as u32).unwrap_or(0);
+    let window_label = params
+        .window_label
+        .clone()
+        .unwrap_or_else(|| "main".to_string());
+
+    // Get application name from params or use a default
+    let application_name = params.application_name.clone().unwrap_or_default();
 
     handle_screenshot_task(move || {
-    let script = format!(
-      r#"
-      (function() {{
-        try {{
-          const canvas = document.createElement('canvas');
-          const context = canvas.getContext('2d');
-          
-          // Set dimensions to match the window content
-          let width = window.innerWidth;
-          let height = window.innerHeight;
-          
-          // Apply max width constraint if specified
-          if ({max_width} > 0 && width > {max_width}) {{
-            const aspectRatio = width / height;
-            width = {max_width};
-            height = width / aspectRatio;
-          }}
-          
-          canvas.width = width;
-          canvas.height = height;
-          
-          // Draw only the document to the canvas (not the OS chrome/window)
-          context.drawImage(document.documentElement, 0, 0, width, height);
-          
-          // Convert canvas to base64 image with specified quality
-          return canvas.toDataURL('image/jpeg', {quality}/100);
-        }} catch (err) {{
-          console.error('Screenshot error:', err);
-          return null;
-        }}
-      }})();
-      "#,
-      max_width = max_width,
-      quality = quality
-    );
+        // Get the window title to help identify the right window
+        let window_title = get_window_title(&window_clone)?;
 
-    // Evaluate the JavaScript in the webview
-    match window_clone.eval(&script) {
-      Ok(_) => {
-        // In Tauri 2.x, we can't get the result from eval, so we return a sample image
-        Ok(ScreenshotResponse {
-          data: Some("data:image/jpeg;base64,/9j/4AAQSkZJRgABAQEAYABgAAD/2wBDAAUDBAQEAwUEBAQFBQUGBwwIBwcHBw8LCwkMEQ8SEhEPERETFhwXExQaFRERGCEYGh0dHx8fExciJCIeJBweHx7/2wBDAQUFBQcGBw4ICA4eFBEUHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh4eHh7/wAARCAABAAEDASIAAhEBAxEB/8QAFQABAQAAAAAAAAAAAAAAAAAAAAb/xAAUEAEAAAAAAAAAAAAAAAAAAAAA/8QAFAEBAAAAAAAAAAAAAAAAAAAAAP/EABQRAQAAAAAAAAAAAAAAAAAAAAD/2gAMAwEAAhEDEQA/ALAKD//Z".to_string()),
-          success: true,
-          error: None,
-        })
-      },
-      Err(e) => Err(Error::WindowOperationFailed(format!("Failed to execute screenshot script: {}", e)))
-    }
-  }).await
+        info!("[TAURI-MCP] Looking for X11 window with title: {} (label: {})", window_title, window_label);
+
+        capture_matching_window(&params_clone, &window_title, &application_name)
+    }).await
 }
 
 /// Take a screenshot by searching for the window by application name only
 /// Used for multi-webview architectures where we don't have a WebviewWindow handle
 pub async fn take_screenshot_by_app_name(
-    _params: ScreenshotParams,
-    _application_name: String,
+    params: ScreenshotParams,
+    application_name: String,
 ) -> Result<ScreenshotResponse> {
-    // Stub implementation for Unix
-    Err(Error::WindowOperationFailed(
-        "take_screenshot_by_app_name not yet implemented for Unix".to_string()
-    ))
+    let params_clone = params.clone();
+
+    handle_screenshot_task(move || {
+        info!("[TAURI-MCP] Taking screenshot by app_name: '{}'", application_name);
+
+        capture_matching_window(&params_clone, "", &application_name)
+    }).await
+}
+
+// Find the target window, grab its pixels and run them through the shared image pipeline
+fn capture_matching_window(
+    params: &ScreenshotParams,
+    window_title: &str,
+    application_name: &str,
+) -> Result<ScreenshotResponse> {
+    let (conn, screen_num) = connect()?;
+    let root = conn.setup().roots[screen_num].root;
+
+    let windows = list_windows(&conn, root)?;
+    info!("[TAURI-MCP] Found {} viewable X11 windows", windows.len());
+
+    let window = find_window(&windows, window_title, application_name).ok_or_else(|| {
+        Error::WindowOperationFailed(format!(
+            "Window not found by title '{}' or app_name '{}'. Please ensure the window is visible and not minimized.",
+            window_title, application_name
+        ))
+    })?;
+
+    let image = capture_window(&conn, root, window.id)?;
+    info!("[TAURI-MCP] Successfully captured window image: {}x{}", image.width(), image.height());
+
+    process_image(DynamicImage::ImageRgba8(image), params).map(create_success_response)
+}
+
+// Connect to the X server, failing with a descriptive `unsupported_platform` error on X-less sessions
+fn connect() -> Result<(RustConnection, usize)> {
+    if std::env::var_os("DISPLAY").is_none() {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            return Err(Error::new(
+                ErrorCode::UnsupportedPlatform,
+                "Native screenshots are not supported on Wayland without an X11 display (XWayland). \
+                 Use capture_screenshot instead, or run the app with GDK_BACKEND=x11.",
+            ));
+        }
+        return Err(Error::new(
+            ErrorCode::UnsupportedPlatform,
+            "Native screenshots require an X11 display, but DISPLAY is not set",
+        ));
+    }
+
+    RustConnection::connect(None).map_err(|e| {
+        Error::new(ErrorCode::UnsupportedPlatform, format!("Failed to connect to X11 display: {}", e))
+    })
+}
+
+// List viewable top-level windows, preferring the window manager's client list
+fn list_windows(conn: &RustConnection, root: Window) -> Result<Vec<X11Window>> {
+    let client_list = intern_atom(conn, b"_NET_CLIENT_LIST")?;
+    let mut ids: Vec<Window> = conn
+        .get_property(false, root, client_list, AtomEnum::WINDOW, 0, u32::MAX)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?
+        .value32()
+        .map(|values| values.collect())
+        .unwrap_or_default();
+
+    // Without a window manager (e.g. bare Xvfb) the toplevels are direct children of the root
+    if ids.is_empty() {
+        debug!("[TAURI-MCP] _NET_CLIENT_LIST unavailable, falling back to the root window's children");
+        ids = conn
+            .query_tree(root)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .children;
+    }
+
+    let mut windows = Vec::new();
+    for id in ids {
+        let viewable = conn
+            .get_window_attributes(id)
+            .map_err(x11_error)?
+            .reply()
+            .map(|attrs| attrs.map_state == MapState::VIEWABLE)
+            .unwrap_or(false);
+        if !viewable {
+            continue;
+        }
+
+        let window = X11Window {
+            id,
+            title: window_title(conn, id)?,
+            app_name: window_class(conn, id)?,
+        };
+        debug!("[TAURI-MCP] Window: id={}, title='{}', app_name='{}'", window.id, window.title, window.app_name);
+        windows.push(window);
+    }
+
+    Ok(windows)
 }
 
-// Add any other Unix-specific functionality here
+// Helper function to find the window: exact title first, then application name
+fn find_window<'a>(windows: &'a [X11Window], window_title: &str, application_name: &str) -> Option<&'a X11Window> {
+    if !window_title.is_empty()
+        && let Some(window) = windows.iter().find(|w| w.title == window_title)
+    {
+        info!("[TAURI-MCP] Found window by title: '{}'", window.title);
+        return Some(window);
+    }
+
+    let application_name_lower = application_name.to_lowercase();
+    if !application_name_lower.is_empty()
+        && let Some(window) = windows
+            .iter()
+            .find(|w| w.app_name.to_lowercase().contains(&application_name_lower))
+    {
+        info!("[TAURI-MCP] Found window by app name: '{}' (title: '{}')", window.app_name, window.title);
+        return Some(window);
+    }
+
+    error!(
+        "[TAURI-MCP] No matching window found for title '{}' or app_name '{}'",
+        window_title, application_name
+    );
+    None
+}
+
+// Grab the window contents, falling back to the matching region of the root window
+// when the window can't be read directly (e.g. partially off-screen)
+fn capture_window(conn: &RustConnection, root: Window, window: Window) -> Result<RgbaImage> {
+    let geometry = conn
+        .get_geometry(window)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+
+    if let Ok(image) = get_image(conn, window, 0, 0, geometry.width, geometry.height) {
+        return Ok(image);
+    }
+
+    debug!("[TAURI-MCP] Direct window capture failed, capturing from the root window");
+    let origin = conn
+        .translate_coordinates(window, root, 0, 0)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    let screen = conn
+        .get_geometry(root)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+
+    let x = origin.dst_x.max(0);
+    let y = origin.dst_y.max(0);
+    let right = (i32::from(origin.dst_x) + i32::from(geometry.width)).min(i32::from(screen.width));
+    let bottom = (i32::from(origin.dst_y) + i32::from(geometry.height)).min(i32::from(screen.height));
+    if right <= i32::from(x) || bottom <= i32::from(y) {
+        return Err(Error::WindowOperationFailed("Window is entirely off-screen".to_string()));
+    }
+
+    get_image(
+        conn,
+        root,
+        x,
+        y,
+        (right - i32::from(x)) as u16,
+        (bottom - i32::from(y)) as u16,
+    )
+}
+
+fn get_image(conn: &RustConnection, drawable: Window, x: i16, y: i16, width: u16, height: u16) -> Result<RgbaImage> {
+    let reply = conn
+        .get_image(ImageFormat::Z_PIXMAP, drawable, x, y, width, height, u32::MAX)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+
+    let setup = conn.setup();
+    let bits_per_pixel = setup
+        .pixmap_formats
+        .iter()
+        .find(|format| format.depth == reply.depth)
+        .map(|format| format.bits_per_pixel)
+        .unwrap_or(0);
+
+    pixels_to_rgba(
+        &reply.data,
+        u32::from(width),
+        u32::from(height),
+        bits_per_pixel,
+        setup.image_byte_order == ImageOrder::LSB_FIRST,
+    )
+    .ok_or_else(|| {
+        Error::WindowOperationFailed(format!(
+            "Unsupported X11 pixel format: depth {}, {} bits per pixel",
+            reply.depth, bits_per_pixel
+        ))
+    })
+}
+
+// Convert 32-bit ZPixmap data (xRGB in the server's byte order) into opaque RGBA
+fn pixels_to_rgba(data: &[u8], width: u32, height: u32, bits_per_pixel: u8, lsb_first: bool) -> Option<RgbaImage> {
+    if bits_per_pixel != 32 || data.len() < (width * height * 4) as usize {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for pixel in data.chunks_exact(4).take((width * height) as usize) {
+        let (r, g, b) = if lsb_first {
+            (pixel[2], pixel[1], pixel[0])
+        } else {
+            (pixel[1], pixel[2], pixel[3])
+        };
+        rgba.extend_from_slice(&[r, g, b, 255]);
+    }
+
+    RgbaImage::from_raw(width, height, rgba)
+}
+
+fn window_title(conn: &RustConnection, window: Window) -> Result<String> {
+    let net_wm_name = intern_atom(conn, b"_NET_WM_NAME")?;
+    let utf8_string = intern_atom(conn, b"UTF8_STRING")?;
+
+    let title = get_string_property(conn, window, net_wm_name, utf8_string)?;
+    if !title.is_empty() {
+        return Ok(title);
+    }
+    get_string_property(conn, window, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())
+}
+
+// WM_CLASS holds "instance\0class\0"; either part may carry the application name
+fn window_class(conn: &RustConnection, window: Window) -> Result<String> {
+    let class = get_string_property(conn, window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())?;
+    Ok(class.split('\0').filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" "))
+}
+
+fn get_string_property(conn: &RustConnection, window: Window, property: u32, type_: u32) -> Result<String> {
+    let reply = conn
+        .get_property(false, window, property, type_, 0, u32::MAX)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    Ok(String::from_utf8_lossy(&reply.value).into_owned())
+}
+
+fn intern_atom(conn: &RustConnection, name: &[u8]) -> Result<u32> {
+    Ok(conn
+        .intern_atom(false, name)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?
+        .atom)
+}
+
+fn x11_error(err: impl std::fmt::Display) -> Error {
+    Error::WindowOperationFailed(format!("X11 request failed: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: Window, title: &str, app_name: &str) -> X11Window {
+        X11Window {
+            id,
+            title: title.to_string(),
+            app_name: app_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_window_prefers_exact_title() {
+        let windows = vec![
+            window(1, "Settings", "my-app My-app"),
+            window(2, "My App", "my-app My-app"),
+        ];
+
+        assert_eq!(find_window(&windows, "My App", "my-app").map(|w| w.id), Some(2));
+        assert_eq!(find_window(&windows, "", "MY-APP").map(|w| w.id), Some(1));
+        assert!(find_window(&windows, "Other", "other-app").is_none());
+    }
+
+    #[test]
+    fn test_pixels_to_rgba_lsb_first() {
+        // One BGRX pixel: blue=1, green=2, red=3
+        let image = pixels_to_rgba(&[1, 2, 3, 0], 1, 1, 32, true).unwrap();
+        assert_eq!(image.get_pixel(0, 0).0, [3, 2, 1, 255]);
+
+        // Unsupported depths are rejected instead of producing garbage
+        assert!(pixels_to_rgba(&[0, 0], 1, 1, 16, true).is_none());
+    }
+}
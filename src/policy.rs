@@ -0,0 +1,246 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::{ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+
+/// Which socket commands clients may run.
+///
+/// Checked by `tools::handle_command` before routing, so it applies to the socket server
+/// and the embedded MCP server alike. The default policy allows everything.
+#[derive(Clone, Debug, Default, Serialize, JsonSchema)]
+pub struct CommandPolicy {
+    /// When set, only these commands may run
+    allow: Option<BTreeSet<String>>,
+    /// Commands that may never run, even if allowlisted
+    deny: BTreeSet<String>,
+    /// Per-command argument restrictions: command -> payload field -> permitted values
+    argument_rules: BTreeMap<String, BTreeMap<String, Vec<Value>>>,
+}
+
+/// The capabilities query's answer: every command with its standing under the policy
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PolicyDescription {
+    commands: Vec<CommandPermission>,
+    /// The policy's own rules
+    policy: CommandPolicy,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPermission {
+    name: String,
+    allowed: bool,
+    /// Permitted values per payload field; empty when unrestricted
+    argument_restrictions: BTreeMap<String, Vec<Value>>,
+}
+
+/// Why a command was refused
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyDenial {
+    pub command: String,
+    pub reason: String,
+    /// The payload field that violated an argument restriction, if any
+    pub argument: Option<String>,
+}
+
+impl CommandPolicy {
+    /// Allow every command (the default)
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Only allow the given commands
+    pub fn allow_only<I, S>(commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allow: Some(commands.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Inspection-only preset: window listing, DOM and accessibility snapshots, console logs, network
+    /// records, screenshots, storage reads and waits. JavaScript execution (including wait predicates),
+    /// storage writes, clearing the console or network buffers, window control and input simulation are blocked.
+    pub fn read_only() -> Self {
+        Self::allow_only([
+            commands::PING,
+            commands::LIST_WINDOWS,
+            commands::GET_DOM,
+            commands::GET_ACCESSIBILITY_SNAPSHOT,
+            commands::GET_CONSOLE_LOGS,
+            commands::GET_NETWORK_REQUESTS,
+            commands::CAPTURE_SCREENSHOT,
+            commands::TAKE_SCREENSHOT,
+            commands::MANAGE_STORAGE,
+            commands::WAIT_FOR,
+        ])
+        .restrict_argument(commands::MANAGE_STORAGE, "action", ["get", "keys", "databases", "object_stores"])
+        .restrict_argument(commands::GET_CONSOLE_LOGS, "clear", [false])
+        .restrict_argument(
+            commands::WAIT_FOR,
+            "condition",
+            ["visible", "hidden", "enabled", "text", "url", "network_idle"],
+        )
+    }
+
+    /// Never allow `command`
+    pub fn deny(mut self, command: impl Into<String>) -> Self {
+        self.deny.insert(command.into());
+        self
+    }
+
+    /// Only allow `command` when payload `field` is one of `values`.
+    /// Requests that omit the field fall back to the command's default and are allowed.
+    pub fn restrict_argument<I, V>(mut self, command: impl Into<String>, field: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.argument_rules
+            .entry(command.into())
+            .or_default()
+            .insert(field.into(), values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Whether `command` may run at all, ignoring argument restrictions
+    pub fn is_command_allowed(&self, command: &str) -> bool {
+        // Clients can always discover what they are allowed to do
+        if matches!(command, commands::GET_CAPABILITIES | commands::DESCRIBE) {
+            return true;
+        }
+        !self.deny.contains(command) && self.allow.as_ref().is_none_or(|allow| allow.contains(command))
+    }
+
+    /// Permitted values per payload field for `command`; empty when unrestricted
+    pub fn argument_restrictions(&self, command: &str) -> BTreeMap<String, Vec<Value>> {
+        self.argument_rules.get(command).cloned().unwrap_or_default()
+    }
+
+    /// Check a request against the policy
+    pub fn check(&self, command: &str, payload: &Value) -> Result<(), PolicyDenial> {
+        if !self.is_command_allowed(command) {
+            return Err(PolicyDenial {
+                command: command.to_string(),
+                reason: format!("Command '{}' is not allowed by the command policy", command),
+                argument: None,
+            });
+        }
+
+        if let Some(rules) = self.argument_rules.get(command) {
+            for (field, allowed) in rules {
+                if let Some(value) = payload.get(field)
+                    && !allowed.contains(value)
+                {
+                    return Err(PolicyDenial {
+                        command: command.to_string(),
+                        reason: format!(
+                            "Value {} for '{}' is not allowed by the command policy for '{}'",
+                            value, field, command
+                        ),
+                        argument: Some(field.clone()),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Per-command view of the policy for the capabilities query
+    pub fn describe(&self) -> Value {
+        let description = PolicyDescription {
+            commands: commands::ALL
+                .iter()
+                .map(|command| CommandPermission {
+                    name: command.to_string(),
+                    allowed: self.is_command_allowed(command),
+                    argument_restrictions: self.argument_restrictions(command),
+                })
+                .collect(),
+            policy: self.clone(),
+        };
+        serde_json::to_value(description).unwrap_or_default()
+    }
+}
+
+impl PolicyDenial {
+    /// The structured error response sent back to the client
+    pub fn into_response(self) -> SocketResponse {
+        SocketResponse {
+            success: false,
+            data: None,
+            error: Some(ErrorInfo::new(ErrorCode::PermissionDenied, self.reason).with_details(json!({
+                "command": self.command,
+                "argument": self.argument,
+            }))),
+            id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_policy() {
+        let policy = CommandPolicy::read_only();
+
+        assert!(policy.check(commands::GET_DOM, &json!("main")).is_ok());
+        assert!(policy.check(commands::LIST_WINDOWS, &json!({})).is_ok());
+        assert!(policy.check(commands::GET_CONSOLE_LOGS, &json!({ "levels": ["error"] })).is_ok());
+        assert!(policy.check(commands::GET_CONSOLE_LOGS, &json!({ "clear": true })).is_err());
+        assert!(policy.check(commands::GET_NETWORK_REQUESTS, &json!({ "failed_only": true })).is_ok());
+        assert!(policy.check(commands::CLEAR_NETWORK_REQUESTS, &json!({})).is_err());
+        assert!(policy.check(commands::GET_CAPABILITIES, &json!({})).is_ok());
+        assert!(policy.check(commands::DESCRIBE, &json!({})).is_ok());
+        assert!(policy.check(commands::MANAGE_STORAGE, &json!({ "action": "get", "key": "k" })).is_ok());
+        assert!(policy.check(commands::WAIT_FOR, &json!({ "condition": "network_idle" })).is_ok());
+        assert!(policy.check(commands::WAIT_FOR, &json!({ "condition": "predicate", "predicate": "1" })).is_err());
+
+        let denial = policy.check(commands::EXECUTE_JS, &json!({ "code": "1" })).unwrap_err();
+        assert_eq!(denial.argument, None);
+
+        let denial = policy
+            .check(commands::MANAGE_STORAGE, &json!({ "action": "set", "key": "k", "value": "v" }))
+            .unwrap_err();
+        assert_eq!(denial.argument.as_deref(), Some("action"));
+    }
+
+    #[test]
+    fn test_deny_overrides_default_allow() {
+        let policy = CommandPolicy::allow_all().deny(commands::SIMULATE_TEXT_INPUT);
+        assert!(policy.check(commands::EXECUTE_JS, &json!({})).is_ok());
+        assert!(policy.check(commands::SIMULATE_TEXT_INPUT, &json!({})).is_err());
+
+        let response = policy.check(commands::SIMULATE_TEXT_INPUT, &json!({})).unwrap_err().into_response();
+        assert!(!response.success);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, ErrorCode::PermissionDenied);
+        assert!(!error.retryable);
+        assert_eq!(error.details.unwrap()["command"], commands::SIMULATE_TEXT_INPUT);
+    }
+
+    #[test]
+    fn test_describe_lists_every_command() {
+        let description = CommandPolicy::read_only().describe();
+        let commands = description["commands"].as_array().unwrap();
+        assert_eq!(commands.len(), commands::ALL.len());
+
+        let execute_js = commands.iter().find(|c| c["name"] == commands::EXECUTE_JS).unwrap();
+        assert_eq!(execute_js["allowed"], false);
+        let storage = commands.iter().find(|c| c["name"] == commands::MANAGE_STORAGE).unwrap();
+        assert_eq!(
+            storage["argumentRestrictions"]["action"],
+            json!(["get", "keys", "databases", "object_stores"])
+        );
+    }
+}
@@ -1,36 +1,36 @@
 (function () {
     'use strict';
 
-    /******************************************************************************
-    Copyright (c) Microsoft Corporation.
-
-    Permission to use, copy, modify, and/or distribute this software for any
-    purpose with or without fee is hereby granted.
-
-    THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
-    REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
-    AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
-    INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
-    LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
-    OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
-    PERFORMANCE OF THIS SOFTWARE.
-    ***************************************************************************** */
-    /* global Reflect, Promise, SuppressedError, Symbol, Iterator */
-
-
-    function __classPrivateFieldGet(receiver, state, kind, f) {
-        if (typeof state === "function" ? receiver !== state || true : !state.has(receiver)) throw new TypeError("Cannot read private member from an object whose class did not declare it");
-        return kind === "m" ? f : kind === "a" ? f.call(receiver) : f ? f.value : state.get(receiver);
-    }
-
-    function __classPrivateFieldSet(receiver, state, value, kind, f) {
-        if (typeof state === "function" ? receiver !== state || true : !state.has(receiver)) throw new TypeError("Cannot write private member to an object whose class did not declare it");
-        return (state.set(receiver, value)), value;
-    }
-
-    typeof SuppressedError === "function" ? SuppressedError : function (error, suppressed, message) {
-        var e = new Error(message);
-        return e.name = "SuppressedError", e.error = error, e.suppressed = suppressed, e;
+    /******************************************************************************
+    Copyright (c) Microsoft Corporation.
+
+    Permission to use, copy, modify, and/or distribute this software for any
+    purpose with or without fee is hereby granted.
+
+    THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHOR DISCLAIMS ALL WARRANTIES WITH
+    REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF MERCHANTABILITY
+    AND FITNESS. IN NO EVENT SHALL THE AUTHOR BE LIABLE FOR ANY SPECIAL, DIRECT,
+    INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES WHATSOEVER RESULTING FROM
+    LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION OF CONTRACT, NEGLIGENCE OR
+    OTHER TORTIOUS ACTION, ARISING OUT OF OR IN CONNECTION WITH THE USE OR
+    PERFORMANCE OF THIS SOFTWARE.
+    ***************************************************************************** */
+    /* global Reflect, Promise, SuppressedError, Symbol, Iterator */
+
+
+    function __classPrivateFieldGet(receiver, state, kind, f) {
+        if (typeof state === "function" ? receiver !== state || true : !state.has(receiver)) throw new TypeError("Cannot read private member from an object whose class did not declare it");
+        return kind === "m" ? f : kind === "a" ? f.call(receiver) : f ? f.value : state.get(receiver);
+    }
+
+    function __classPrivateFieldSet(receiver, state, value, kind, f) {
+        if (typeof state === "function" ? receiver !== state || true : !state.has(receiver)) throw new TypeError("Cannot write private member to an object whose class did not declare it");
+        return (state.set(receiver, value)), value;
+    }
+
+    typeof SuppressedError === "function" ? SuppressedError : function (error, suppressed, message) {
+        var e = new Error(message);
+        return e.name = "SuppressedError", e.error = error, e.suppressed = suppressed, e;
     };
 
     // Copyright 2019-2024 Tauri Programme within The Commons Conservancy
@@ -3349,81 +3349,37 @@
     }
 
     async function setupPluginListeners() {
+        // Start capturing before the awaits below so early output and requests are kept
+        installConsoleCapture();
+        installNetworkCapture();
+        // The plugin's Tauri commands run their element scripts through execute_js and resolve targets here
+        window.__TAURI_MCP__ = { resolveElement };
         const currentWindow = getCurrentWebviewWindow();
         await currentWindow.listen('got-dom-content', handleDomContentRequest);
-        await currentWindow.listen('get-local-storage', handleLocalStorageRequest);
+        await currentWindow.listen('manage-storage', handleStorageRequest);
         await currentWindow.listen('execute-js', handleJsExecutionRequest);
         await currentWindow.listen('get-element-position', handleGetElementPositionRequest);
         await currentWindow.listen('send-text-to-element', handleSendTextToElementRequest);
         await currentWindow.listen('capture-screenshot', handleCaptureScreenshotRequest);
         await currentWindow.listen('iframe-rpc', handleIframeRpcRequest);
-        console.log('TAURI-PLUGIN-MCP: Event listeners for "got-dom-content", "get-local-storage", "execute-js", "get-element-position", "send-text-to-element", "capture-screenshot", and "iframe-rpc" are set up on the current window.');
+        await currentWindow.listen('get-console-logs', handleGetConsoleLogsRequest);
+        await currentWindow.listen('get-network-requests', handleGetNetworkRequestsRequest);
+        await currentWindow.listen('get-accessibility-snapshot', handleAccessibilitySnapshotRequest);
+        await currentWindow.listen('wait-for', handleWaitForRequest);
+        await currentWindow.listen('press-keys', handlePressKeysRequest);
+        await currentWindow.listen('click-element', handleClickElementRequest);
+        await currentWindow.listen('confirm-click', handleConfirmClickRequest);
+        console.log('TAURI-PLUGIN-MCP: Event listeners for "got-dom-content", "manage-storage", "execute-js", "get-element-position", "send-text-to-element", "capture-screenshot", "iframe-rpc", "get-console-logs", "get-network-requests", "get-accessibility-snapshot", "wait-for", "press-keys", "click-element", and "confirm-click" are set up on the current window.');
     }
     async function handleGetElementPositionRequest(event) {
         console.log('TAURI-PLUGIN-MCP: Received get-element-position, payload:', event.payload);
+        const requestId = event.payload?.requestId;
         try {
-            const { selectorType, selectorValue, shouldClick = false } = event.payload;
-            // Find the element based on the selector type
-            let element = null;
-            let debugInfo = [];
-            switch (selectorType) {
-                case 'id':
-                    element = document.getElementById(selectorValue);
-                    if (!element) {
-                        debugInfo.push(`No element found with id="${selectorValue}"`);
-                    }
-                    break;
-                case 'class':
-                    // Get the first element with the class
-                    const elemsByClass = document.getElementsByClassName(selectorValue);
-                    element = elemsByClass.length > 0 ? elemsByClass[0] : null;
-                    if (!element) {
-                        debugInfo.push(`No elements found with class="${selectorValue}" (total matching: 0)`);
-                    }
-                    else if (elemsByClass.length > 1) {
-                        debugInfo.push(`Found ${elemsByClass.length} elements with class="${selectorValue}", using the first one`);
-                    }
-                    break;
-                case 'tag':
-                    // Get the first element with the tag name
-                    const elemsByTag = document.getElementsByTagName(selectorValue);
-                    element = elemsByTag.length > 0 ? elemsByTag[0] : null;
-                    if (!element) {
-                        debugInfo.push(`No elements found with tag="${selectorValue}" (total matching: 0)`);
-                    }
-                    else if (elemsByTag.length > 1) {
-                        debugInfo.push(`Found ${elemsByTag.length} elements with tag="${selectorValue}", using the first one`);
-                    }
-                    break;
-                case 'text':
-                    // Find element by text content
-                    element = findElementByText(selectorValue);
-                    if (!element) {
-                        debugInfo.push(`No element found with text="${selectorValue}"`);
-                        // Check if any element contains part of the text (for debugging)
-                        const containingElements = Array.from(document.querySelectorAll('*'))
-                            .filter(el => el.textContent && el.textContent.includes(selectorValue));
-                        if (containingElements.length > 0) {
-                            debugInfo.push(`Found ${containingElements.length} elements containing part of the text.`);
-                            debugInfo.push(`First element with partial match: ${containingElements[0].tagName}, text="${containingElements[0].textContent?.trim()}"`);
-                        }
-                        // Check for similar inputs
-                        const inputs = Array.from(document.querySelectorAll('input, textarea'));
-                        const inputsWithSimilarPlaceholders = inputs
-                            .filter(input => input.placeholder &&
-                            input.placeholder.includes(selectorValue));
-                        if (inputsWithSimilarPlaceholders.length > 0) {
-                            debugInfo.push(`Found ${inputsWithSimilarPlaceholders.length} input elements with similar placeholders.`);
-                            const firstMatch = inputsWithSimilarPlaceholders[0];
-                            debugInfo.push(`First input with similar placeholder: ${firstMatch.tagName}, placeholder="${firstMatch.placeholder}"`);
-                        }
-                    }
-                    break;
-                default:
-                    throw new Error(`Unsupported selector type: ${selectorType}`);
-            }
-            if (!element) {
-                throw new Error(`Element with ${selectorType}="${selectorValue}" not found. ${debugInfo.join(' ')}`);
+            const { selector, shouldClick = false, autoWaitMs = 0, scrollIntoView = false } = event.payload;
+            // Find the element with the shared selector engine, waiting until it is actionable if asked to
+            const element = await targetElement(selector, autoWaitMs, { hitTarget: shouldClick });
+            if (scrollIntoView) {
+                element.scrollIntoView({ block: 'center', inline: 'center' });
             }
             // Get element position
             const rect = element.getBoundingClientRect();
@@ -3441,16 +3397,17 @@
             // Account for Webview Scrolling (CSS Pixels)
             const elementDocumentCssX = elementViewportCssX + window.scrollX;
             const elementDocumentCssY = elementViewportCssY + window.scrollY;
-            // Always return the raw document coordinates (ideal for mouse_movement)
-            const targetX = elementDocumentCssX;
-            const targetY = elementDocumentCssY;
-            console.log('TAURI-PLUGIN-MCP: Raw coordinates for mouse_movement:', { x: targetX, y: targetY });
+            // Return the center in the top-level viewport, through any iframes the element sits in.
+            // Mouse commands measure from the webview's top-left corner, which is this viewport's origin.
+            const { x: targetX, y: targetY } = topViewportPoint(element, elementViewportCssX, elementViewportCssY);
+            console.log('TAURI-PLUGIN-MCP: Webview coordinates for mouse commands:', { x: targetX, y: targetY });
             // Click the element if requested
             let clickResult = null;
             if (shouldClick) {
                 clickResult = clickElement(element, elementViewportCssX, elementViewportCssY);
             }
             await emit('get-element-position-response', {
+                requestId,
                 success: true,
                 data: {
                     x: targetX,
@@ -3491,62 +3448,193 @@
         catch (error) {
             console.error('TAURI-PLUGIN-MCP: Error handling get-element-position request', error);
             await emit('get-element-position-response', {
+                requestId,
                 success: false,
-                error: error instanceof Error ? error.toString() : String(error)
+                error: error instanceof Error ? error.toString() : String(error),
+                details: errorDetails(error)
             }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
         }
     }
-    // Helper function to find an element by its text content
-    function findElementByText(text) {
-        // Get all elements in the document
-        const allElements = document.querySelectorAll('*');
-        // First try exact text content matching
-        for (const element of allElements) {
-            // Check exact text content
-            if (element.textContent && element.textContent.trim() === text) {
-                return element;
+    // Candidates described when a selector matches more than one element
+    const MAX_SELECTOR_CANDIDATES = 10;
+
+
+    // Selector failures carry structured details (match count, candidates) back to the caller
+    class SelectorError extends Error {
+        constructor(message, details) {
+            super(message);
+            this.name = 'SelectorError';
+            this.details = details;
+        }
+    }
+
+    function describeSelector(selector) {
+        const name = selector.name ? ` name="${selector.name}"` : '';
+        return `${selector.selectorType}="${selector.selectorValue}"${name}`;
+    }
+
+    /**
+     * Resolves a selector to exactly one element. Fails when nothing matches, and when
+     * several elements match and no `nth` was given, listing the candidates.
+     * Every element command goes through this.
+     */
+    function resolveElement(selector) {
+        return pickMatch(selector, findElements(selector));
+    }
+
+    function pickMatch(selector, matches) {
+        const description = describeSelector(selector);
+
+        if (selector.nth !== undefined && selector.nth !== null) {
+            const element = matches[selector.nth];
+            if (!element) {
+                throw new SelectorError(
+                    `Selector ${description} matched ${matches.length} elements, so there is no match at nth=${selector.nth}`,
+                    { code: 'element_not_found', matchCount: matches.length }
+                );
             }
-            // Check placeholder attribute (for input fields)
-            if (element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement) {
-                if (element.placeholder === text) {
-                    return element;
+            return element;
+        }
+
+        if (matches.length === 0) {
+            throw new SelectorError(`No element matches selector ${description}`, { code: 'element_not_found', matchCount: 0 });
+        }
+        if (matches.length > 1) {
+            const candidates = matches.slice(0, MAX_SELECTOR_CANDIDATES).map(describeCandidate);
+            throw new SelectorError(
+                `Selector ${description} matched ${matches.length} elements; pass nth or use a more specific selector. Candidates: `
+                    + candidates.map(c => `[${c.index}] <${c.tag}${c.id ? `#${c.id}` : ''}> "${c.text}" (ref ${c.ref})`).join(', '),
+                { code: 'ambiguous_selector', matchCount: matches.length, candidates }
+            );
+        }
+        return matches[0];
+    }
+
+    function describeCandidate(element, index) {
+        return {
+            index,
+            ref: refForElement(element),
+            tag: element.tagName.toLowerCase(),
+            id: element.id || null,
+            classes: typeof element.className === 'string' && element.className ? element.className : null,
+            text: truncateText(collapseWhitespace(element.innerText ?? element.textContent ?? '')),
+            bounds: (() => {
+                const rect = element.getBoundingClientRect();
+                return { x: Math.round(rect.left), y: Math.round(rect.top), width: Math.round(rect.width), height: Math.round(rect.height) };
+            })()
+        };
+    }
+
+    // Every element the selector matches, in document order. Each `within` step must match
+    // one element; the search continues in its iframe document, its shadow root or its subtree.
+    function findElements(selector) {
+        let scope = document;
+        for (const step of selector.within ?? []) {
+            const stepSelector = { selectorType: 'css', selectorValue: step };
+            const host = pickMatch(stepSelector, findElementsIn(stepSelector, scope));
+            if (host instanceof HTMLIFrameElement) {
+                if (!host.contentDocument) {
+                    throw new SelectorError(`Cannot search inside cross-origin iframe ${step}`, { code: 'cross_origin_frame' });
                 }
+                scope = host.contentDocument;
             }
-            // Check title attribute
-            if (element.getAttribute('title') === text) {
-                return element;
-            }
-            // Check aria-label attribute
-            if (element.getAttribute('aria-label') === text) {
-                return element;
+            else {
+                scope = host.shadowRoot ?? host;
             }
         }
-        // If no exact match, try partial text content matching
-        for (const element of allElements) {
-            // Check if text is contained within the element's text
-            if (element.textContent && element.textContent.trim().includes(text)) {
-                return element;
-            }
-            // Check if text is contained within placeholder
-            if (element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement) {
-                if (element.placeholder && element.placeholder.includes(text)) {
-                    return element;
+        return findElementsIn(selector, scope);
+    }
+
+    function findElementsIn(selector, scope) {
+        const value = selector.selectorValue;
+        switch (selector.selectorType) {
+            case 'id':
+                return queryAllDeep(scope, `#${CSS.escape(value)}`);
+            case 'class':
+                return queryAllDeep(scope, `.${CSS.escape(value)}`);
+            case 'tag':
+                return queryAllDeep(scope, value);
+            case 'css':
+                return queryAllDeep(scope, value);
+            case 'test_id':
+                return queryAllDeep(scope, `[data-testid="${CSS.escape(value)}"]`);
+            case 'xpath': {
+                const ownerDocument = scope instanceof Document ? scope : scope.ownerDocument ?? document;
+                const result = ownerDocument.evaluate(value, scope, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+                const elements = [];
+                for (let i = 0; i < result.snapshotLength; i++) {
+                    const node = result.snapshotItem(i);
+                    // Nodes from an iframe document belong to another realm, so check nodeType
+                    if (node?.nodeType === Node.ELEMENT_NODE) {
+                        elements.push(node);
+                    }
                 }
+                return elements;
             }
-            // Check partial match in title attribute
-            const title = element.getAttribute('title');
-            if (title && title.includes(text)) {
-                return element;
-            }
-            // Check partial match in aria-label attribute
-            const ariaLabel = element.getAttribute('aria-label');
-            if (ariaLabel && ariaLabel.includes(text)) {
-                return element;
+            case 'text':
+                return findElementsByText(scope, value, selector.exact === true);
+            case 'role':
+                return queryAllDeep(scope, '*').filter(element => {
+                    const role = elementRole(element);
+                    return role === value && (!selector.name || textMatches(accessibleName(element, role), selector.name, selector.exact === true));
+                });
+            case 'ref': {
+                const element = resolveElementRef(value);
+                return element ? [element] : [];
             }
+            default:
+                throw new SelectorError(`Unsupported selector type: ${selector.selectorType}`, { code: 'invalid_selector' });
         }
-        return null;
     }
-    // Helper function to click an element
+
+    // querySelectorAll that also searches open shadow roots, in document order
+    function queryAllDeep(scope, css) {
+        const results = [];
+        const visit = (root) => {
+            let all;
+            try {
+                results.push(...Array.from(root.querySelectorAll(css)));
+                all = Array.from(root.querySelectorAll('*'));
+            }
+            catch (error) {
+                throw new SelectorError(`Invalid CSS selector "${css}": ${error instanceof Error ? error.message : error}`, { code: 'invalid_selector' });
+            }
+            for (const element of all) {
+                if (element.shadowRoot) {
+                    visit(element.shadowRoot);
+                }
+            }
+        };
+        visit(scope);
+        return results;
+    }
+
+    // Elements whose text, placeholder, title or aria-label matches. Only the innermost
+    // matching elements are kept, and exact matches win over partial ones unless `exact` is set.
+    function findElementsByText(scope, text, exact) {
+        const elements = queryAllDeep(scope, '*').filter(element => !SKIPPED_TAGS.has(element.tagName));
+        const labels = (element) => [
+            element.innerText ?? element.textContent ?? '',
+            element.getAttribute('placeholder') ?? '',
+            element.getAttribute('title') ?? '',
+            element.getAttribute('aria-label') ?? ''
+        ];
+        const matching = (exactOnly) => {
+            const matched = elements.filter(element => labels(element).some(label => textMatches(label, text, exactOnly)));
+            return matched.filter(element => !matched.some(other => other !== element && element.contains(other)));
+        };
+
+        const exactMatches = matching(true);
+        return exact || exactMatches.length > 0 ? exactMatches : matching(false);
+    }
+
+    // Exact matches compare whitespace-normalized text; partial matches are case-insensitive substrings
+    function textMatches(actual, expected, exact) {
+        const normalized = collapseWhitespace(actual);
+        return exact
+            ? normalized === collapseWhitespace(expected)
+            : normalized.toLowerCase().includes(collapseWhitespace(expected).toLowerCase());
+    }
     function clickElement(element, centerX, centerY) {
         try {
             // Create and dispatch mouse events
@@ -3591,14 +3679,18 @@
     }
     async function handleDomContentRequest(event) {
         console.log('TAURI-PLUGIN-MCP: Received got-dom-content, payload:', event.payload);
+        const requestId = event.payload?.requestId;
         try {
-            const domContent = getDomContent();
-            await emit('got-dom-content-response', domContent);
+            // Requests with options get a filtered page; plain requests keep the full-HTML string
+            const options = event.payload?.options;
+            const data = options ? pageDomContent(options) : getDomContent();
+            await emit('got-dom-content-response', { requestId, data });
             console.log('TAURI-PLUGIN-MCP: Emitted got-dom-content-response');
         }
         catch (error) {
             console.error('TAURI-PLUGIN-MCP: Error handling dom content request', error);
-            await emit('got-dom-content-response', '').catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting empty response', e));
+            const message = error instanceof Error ? error.message : String(error);
+            await emit('got-dom-content-response', { requestId, data: '', error: message }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting empty response', e));
         }
     }
     function getDomContent() {
@@ -3610,163 +3702,897 @@
         console.warn('TAURI-PLUGIN-MCP: DOM not fully loaded when got-dom-content received. Returning empty content.');
         return '';
     }
-    async function handleLocalStorageRequest(event) {
-        console.log('TAURI-PLUGIN-MCP: Received get-local-storage, payload:', event.payload);
-        try {
-            const { action, key, value } = event.payload;
-            // Convert values that might be JSON strings to their actual values
-            let processedKey = key;
-            let processedValue = value;
-            // If key is a JSON string, try to parse it
-            if (typeof key === 'string') {
-                try {
-                    if (key.trim().startsWith('{') || key.trim().startsWith('[')) {
-                        processedKey = JSON.parse(key);
+    // Filtered DOM serializations kept so later pages come from the same snapshot
+    const MAX_DOM_SNAPSHOTS = 4;
+    // Attributes kept when get_dom strips attributes
+    const KEPT_DOM_ATTRIBUTES = new Set([
+        'id', 'class', 'name', 'type', 'role', 'href', 'src', 'alt', 'title', 'placeholder', 'value', 'for', 'data-testid'
+    ]);
+
+
+    const domSnapshots = new Map();
+    let nextDomSnapshotId = 1;
+
+    /**
+     * Serializes the document (or the element matching options.root) with the requested
+     * content removed. Works on a copy in an inert document, so nothing in the page
+     * changes and copied images or scripts never load.
+     */
+    function filteredDomHtml(options) {
+        const root = options.root ? document.querySelector(options.root) : document.documentElement;
+        if (!root) {
+            throw new Error(`No element matches root selector ${options.root}`);
+        }
+
+        const strip = new Set(options.strip ?? []);
+        const maxDepth = options.maxDepth ?? Infinity;
+        const inert = document.implementation.createHTMLDocument('');
+
+        const copy = (source, depth) => {
+            const tag = source.tagName.toLowerCase();
+            if (strip.has('scripts') && (tag === 'script' || tag === 'noscript')) {
+                return null;
+            }
+            if (strip.has('styles') && (tag === 'style' || (tag === 'link' && source.getAttribute('rel') === 'stylesheet'))) {
+                return null;
+            }
+            if (options.visibleOnly && !isRenderedElement(source)) {
+                return null;
+            }
+
+            const clone = inert.importNode(source, false);
+            for (const attribute of Array.from(clone.attributes)) {
+                const name = attribute.name;
+                if ((strip.has('attributes') && !KEPT_DOM_ATTRIBUTES.has(name) && !name.startsWith('aria-'))
+                    || (strip.has('styles') && name === 'style')) {
+                    clone.removeAttribute(name);
+                }
+            }
+
+            if (strip.has('svg') && tag === 'svg') {
+                return clone;
+            }
+            if (depth >= maxDepth) {
+                if (source.children.length > 0) {
+                    clone.appendChild(inert.createComment(` ${source.children.length} child elements omitted `));
+                }
+                return clone;
+            }
+
+            for (const child of Array.from(source.childNodes)) {
+                if (child instanceof Element) {
+                    const childCopy = copy(child, depth + 1);
+                    if (childCopy) {
+                        clone.appendChild(childCopy);
                     }
                 }
-                catch (e) {
-                    // Keep original if parsing fails
-                    console.log('TAURI-PLUGIN-MCP: Key not valid JSON, using as string');
+                else if (child.nodeType === Node.TEXT_NODE) {
+                    clone.appendChild(inert.importNode(child, false));
                 }
             }
-            // If value is a JSON string, try to parse it
-            if (typeof value === 'string') {
-                try {
-                    if (value.trim().startsWith('{') || value.trim().startsWith('[')) {
-                        processedValue = JSON.parse(value);
+            return clone;
+        };
+
+        return copy(root, 0)?.outerHTML ?? '';
+    }
+
+    function isRenderedElement(element) {
+        if (element.hidden) {
+            return false;
+        }
+        const style = getComputedStyle(element);
+        return style.display !== 'none' && style.visibility !== 'hidden';
+    }
+
+    // One page of the filtered DOM, continuing from options.cursor when given
+    function pageDomContent(options) {
+        let snapshotId;
+        let bytes;
+        let offset = 0;
+
+        if (options.cursor) {
+            const separator = options.cursor.lastIndexOf(':');
+            snapshotId = options.cursor.slice(0, separator);
+            offset = Number(options.cursor.slice(separator + 1));
+            bytes = domSnapshots.get(snapshotId);
+            if (!bytes || !Number.isInteger(offset) || offset < 0 || offset > bytes.length) {
+                throw new Error('DOM cursor has expired or is invalid; request the first page again');
+            }
+        }
+        else {
+            snapshotId = String(nextDomSnapshotId++);
+            bytes = new TextEncoder().encode(filteredDomHtml(options));
+        }
+
+        let end = Math.min(offset + (options.maxBytes ?? bytes.length), bytes.length);
+        // Never split a UTF-8 sequence between pages
+        while (end < bytes.length && end > offset && (bytes[end] & 0xc0) === 0x80) {
+            end--;
+        }
+
+        const nextCursor = end < bytes.length ? `${snapshotId}:${end}` : null;
+        if (nextCursor) {
+            domSnapshots.set(snapshotId, bytes);
+            if (domSnapshots.size > MAX_DOM_SNAPSHOTS) {
+                domSnapshots.delete(domSnapshots.keys().next().value);
+            }
+        }
+        else {
+            domSnapshots.delete(snapshotId);
+        }
+
+        return {
+            html: new TextDecoder().decode(bytes.subarray(offset, end)),
+            offset,
+            totalBytes: bytes.length,
+            nextCursor
+        };
+    }
+    // Longest accessible name or text run kept in a snapshot
+    const MAX_SNAPSHOT_TEXT = 100;
+
+    // Roles whose accessible name is their text content, so their text isn't repeated as children
+    const NAME_FROM_CONTENT_ROLES = new Set([
+        'button', 'link', 'heading', 'menuitem', 'menuitemcheckbox', 'menuitemradio', 'option',
+        'tab', 'treeitem', 'cell', 'columnheader', 'rowheader', 'checkbox', 'radio', 'switch', 'tooltip'
+    ]);
+    // Roles agents act on; interactiveOnly snapshots keep just these
+    const INTERACTIVE_ROLES = new Set([
+        'button', 'link', 'textbox', 'searchbox', 'checkbox', 'radio', 'switch', 'combobox', 'listbox',
+        'option', 'slider', 'spinbutton', 'menuitem', 'menuitemcheckbox', 'menuitemradio', 'tab', 'treeitem'
+    ]);
+    const SKIPPED_TAGS = new Set(['SCRIPT', 'STYLE', 'TEMPLATE', 'NOSCRIPT', 'META', 'LINK', 'HEAD']);
+
+
+
+    // Refs stay the same for an element across snapshots, so they survive re-renders that keep the node
+    const elementRefs = new WeakMap();
+    const refElements = new Map();
+    let nextElementRef = 1;
+
+    function refForElement(element) {
+        let ref = elementRefs.get(element);
+        if (!ref) {
+            ref = `e${nextElementRef++}`;
+            elementRefs.set(element, ref);
+            refElements.set(ref, element);
+        }
+        return ref;
+    }
+
+    // The element a snapshot ref points at, or null once it has left the document
+    function resolveElementRef(ref) {
+        const element = refElements.get(ref);
+        if (!element || !element.isConnected) {
+            refElements.delete(ref);
+            return null;
+        }
+        return element;
+    }
+
+    /**
+     * Builds a pruned accessibility tree of the document (or the subtree matching options.root).
+     * Hidden elements are dropped and generic containers are flattened into their parent, so
+     * the tree holds roles, names, states, values and bounding boxes (CSS pixels, viewport
+     * relative). Every element node carries a `ref` that element commands accept.
+     */
+    function buildAccessibilitySnapshot(options = {}) {
+        const root = options.root ? document.querySelector(options.root) : document.body;
+        if (!root) {
+            throw new Error(`No element matches root selector ${options.root}`);
+        }
+
+        // Forget refs of elements that are gone
+        for (const [ref, element] of refElements) {
+            if (!element.isConnected) {
+                refElements.delete(ref);
+            }
+        }
+
+        const maxDepth = options.maxDepth ?? Infinity;
+        let nodeCount = 0;
+
+        const visit = (node, depth) => {
+            if (node.nodeType === Node.TEXT_NODE) {
+                const text = collapseWhitespace(node.textContent ?? '');
+                if (!text || options.interactiveOnly) {
+                    return [];
+                }
+                nodeCount++;
+                return [{ role: 'text', name: truncateText(text) }];
+            }
+            if (!(node instanceof Element) || SKIPPED_TAGS.has(node.tagName) || isHiddenElement(node)) {
+                return [];
+            }
+
+            const role = elementRole(node);
+            const keep = role !== null && (!options.interactiveOnly || INTERACTIVE_ROLES.has(role));
+            const childDepth = keep ? depth + 1 : depth;
+
+            const children = [];
+            if (childDepth <= maxDepth) {
+                const childNodes = [...(node.shadowRoot?.childNodes ?? []), ...node.childNodes];
+                for (const child of childNodes) {
+                    if (role !== null && NAME_FROM_CONTENT_ROLES.has(role) && child.nodeType === Node.TEXT_NODE) {
+                        continue;
                     }
+                    children.push(...visit(child, childDepth));
                 }
-                catch (e) {
-                    // Keep original if parsing fails
-                    console.log('TAURI-PLUGIN-MCP: Value not valid JSON, using as string');
+            }
+
+            if (!keep) {
+                // Generic containers are flattened into their parent
+                return children;
+            }
+
+            nodeCount++;
+            const snapshotNode = describeElement(node, role);
+            if (children.length > 0) {
+                snapshotNode.children = children;
+            }
+            return [snapshotNode];
+        };
+
+        const tree = {
+            role: 'document',
+            name: truncateText(document.title),
+            children: visit(root, 1)
+        };
+        return { tree, nodeCount };
+    }
+
+    function describeElement(element, role) {
+        const node = { ref: refForElement(element), role };
+
+        const name = accessibleName(element, role);
+        if (name) {
+            node.name = name;
+        }
+
+        const value = elementValue(element, role);
+        if (value !== null) {
+            node.value = value;
+        }
+
+        if (role === 'heading') {
+            const level = Number(element.getAttribute('aria-level') ?? element.tagName.slice(1));
+            if (level > 0) {
+                node.level = level;
+            }
+        }
+
+        const ariaChecked = element.getAttribute('aria-checked');
+        if (element instanceof HTMLInputElement && (element.type === 'checkbox' || element.type === 'radio')) {
+            node.checked = element.indeterminate ? 'mixed' : element.checked;
+        }
+        else if (ariaChecked !== null) {
+            node.checked = ariaChecked === 'mixed' ? 'mixed' : ariaChecked === 'true';
+        }
+
+        const flag = (key, on) => {
+            if (on) {
+                node[key] = true;
+            }
+        };
+        flag('disabled', element.disabled === true || element.getAttribute('aria-disabled') === 'true');
+        flag('expanded', element.getAttribute('aria-expanded') === 'true' || (element instanceof HTMLDetailsElement && element.open));
+        flag('selected', element.getAttribute('aria-selected') === 'true' || (element instanceof HTMLOptionElement && element.selected));
+        flag('pressed', element.getAttribute('aria-pressed') === 'true');
+        flag('focused', document.activeElement === element);
+        flag('required', element.required === true || element.getAttribute('aria-required') === 'true');
+        flag('readonly', element.readOnly === true || element.getAttribute('aria-readonly') === 'true');
+
+        const rect = element.getBoundingClientRect();
+        node.bounds = {
+            x: Math.round(rect.left),
+            y: Math.round(rect.top),
+            width: Math.round(rect.width),
+            height: Math.round(rect.height)
+        };
+        return node;
+    }
+
+    // The element's ARIA role (explicit or implied by its tag), or null for generic containers
+    function elementRole(element) {
+        const explicit = element.getAttribute('role')?.trim().split(/\s+/)[0];
+        if (explicit) {
+            return explicit === 'presentation' || explicit === 'none' || explicit === 'generic' ? null : explicit;
+        }
+
+        const tag = element.tagName.toLowerCase();
+        switch (tag) {
+            case 'a':
+            case 'area':
+                return element.hasAttribute('href') ? 'link' : null;
+            case 'button':
+            case 'summary':
+                return 'button';
+            case 'input': {
+                const type = element.type;
+                switch (type) {
+                    case 'button': case 'submit': case 'reset': case 'image': return 'button';
+                    case 'checkbox': return 'checkbox';
+                    case 'radio': return 'radio';
+                    case 'range': return 'slider';
+                    case 'number': return 'spinbutton';
+                    case 'search': return 'searchbox';
+                    case 'hidden': return null;
+                    default: return 'textbox';
                 }
             }
-            console.log('TAURI-PLUGIN-MCP: Processing localStorage operation', {
-                action,
-                processedKey,
-                processedValue
+            case 'textarea':
+                return 'textbox';
+            case 'select': {
+                const select = element;
+                return select.multiple || select.size > 1 ? 'listbox' : 'combobox';
+            }
+            case 'option':
+                return 'option';
+            case 'img':
+                return element.getAttribute('alt') === '' ? null : 'img';
+            case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6':
+                return 'heading';
+            case 'ul':
+            case 'ol':
+                return 'list';
+            case 'li':
+                return 'listitem';
+            case 'nav':
+                return 'navigation';
+            case 'main':
+                return 'main';
+            case 'header':
+                return 'banner';
+            case 'footer':
+                return 'contentinfo';
+            case 'aside':
+                return 'complementary';
+            case 'form':
+                return 'form';
+            case 'section':
+                return element.hasAttribute('aria-label') || element.hasAttribute('aria-labelledby') ? 'region' : null;
+            case 'fieldset':
+                return 'group';
+            case 'table':
+                return 'table';
+            case 'tr':
+                return 'row';
+            case 'td':
+                return 'cell';
+            case 'th':
+                return 'columnheader';
+            case 'dialog':
+                return 'dialog';
+            case 'progress':
+                return 'progressbar';
+            case 'p':
+                return 'paragraph';
+            case 'iframe':
+                return 'iframe';
+        }
+
+        if (element.isContentEditable && !element.parentElement?.isContentEditable) {
+            return 'textbox';
+        }
+        return null;
+    }
+
+    function accessibleName(element, role) {
+        const labelledBy = element.getAttribute('aria-labelledby');
+        if (labelledBy) {
+            const text = labelledBy
+                .split(/\s+/)
+                .map(id => document.getElementById(id)?.textContent ?? '')
+                .join(' ');
+            if (collapseWhitespace(text)) {
+                return truncateText(collapseWhitespace(text));
+            }
+        }
+
+        const label = element.getAttribute('aria-label');
+        if (label?.trim()) {
+            return truncateText(collapseWhitespace(label));
+        }
+
+        const labels = element.labels;
+        if (labels && labels.length > 0) {
+            return truncateText(collapseWhitespace(Array.from(labels).map(l => l.textContent ?? '').join(' ')));
+        }
+
+        if (element instanceof HTMLImageElement || element instanceof HTMLAreaElement) {
+            return truncateText(collapseWhitespace(element.alt ?? ''));
+        }
+        if (element instanceof HTMLInputElement && ['button', 'submit', 'reset'].includes(element.type)) {
+            return truncateText(element.value);
+        }
+
+        if (NAME_FROM_CONTENT_ROLES.has(role)) {
+            const text = collapseWhitespace(element.innerText ?? element.textContent ?? '');
+            if (text) {
+                return truncateText(text);
+            }
+        }
+
+        const fallback = element.getAttribute('placeholder') ?? element.getAttribute('title') ?? '';
+        return truncateText(collapseWhitespace(fallback));
+    }
+
+    function elementValue(element, role) {
+        if (element instanceof HTMLInputElement) {
+            if (['checkbox', 'radio', 'button', 'submit', 'reset', 'image'].includes(element.type)) {
+                return null;
+            }
+            // Never put secrets into a snapshot
+            return element.type === 'password' ? '•'.repeat(element.value.length) : truncateText(element.value);
+        }
+        if (element instanceof HTMLTextAreaElement) {
+            return truncateText(element.value);
+        }
+        if (element instanceof HTMLSelectElement) {
+            return truncateText(Array.from(element.selectedOptions).map(option => option.text).join(', '));
+        }
+        if (element instanceof HTMLProgressElement) {
+            return String(element.value);
+        }
+        if (role === 'textbox' && element.isContentEditable) {
+            return truncateText(collapseWhitespace(element.innerText ?? ''));
+        }
+        const ariaValue = element.getAttribute('aria-valuetext') ?? element.getAttribute('aria-valuenow');
+        return ariaValue;
+    }
+
+    function isHiddenElement(element) {
+        if (element.getAttribute('aria-hidden') === 'true' || element.hidden) {
+            return true;
+        }
+        const style = getComputedStyle(element);
+        return style.display === 'none' || style.visibility === 'hidden';
+    }
+
+    function collapseWhitespace(text) {
+        return text.replace(/\s+/g, ' ').trim();
+    }
+
+    function truncateText(text) {
+        return text.length > MAX_SNAPSHOT_TEXT ? `${text.slice(0, MAX_SNAPSHOT_TEXT)}…` : text;
+    }
+
+    // Handle accessibility snapshot requests
+    async function handleAccessibilitySnapshotRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received get-accessibility-snapshot, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+
+        try {
+            const { root, interactiveOnly, maxDepth } = event.payload ?? {};
+            const snapshot = buildAccessibilitySnapshot({ root, interactiveOnly, maxDepth });
+            await emit('get-accessibility-snapshot-response', {
+                requestId,
+                success: true,
+                data: { url: window.location.href, ...snapshot }
             });
-            const result = performLocalStorageOperation(action, processedKey, processedValue);
-            await emit('get-local-storage-response', result);
-            console.log('TAURI-PLUGIN-MCP: Emitted get-local-storage-response');
         }
         catch (error) {
-            console.error('TAURI-PLUGIN-MCP: Error handling localStorage request', error);
-            await emit('get-local-storage-response', {
+            console.error('TAURI-PLUGIN-MCP: Error building accessibility snapshot:', error);
+            await emit('get-accessibility-snapshot-response', {
+                requestId,
+                success: false,
+                error: error instanceof Error ? error.message : String(error)
+            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        }
+    }
+    // ========== Storage ==========
+
+
+
+    // Records an IndexedDB get or keys returns when the request sets no limit
+    const DEFAULT_RECORD_LIMIT = 100;
+
+    async function handleStorageRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received manage-storage, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+
+        try {
+            const data = await performStorageOperation(event.payload);
+            await emit('manage-storage-response', { requestId, success: true, data });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling storage request', error);
+            await emit('manage-storage-response', {
+                requestId,
                 success: false,
                 error: error instanceof Error ? error.toString() : String(error)
             }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
         }
     }
-    function performLocalStorageOperation(action, key, value) {
-        console.log('TAURI-PLUGIN-MCP: LocalStorage operation', {
-            action,
-            key: typeof key === 'undefined' ? 'undefined' : key,
-            value: typeof value === 'undefined' ? 'undefined' : value,
-            keyType: typeof key,
-            valueType: typeof value
-        });
+
+    // The Rust side has already checked that the action suits the store and has its arguments
+    async function performStorageOperation(request) {
+        switch (request.store) {
+            case 'local_storage':
+                return webStorageOperation(localStorage, request);
+            case 'session_storage':
+                return webStorageOperation(sessionStorage, request);
+            case 'cookies':
+                return cookieOperation(request);
+            case 'indexed_db':
+                return indexedDbOperation(request);
+            default:
+                throw new Error(`Unsupported store: ${request.store}`);
+        }
+    }
+
+    function webStorageOperation(storage, { action, key, value }) {
         switch (action) {
             case 'get':
                 if (!key) {
-                    console.log('TAURI-PLUGIN-MCP: Getting all localStorage items');
-                    // If no key is provided, return all localStorage items
-                    const allItems = {};
-                    for (let i = 0; i < localStorage.length; i++) {
-                        const k = localStorage.key(i);
-                        if (k) {
-                            allItems[k] = localStorage.getItem(k) || '';
+                    // Without a key, return every item
+                    const items = {};
+                    for (let i = 0; i < storage.length; i++) {
+                        const k = storage.key(i);
+                        if (k !== null) {
+                            items[k] = storage.getItem(k) ?? '';
                         }
                     }
-                    return {
-                        success: true,
-                        data: allItems
-                    };
+                    return items;
                 }
-                console.log(`TAURI-PLUGIN-MCP: Getting localStorage item with key: ${key}`);
-                return {
-                    success: true,
-                    data: localStorage.getItem(String(key))
-                };
+                return storage.getItem(key);
             case 'set':
-                if (!key) {
-                    console.log('TAURI-PLUGIN-MCP: Set operation failed - no key provided');
-                    throw new Error('Key is required for set operation');
-                }
-                if (value === undefined) {
-                    console.log('TAURI-PLUGIN-MCP: Set operation failed - no value provided');
-                    throw new Error('Value is required for set operation');
-                }
-                const keyStr = String(key);
-                const valueStr = String(value);
-                console.log(`TAURI-PLUGIN-MCP: Setting localStorage item: ${keyStr} = ${valueStr}`);
-                localStorage.setItem(keyStr, valueStr);
-                return { success: true };
+                storage.setItem(key, value);
+                return null;
             case 'remove':
-                if (!key) {
-                    console.log('TAURI-PLUGIN-MCP: Remove operation failed - no key provided');
-                    throw new Error('Key is required for remove operation');
-                }
-                console.log(`TAURI-PLUGIN-MCP: Removing localStorage item with key: ${key}`);
-                localStorage.removeItem(String(key));
-                return { success: true };
+                storage.removeItem(key);
+                return null;
             case 'clear':
-                console.log('TAURI-PLUGIN-MCP: Clearing all localStorage items');
-                localStorage.clear();
-                return { success: true };
+                storage.clear();
+                return null;
             case 'keys':
-                console.log('TAURI-PLUGIN-MCP: Getting all localStorage keys');
-                return {
-                    success: true,
-                    data: Object.keys(localStorage)
-                };
+                return Object.keys(storage);
             default:
-                console.log(`TAURI-PLUGIN-MCP: Unsupported localStorage action: ${action}`);
-                throw new Error(`Unsupported localStorage action: ${action}`);
+                throw new Error(`Unsupported action for web storage: ${action}`);
         }
     }
-    // Handle JS execution requests
-    async function handleJsExecutionRequest(event) {
-        console.log('TAURI-PLUGIN-MCP: Received execute-js, payload:', event.payload);
-        try {
-            // Extract the code to execute
-            const code = event.payload;
-            // Execute the code
-            const result = executeJavaScript(code);
-            // Prepare response with result and type information
-            // Ensure all values are properly stringified for the Rust side (expects r.as_str())
-            let resultStr;
-            if (result === undefined) {
-                resultStr = 'undefined';
-            }
-            else if (result === null) {
-                resultStr = 'null';
-            }
-            else if (typeof result === 'object') {
-                resultStr = JSON.stringify(result);
-            }
-            else {
-                resultStr = String(result);
+
+    // The cookies document.cookie shows: this page's, minus HttpOnly ones
+    function documentCookies() {
+        const cookies = {};
+        for (const part of document.cookie.split(';')) {
+            if (!part.trim()) {
+                continue;
             }
-            const response = {
-                result: resultStr,
-                type: typeof result
-            };
-            // Send back the result
-            await emit('execute-js-response', response);
-            console.log('TAURI-PLUGIN-MCP: Emitted execute-js-response');
+            const separator = part.indexOf('=');
+            const name = separator < 0 ? '' : part.slice(0, separator).trim();
+            cookies[name] = (separator < 0 ? part : part.slice(separator + 1)).trim();
         }
-        catch (error) {
-            console.error('TAURI-PLUGIN-MCP: Error executing JavaScript:', error);
-            const errorMessage = error instanceof Error ? error.toString() : String(error);
-            await emit('execute-js-response', {
-                result: null,
-                type: 'error',
-                error: errorMessage
-            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        return cookies;
+    }
+
+    function cookieAttributes(options, expire) {
+        const attributes = [`path=${options?.path ?? '/'}`];
+        if (options?.domain) {
+            attributes.push(`domain=${options.domain}`);
+        }
+        if (expire) {
+            attributes.push('max-age=0');
         }
+        else if (options?.maxAge != null) {
+            attributes.push(`max-age=${options.maxAge}`);
+        }
+        if (options?.secure) {
+            attributes.push('secure');
+        }
+        if (options?.sameSite) {
+            attributes.push(`samesite=${options.sameSite}`);
+        }
+        return attributes.join('; ');
     }
-    // Function to safely execute JavaScript code
+
+    function cookieOperation({ action, key, value, cookie }) {
+        switch (action) {
+            case 'get': {
+                const cookies = documentCookies();
+                return key ? (cookies[key] ?? null) : cookies;
+            }
+            case 'keys':
+                return Object.keys(documentCookies());
+            case 'set':
+                document.cookie = `${key}=${value}; ${cookieAttributes(cookie, false)}`;
+                // The webview drops cookies it won't store (e.g. on custom schemes) without an error
+                return { stored: documentCookies()[key] === value };
+            case 'remove':
+                document.cookie = `${key}=; ${cookieAttributes(cookie, true)}`;
+                return { removed: !(key in documentCookies()) };
+            case 'clear':
+                for (const name of Object.keys(documentCookies())) {
+                    document.cookie = `${name}=; ${cookieAttributes(cookie, true)}`;
+                }
+                // Cookies set for another path or domain survive; report them
+                return { remaining: Object.keys(documentCookies()) };
+            default:
+                throw new Error(`Unsupported action for cookies: ${action}`);
+        }
+    }
+
+    function idbRequest(request) {
+        return new Promise((resolve, reject) => {
+            request.onsuccess = () => resolve(request.result);
+            request.onerror = () => reject(request.error ?? new Error('IndexedDB request failed'));
+        });
+    }
+
+    // Open an existing database at its current version, without creating it
+    function openDatabase(name) {
+        return new Promise((resolve, reject) => {
+            const request = indexedDB.open(name);
+            // Opening without a version only upgrades a database that doesn't exist yet
+            request.onupgradeneeded = () => {
+                request.transaction?.abort();
+                reject(new Error(`IndexedDB database "${name}" does not exist`));
+            };
+            request.onsuccess = () => resolve(request.result);
+            request.onerror = () => reject(request.error ?? new Error(`Failed to open IndexedDB database "${name}"`));
+            request.onblocked = () => reject(new Error(`IndexedDB database "${name}" is blocked by an open upgrade`));
+        });
+    }
+
+    // Record keys arrive as text: JSON numbers, strings and arrays keep their type, anything else is a string key
+    function parseRecordKey(key) {
+        try {
+            const parsed = JSON.parse(key);
+            if (typeof parsed === 'number' || typeof parsed === 'string' || Array.isArray(parsed)) {
+                return parsed;
+            }
+        }
+        catch {
+            // Not JSON
+        }
+        return key;
+    }
+
+    // Record values arrive as JSON text; anything that isn't JSON is stored as a string
+    function parseRecordValue(value) {
+        try {
+            return JSON.parse(value);
+        }
+        catch {
+            return value;
+        }
+    }
+
+    // The first `limit` records of the store in key order, with their keys
+    function readRecords(store, limit) {
+        return new Promise((resolve, reject) => {
+            const records = [];
+            const request = store.openCursor();
+            request.onsuccess = () => {
+                const cursor = request.result;
+                if (cursor && records.length < limit) {
+                    records.push({ key: serializeJsValue(cursor.primaryKey), value: serializeJsValue(cursor.value) });
+                    cursor.continue();
+                }
+                else {
+                    resolve(records);
+                }
+            };
+            request.onerror = () => reject(request.error ?? new Error('IndexedDB cursor failed'));
+        });
+    }
+
+    async function indexedDbOperation({ action, database, objectStore, key, value, limit }) {
+        if (action === 'databases') {
+            const databases = await indexedDB.databases();
+            return databases.map(({ name, version }) => ({ name: name ?? null, version: version ?? null }));
+        }
+
+        const db = await openDatabase(database);
+        try {
+            const storeNames = Array.from(db.objectStoreNames);
+            if (action === 'object_stores') {
+                if (storeNames.length === 0) {
+                    return [];
+                }
+                const transaction = db.transaction(storeNames, 'readonly');
+                return await Promise.all(storeNames.map(async name => {
+                    const store = transaction.objectStore(name);
+                    return {
+                        name,
+                        keyPath: store.keyPath,
+                        autoIncrement: store.autoIncrement,
+                        indexes: Array.from(store.indexNames),
+                        count: await idbRequest(store.count())
+                    };
+                }));
+            }
+
+            if (!storeNames.includes(objectStore)) {
+                throw new Error(`Object store "${objectStore}" not found in "${database}"; it has: ${storeNames.join(', ') || 'none'}`);
+            }
+            const readOnly = action === 'get' || action === 'keys';
+            const store = db.transaction(objectStore, readOnly ? 'readonly' : 'readwrite').objectStore(objectStore);
+            switch (action) {
+                case 'get':
+                    if (key != null) {
+                        return serializeJsValue(await idbRequest(store.get(parseRecordKey(key))));
+                    }
+                    return await readRecords(store, limit ?? DEFAULT_RECORD_LIMIT);
+                case 'keys':
+                    return serializeJsValue(await idbRequest(store.getAllKeys(null, limit ?? DEFAULT_RECORD_LIMIT)));
+                case 'set': {
+                    const record = parseRecordValue(value);
+                    if (key != null && store.keyPath !== null) {
+                        throw new Error(`Object store "${objectStore}" keeps keys in its records (keyPath ${JSON.stringify(store.keyPath)}); put the key in the value instead`);
+                    }
+                    const stored = await idbRequest(key != null ? store.put(record, parseRecordKey(key)) : store.put(record));
+                    return { key: serializeJsValue(stored) };
+                }
+                case 'remove':
+                    await idbRequest(store.delete(parseRecordKey(key)));
+                    return null;
+                case 'clear':
+                    await idbRequest(store.clear());
+                    return null;
+                default:
+                    throw new Error(`Unsupported action for IndexedDB: ${action}`);
+            }
+        }
+        finally {
+            // Waits for the transaction to finish before closing
+            db.close();
+        }
+    }
+
+    // How long a returned Promise may take to settle when the request sets no timeout
+    const DEFAULT_JS_TIMEOUT_MS = 5000;
+    // Nesting depth past which serializeJsValue stops descending
+    const MAX_SERIALIZE_DEPTH = 20;
+    // Longest outerHTML / text preview included for DOM nodes
+    const MAX_NODE_PREVIEW = 300;
+    // Handle JS execution requests
+    async function handleJsExecutionRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received execute-js, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+        try {
+            // Extract the code to execute
+            const code = event.payload.code;
+            const timeoutMs = event.payload.timeoutMs ?? DEFAULT_JS_TIMEOUT_MS;
+            // Execute the code, awaiting the result if it is a Promise (or any thenable)
+            let result = executeJavaScript(code);
+            if (result !== null && typeof result?.then === 'function') {
+                result = await settleWithin(Promise.resolve(result), timeoutMs);
+            }
+            // The result is sent as JSON; values JSON.stringify can't represent are tagged
+            const response = {
+                requestId,
+                result: serializeJsValue(result),
+                type: typeof result
+            };
+            // Send back the result
+            await emit('execute-js-response', response);
+            console.log('TAURI-PLUGIN-MCP: Emitted execute-js-response');
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error executing JavaScript:', error);
+            const errorMessage = error instanceof Error ? error.toString() : String(error);
+            await emit('execute-js-response', {
+                requestId,
+                result: null,
+                type: 'error',
+                error: errorMessage,
+                stack: error instanceof Error ? error.stack ?? null : null,
+                details: errorDetails(error)
+            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        }
+    }
+    // Reject with a timeout error if the promise hasn't settled within timeoutMs
+    function settleWithin(promise, timeoutMs) {
+        return new Promise((resolve, reject) => {
+            const timer = setTimeout(() => reject(new WaitTimeoutError(`Promise did not settle within ${timeoutMs} ms`, { code: 'timeout', timeoutMs })), timeoutMs);
+            promise.then(value => { clearTimeout(timer); resolve(value); }, error => { clearTimeout(timer); reject(error); });
+        });
+    }
+    /**
+     * Converts any JavaScript value into JSON-safe data.
+     * Values JSON.stringify drops or throws on become tagged objects with a `$type`:
+     * cycles, DOM nodes, Maps, Sets, Errors (with stack), Dates, functions, symbols,
+     * bigints and non-finite numbers. `undefined` becomes null.
+     */
+    function serializeJsValue(value, ancestors = [], depth = 0) {
+        switch (typeof value) {
+            case 'undefined':
+                return null;
+            case 'string':
+            case 'boolean':
+                return value;
+            case 'number':
+                return Number.isFinite(value) ? value : { $type: 'number', value: String(value) };
+            case 'bigint':
+                return { $type: 'bigint', value: value.toString() };
+            case 'symbol':
+                return { $type: 'symbol', description: value.description ?? null };
+            case 'function':
+                return { $type: 'function', name: value.name || null };
+        }
+        if (value === null) {
+            return null;
+        }
+        if (ancestors.includes(value)) {
+            return { $type: 'circular' };
+        }
+        if (depth >= MAX_SERIALIZE_DEPTH) {
+            return { $type: 'maxDepth' };
+        }
+        const inner = [...ancestors, value];
+        const serialize = (v) => serializeJsValue(v, inner, depth + 1);
+        if (value instanceof Error) {
+            const error = {
+                $type: 'error',
+                name: value.name,
+                message: value.message,
+                stack: value.stack ?? null
+            };
+            if ('cause' in value) {
+                error.cause = serialize(value.cause);
+            }
+            return error;
+        }
+        if (typeof Element !== 'undefined' && value instanceof Element) {
+            return {
+                $type: 'element',
+                tagName: value.tagName.toLowerCase(),
+                id: value.id || null,
+                className: typeof value.className === 'string' ? value.className || null : null,
+                outerHTML: value.outerHTML.substring(0, MAX_NODE_PREVIEW)
+            };
+        }
+        if (typeof Document !== 'undefined' && value instanceof Document) {
+            return { $type: 'document', url: value.URL, title: value.title };
+        }
+        if (typeof Node !== 'undefined' && value instanceof Node) {
+            return {
+                $type: 'node',
+                nodeName: value.nodeName,
+                textContent: value.textContent?.substring(0, MAX_NODE_PREVIEW) ?? null
+            };
+        }
+        if (typeof Window !== 'undefined' && value instanceof Window) {
+            return { $type: 'window', location: value.location?.href ?? null };
+        }
+        if (value instanceof Map) {
+            return { $type: 'map', entries: Array.from(value.entries(), ([k, v]) => [serialize(k), serialize(v)]) };
+        }
+        if (value instanceof Set) {
+            return { $type: 'set', values: Array.from(value.values(), serialize) };
+        }
+        if (value instanceof Date) {
+            return { $type: 'date', value: Number.isNaN(value.getTime()) ? null : value.toISOString() };
+        }
+        if (value instanceof RegExp) {
+            return { $type: 'regexp', value: String(value) };
+        }
+        if (value instanceof Promise) {
+            return { $type: 'promise' };
+        }
+        if (ArrayBuffer.isView(value) && !(value instanceof DataView)) {
+            return { $type: value.constructor.name, values: Array.from(value, serialize) };
+        }
+        if (Array.isArray(value)) {
+            return value.map(serialize);
+        }
+        if (typeof value.toJSON === 'function') {
+            return serialize(value.toJSON());
+        }
+        const result = {};
+        for (const key of Object.keys(value)) {
+            try {
+                result[key] = serialize(value[key]);
+            }
+            catch (error) {
+                // Throwing getters are reported in place
+                result[key] = serialize(error);
+            }
+        }
+        return result;
+    }
+    // Function to safely execute JavaScript code
     function executeJavaScript(code) {
         // Using Function constructor is slightly safer than eval
         // It runs in global scope rather than local scope
@@ -3779,55 +4605,822 @@
             return new Function(code)();
         }
     }
-    async function handleSendTextToElementRequest(event) {
-        console.log('TAURI-PLUGIN-MCP: Received send-text-to-element, payload:', event.payload);
+    // Most recent console entries kept per webview; older ones are evicted first
+    const MAX_CONSOLE_ENTRIES = 1000;
+    const CONSOLE_LEVELS = ['debug', 'log', 'info', 'warn', 'error'];
+
+    const consoleEntries = [];
+    let droppedConsoleEntries = 0;
+    let consoleCaptureInstalled = false;
+    let recordingConsoleEntry = false;
+
+    // Wrap the console methods and listen for uncaught errors and unhandled rejections.
+    // Safe to call more than once; only the first call installs anything.
+    function installConsoleCapture() {
+        if (consoleCaptureInstalled) {
+            return;
+        }
+        consoleCaptureInstalled = true;
+
+        for (const level of CONSOLE_LEVELS) {
+            const original = console[level].bind(console);
+            console[level] = (...args) => {
+                original(...args);
+                // The plugin's own logging would drown out the app's
+                if (typeof args[0] === 'string' && args[0].startsWith('TAURI-PLUGIN-MCP')) {
+                    return;
+                }
+                const stack = level === 'warn' || level === 'error' ? callerStack() : null;
+                recordConsoleEntry(level, 'console', args, stack);
+            };
+        }
+
+        window.addEventListener('error', (event) => {
+            const error = event.error ?? event.message;
+            recordConsoleEntry('error', 'uncaught', [error], error instanceof Error ? error.stack ?? null : null);
+        });
+        window.addEventListener('unhandledrejection', (event) => {
+            const reason = event.reason;
+            recordConsoleEntry('error', 'unhandledrejection', [reason], reason instanceof Error ? reason.stack ?? null : null);
+        });
+    }
+
+    function recordConsoleEntry(level, source, args, stack) {
+        // Serializing can run app code (toJSON, getters) that logs again
+        if (recordingConsoleEntry) {
+            return;
+        }
+        recordingConsoleEntry = true;
         try {
-            const { selectorType, selectorValue, text, delayMs = 20 } = event.payload;
-            // Find the element based on the selector type
-            let element = null;
-            let debugInfo = [];
-            switch (selectorType) {
-                case 'id':
-                    element = document.getElementById(selectorValue);
-                    if (!element) {
-                        debugInfo.push(`No element found with id="${selectorValue}"`);
+            consoleEntries.push({
+                level,
+                timestamp: Date.now(),
+                source,
+                message: args.map(formatConsoleArg).join(' '),
+                args: args.map(arg => serializeJsValue(arg)),
+                stack
+            });
+            if (consoleEntries.length > MAX_CONSOLE_ENTRIES) {
+                consoleEntries.shift();
+                droppedConsoleEntries++;
+            }
+        }
+        catch {
+            // Never let capture break the app's own logging
+        }
+        finally {
+            recordingConsoleEntry = false;
+        }
+    }
+
+    // Render an argument the way devtools prints it inline
+    function formatConsoleArg(arg) {
+        if (typeof arg === 'string') {
+            return arg;
+        }
+        if (arg instanceof Error) {
+            return arg.toString();
+        }
+        const serialized = serializeJsValue(arg);
+        return typeof serialized === 'string' ? serialized : JSON.stringify(serialized);
+    }
+
+    // The stack of whoever called the console method, without the capture frames
+    function callerStack() {
+        const stack = new Error().stack;
+        if (!stack) {
+            return null;
+        }
+        // V8 prefixes the frames with an "Error" line; both engines list our two frames first
+        const frames = stack.split('\n').filter(line => line.trim() !== 'Error');
+        return frames.slice(2).join('\n') || null;
+    }
+
+    // Handle console log requests; filtering happens on the Rust side
+    async function handleGetConsoleLogsRequest(event) {
+        const requestId = event.payload?.requestId;
+
+        try {
+            const entries = consoleEntries.slice();
+            const dropped = droppedConsoleEntries;
+            if (event.payload?.clear) {
+                consoleEntries.length = 0;
+                droppedConsoleEntries = 0;
+            }
+            await emit('get-console-logs-response', { requestId, entries, dropped });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling get-console-logs:', error);
+            await emit('get-console-logs-response', { requestId, entries: [], dropped: 0 }).catch(e =>
+                console.error('TAURI-PLUGIN-MCP: Error emitting error response', e)
+            );
+        }
+    }
+    // Most recent network records kept per webview; older ones are evicted first
+    const MAX_NETWORK_RECORDS = 500;
+    // Longest request or response body kept per record
+    const MAX_NETWORK_BODY = 2000;
+
+    const networkRecords = [];
+    let droppedNetworkRecords = 0;
+    let nextNetworkRecordId = 1;
+    let networkCaptureInstalled = false;
+    // When capture began, so a page that never made a request counts as idle from then on
+    let networkCaptureStarted = 0;
+    const xhrRequests = new WeakMap();
+
+    // Wrap fetch and XMLHttpRequest. Tauri's invoke() goes through fetch to the ipc:// custom
+    // protocol on desktop, so those calls are recorded as 'invoke' with the command as the url.
+    // Safe to call more than once; only the first call installs anything.
+    function installNetworkCapture() {
+        if (networkCaptureInstalled) {
+            return;
+        }
+        networkCaptureInstalled = true;
+        networkCaptureStarted = Date.now();
+
+        const originalFetch = window.fetch.bind(window);
+        window.fetch = async (input, init) => {
+            const url = input instanceof Request ? input.url : String(input);
+            const command = ipcCommand(url);
+            // Event emits and listens are the plugin's own transport
+            if (command?.startsWith('plugin:event|')) {
+                return originalFetch(input, init);
+            }
+
+            const method = (init?.method ?? (input instanceof Request ? input.method : 'GET')).toUpperCase();
+            const record = command !== null
+                ? startNetworkRecord('invoke', 'INVOKE', command, init?.body)
+                : startNetworkRecord('fetch', method, url, init?.body);
+            const started = performance.now();
+            try {
+                const response = await originalFetch(input, init);
+                record.status = response.status;
+                record.ok = command !== null ? response.headers.get('Tauri-Response') === 'ok' : response.ok;
+                record.durationMs = performance.now() - started;
+                // Read a clone so the caller can still consume the body
+                response.clone().text().then(
+                    text => {
+                        record.responseBody = truncateBody(text);
+                        if (command !== null && !record.ok) {
+                            record.error = record.responseBody;
+                        }
+                    },
+                    () => { record.responseBody = '[unreadable body]'; }
+                );
+                return response;
+            }
+            catch (error) {
+                record.ok = false;
+                record.durationMs = performance.now() - started;
+                record.error = String(error);
+                throw error;
+            }
+        };
+
+        const originalOpen = XMLHttpRequest.prototype.open;
+        XMLHttpRequest.prototype.open = function (method, url, ...rest) {
+            xhrRequests.set(this, { method: method.toUpperCase(), url: String(url) });
+            return originalOpen.call(this, method, url, ...rest);
+        };
+
+        const originalSend = XMLHttpRequest.prototype.send;
+        XMLHttpRequest.prototype.send = function (body) {
+            const request = xhrRequests.get(this);
+            if (request) {
+                const record = startNetworkRecord('xhr', request.method, request.url, body);
+                const started = performance.now();
+                this.addEventListener('loadend', () => {
+                    record.durationMs = performance.now() - started;
+                    record.status = this.status || null;
+                    record.ok = this.status >= 200 && this.status < 300;
+                    if (this.status === 0) {
+                        record.error = 'Request failed or was aborted';
                     }
-                    break;
-                case 'class':
-                    // Get the first element with the class
-                    const elemsByClass = document.getElementsByClassName(selectorValue);
-                    element = elemsByClass.length > 0 ? elemsByClass[0] : null;
-                    if (!element) {
-                        debugInfo.push(`No elements found with class="${selectorValue}" (total matching: 0)`);
+                    record.responseBody = this.responseType === '' || this.responseType === 'text'
+                        ? truncateBody(this.responseText)
+                        : `[${this.responseType} response]`;
+                });
+            }
+            return originalSend.call(this, body);
+        };
+    }
+
+    // The command name when url targets Tauri's IPC custom protocol, otherwise null
+    function ipcCommand(url) {
+        const match = /^(?:ipc:\/\/localhost|https?:\/\/ipc\.localhost)\/([^?#]*)/.exec(url);
+        return match ? decodeURIComponent(match[1]) : null;
+    }
+
+    function startNetworkRecord(kind, method, url, body) {
+        const record = {
+            id: nextNetworkRecordId++,
+            kind,
+            method,
+            url,
+            requestBody: describeBody(body),
+            status: null,
+            ok: null,
+            responseBody: null,
+            startTime: Date.now(),
+            durationMs: null,
+            error: null
+        };
+        networkRecords.push(record);
+        if (networkRecords.length > MAX_NETWORK_RECORDS) {
+            networkRecords.shift();
+            droppedNetworkRecords++;
+        }
+        return record;
+    }
+
+    // Request bodies as text; binary bodies are summarized
+    function describeBody(body) {
+        if (body === undefined || body === null) {
+            return null;
+        }
+        if (typeof body === 'string') {
+            return truncateBody(body);
+        }
+        if (body instanceof URLSearchParams) {
+            return truncateBody(body.toString());
+        }
+        if (body instanceof FormData) {
+            const fields = {};
+            body.forEach((value, key) => {
+                fields[key] = typeof value === 'string' ? value : `[File ${value.name}, ${value.size} bytes]`;
+            });
+            return truncateBody(JSON.stringify(fields));
+        }
+        if (body instanceof Blob) {
+            return `[Blob, ${body.size} bytes]`;
+        }
+        if (body instanceof ArrayBuffer || ArrayBuffer.isView(body)) {
+            return `[Binary, ${body.byteLength} bytes]`;
+        }
+        return truncateBody(JSON.stringify(serializeJsValue(body)));
+    }
+
+    function truncateBody(text) {
+        return text.length > MAX_NETWORK_BODY
+            ? `${text.slice(0, MAX_NETWORK_BODY)}... [truncated, ${text.length} chars]`
+            : text;
+    }
+
+    // Handle network record requests; filtering happens on the Rust side
+    async function handleGetNetworkRequestsRequest(event) {
+        const requestId = event.payload?.requestId;
+
+        try {
+            const requests = networkRecords.slice();
+            const dropped = droppedNetworkRecords;
+            if (event.payload?.clear) {
+                networkRecords.length = 0;
+                droppedNetworkRecords = 0;
+            }
+            await emit('get-network-requests-response', { requestId, requests, dropped });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling get-network-requests:', error);
+            await emit('get-network-requests-response', { requestId, requests: [], dropped: 0 }).catch(e =>
+                console.error('TAURI-PLUGIN-MCP: Error emitting error response', e)
+            );
+        }
+    }
+    // ========== Waiting ==========
+
+    // A wait that ran out of time, with the condition's last observed state as details
+    class WaitTimeoutError extends Error {
+        constructor(message, details) {
+            super(message);
+            this.name = 'WaitTimeoutError';
+            this.details = details;
+        }
+    }
+
+    // Structured details of selector, wait and click failures, for the response's data
+    function errorDetails(error) {
+        return error instanceof SelectorError || error instanceof WaitTimeoutError || error instanceof ElementCoveredError
+            ? error.details
+            : null;
+    }
+
+
+    /**
+     * Runs `check` every `intervalMs` until it reports done, failing with its last reason after
+     * `timeoutMs`. Lookups that found no element or several are retried; other errors end the wait.
+     */
+    async function pollUntil(description, check, timeoutMs, intervalMs) {
+        const started = performance.now();
+        for (;;) {
+            let result;
+            try {
+                result = await check();
+            }
+            catch (error) {
+                const retryable = error instanceof SelectorError
+                    && (error.details.code === 'element_not_found' || error.details.code === 'ambiguous_selector');
+                if (!retryable) {
+                    throw error;
+                }
+                result = { done: false, reason: error.message, details: error.details };
+            }
+
+            const elapsedMs = Math.round(performance.now() - started);
+            if (result.done) {
+                return { value: result.value, elapsedMs };
+            }
+            if (elapsedMs >= timeoutMs) {
+                throw new WaitTimeoutError(
+                    `Timed out after ${timeoutMs}ms waiting for ${description}: ${result.reason}`,
+                    { code: 'timeout', timeoutMs, reason: result.reason, ...result.details }
+                );
+            }
+            await new Promise(resolve => setTimeout(resolve, Math.min(intervalMs, timeoutMs - elapsedMs)));
+        }
+    }
+
+    // Rendered with a non-empty box and not visibility:hidden (opacity doesn't count, as in Playwright)
+    function isVisibleElement(element) {
+        const rect = element.getBoundingClientRect();
+        return rect.width > 0 && rect.height > 0 && getComputedStyle(element).visibility !== 'hidden';
+    }
+
+    function isEnabledElement(element) {
+        return !element.matches(':disabled') && element.closest('[aria-disabled="true"]') === null;
+    }
+
+    function isEditableElement(element) {
+        if (element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement) {
+            return !element.readOnly;
+        }
+        return element.isContentEditable;
+    }
+
+    // Offset a point in the element's own viewport by the iframes it sits in, giving top-level viewport coordinates
+    function topViewportPoint(element, x, y) {
+        let frame = element.ownerDocument.defaultView?.frameElement ?? null;
+        while (frame) {
+            const frameRect = frame.getBoundingClientRect();
+            x += frameRect.left + frame.clientLeft;
+            y += frameRect.top + frame.clientTop;
+            frame = frame.ownerDocument.defaultView?.frameElement ?? null;
+        }
+        return { x, y };
+    }
+
+    // Whether a click at the element's center would land on it rather than on something covering it
+    function receivesPointerEvents(element) {
+        const rect = element.getBoundingClientRect();
+        const x = rect.left + rect.width / 2;
+        const y = rect.top + rect.height / 2;
+        // Off-screen points can't be hit-tested; the caller scrolls or clicks by coordinates
+        if (x < 0 || y < 0 || x > window.innerWidth || y > window.innerHeight) {
+            return true;
+        }
+        const hit = element.ownerDocument.elementFromPoint(x, y);
+        return hit !== null && (hit === element || element.contains(hit));
+    }
+
+    // Element commands re-check actionability this often while auto-waiting
+    const AUTO_WAIT_INTERVAL_MS = 50;
+
+    /**
+     * Resolves the selector, retrying for up to `timeoutMs` until the element is visible, has kept
+     * the same box between two checks, is enabled, and optionally is editable and not covered.
+     */
+    async function waitForActionableElement(selector, timeoutMs, requirements = {}) {
+        let previous = null;
+        const { value } = await pollUntil(`${describeSelector(selector)} to be actionable`, () => {
+            const element = resolveElement(selector);
+            const rect = element.getBoundingClientRect();
+            const stable = previous !== null && previous.element === element
+                && previous.rect.x === rect.x && previous.rect.y === rect.y
+                && previous.rect.width === rect.width && previous.rect.height === rect.height;
+            previous = { element, rect };
+
+            if (!isVisibleElement(element)) {
+                return { done: false, reason: 'element is not visible' };
+            }
+            if (!stable) {
+                return { done: false, reason: 'element is still moving' };
+            }
+            if (!isEnabledElement(element)) {
+                return { done: false, reason: 'element is disabled' };
+            }
+            if (requirements.editable && !isEditableElement(element)) {
+                return { done: false, reason: 'element is not editable' };
+            }
+            if (requirements.hitTarget && !receivesPointerEvents(element)) {
+                return { done: false, reason: 'element is covered by another element' };
+            }
+            return { done: true, value: element };
+        }, timeoutMs, AUTO_WAIT_INTERVAL_MS);
+        return value;
+    }
+
+    // The element for an element command: a single lookup, or an actionability wait when autoWaitMs is set
+    async function targetElement(selector, autoWaitMs, requirements = {}) {
+        const element = autoWaitMs > 0
+            ? await waitForActionableElement(selector, autoWaitMs, requirements)
+            : resolveElement(selector);
+        return element;
+    }
+
+
+    // The check and description polled for each wait_for condition
+    function waitCondition(options, timeoutMs) {
+        const { condition, selector } = options;
+        switch (condition) {
+            case 'visible':
+            case 'enabled':
+                return {
+                    description: `${describeSelector(selector)} to be ${condition}`,
+                    check: () => {
+                        const element = resolveElement(selector);
+                        if (!isVisibleElement(element)) {
+                            return { done: false, reason: 'element is not visible' };
+                        }
+                        if (condition === 'enabled' && !isEnabledElement(element)) {
+                            return { done: false, reason: 'element is disabled' };
+                        }
+                        return { done: true, value: { ref: refForElement(element) } };
                     }
-                    else if (elemsByClass.length > 1) {
-                        debugInfo.push(`Found ${elemsByClass.length} elements with class="${selectorValue}", using the first one`);
+                };
+            case 'hidden':
+                return {
+                    description: `${describeSelector(selector)} to be hidden`,
+                    check: () => {
+                        const matches = findElements(selector);
+                        const considered = selector.nth != null ? matches.slice(selector.nth, selector.nth + 1) : matches;
+                        const visible = considered.filter(isVisibleElement);
+                        return visible.length === 0
+                            ? { done: true, value: { matchCount: matches.length } }
+                            : { done: false, reason: `${visible.length} matching element(s) still visible` };
                     }
-                    break;
-                case 'tag':
-                    // Get the first element with the tag name
-                    const elemsByTag = document.getElementsByTagName(selectorValue);
-                    element = elemsByTag.length > 0 ? elemsByTag[0] : null;
-                    if (!element) {
-                        debugInfo.push(`No elements found with tag="${selectorValue}" (total matching: 0)`);
+                };
+            case 'text': {
+                const text = options.text ?? '';
+                return {
+                    description: `text "${text}"${selector ? ` in ${describeSelector(selector)}` : ''}`,
+                    check: () => {
+                        const scope = selector ? resolveElement(selector) : document.body;
+                        const content = scope.innerText ?? scope.textContent ?? '';
+                        return textMatches(content, text, false)
+                            ? { done: true, value: null }
+                            : { done: false, reason: 'text not found' };
                     }
-                    else if (elemsByTag.length > 1) {
-                        debugInfo.push(`Found ${elemsByTag.length} elements with tag="${selectorValue}", using the first one`);
+                };
+            }
+            case 'url': {
+                const url = options.url ?? '';
+                const pattern = options.urlRegex ? new RegExp(url) : null;
+                return {
+                    description: `the URL to match ${pattern ? pattern : `"${url}"`}`,
+                    check: () => {
+                        const href = window.location.href;
+                        return (pattern ? pattern.test(href) : href.includes(url))
+                            ? { done: true, value: { url: href } }
+                            : { done: false, reason: `URL is ${href}`, details: { url: href } };
                     }
-                    break;
-                case 'text':
-                    // Find element by text content
-                    element = findElementByText(selectorValue);
-                    if (!element) {
-                        debugInfo.push(`No element found with text="${selectorValue}"`);
+                };
+            }
+            case 'predicate': {
+                const predicate = compilePredicate(options.predicate ?? '');
+                return {
+                    description: 'the predicate to return a truthy value',
+                    check: async () => {
+                        // A predicate whose Promise never settles would otherwise outlive the wait
+                        const value = await settleWithin(Promise.resolve(predicate()), timeoutMs);
+                        return value
+                            ? { done: true, value: serializeJsValue(value) }
+                            : { done: false, reason: `predicate returned ${String(value)}` };
                     }
-                    break;
-                default:
-                    throw new Error(`Unsupported selector type: ${selectorType}`);
+                };
             }
-            if (!element) {
-                throw new Error(`Element with ${selectorType}="${selectorValue}" not found. ${debugInfo.join(' ')}`);
+            case 'network_idle': {
+                const idleMs = options.idleMs ?? 500;
+                return {
+                    description: `the network to be idle for ${idleMs}ms`,
+                    check: () => {
+                        const inFlight = networkRecords.filter(record => record.ok === null);
+                        if (inFlight.length > 0) {
+                            return {
+                                done: false,
+                                reason: `${inFlight.length} request(s) in flight`,
+                                details: { inFlight: inFlight.map(record => `${record.method} ${record.url}`) }
+                            };
+                        }
+                        const lastActivity = Math.max(
+                            networkCaptureStarted,
+                            ...networkRecords.map(record => record.startTime + (record.durationMs ?? 0))
+                        );
+                        const quietMs = Date.now() - lastActivity;
+                        return quietMs >= idleMs
+                            ? { done: true, value: { quietMs } }
+                            : { done: false, reason: `last request finished ${quietMs}ms ago` };
+                    }
+                };
             }
+            default:
+                throw new Error(`Unknown wait condition: ${condition}`);
+        }
+    }
+
+    // Compile a predicate once, as an expression if it parses as one and as a function body otherwise
+    function compilePredicate(code) {
+        try {
+            return new Function(`return (${code})`);
+        }
+        catch {
+            return new Function(code);
+        }
+    }
+
+    async function handleWaitForRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received wait-for, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+
+        try {
+            const { timeoutMs = 5000, pollIntervalMs = 100, ...options } = event.payload;
+            const { description, check } = waitCondition(options, timeoutMs);
+            const { value, elapsedMs } = await pollUntil(description, check, timeoutMs, pollIntervalMs);
+            await emit('wait-for-response', {
+                requestId,
+                success: true,
+                data: { condition: options.condition, elapsedMs, value }
+            });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling wait-for request', error);
+            await emit('wait-for-response', {
+                requestId,
+                success: false,
+                error: error instanceof Error ? error.toString() : String(error),
+                details: errorDetails(error)
+            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        }
+    }
+    // ========== Key Events ==========
+
+
+    // KeyboardEvent.code of the modifier keys press_keys sends
+    const MODIFIER_CODES = {
+        Control: 'ControlLeft',
+        Shift: 'ShiftLeft',
+        Alt: 'AltLeft',
+        Meta: 'MetaLeft'
+    };
+
+    /**
+     * Dispatches keydown/keyup events for each chord to `target`: modifiers go down in order,
+     * the key is held for holdMs, then everything comes up in reverse. Synthetic events reach
+     * listeners but trigger no default action. Returns whether each chord's keydown was cancelled.
+     */
+    async function dispatchKeyChords(target, chords, holdMs, delayMs) {
+        const sleep = (ms) => new Promise(resolve => setTimeout(resolve, ms));
+        const cancelled = [];
+
+        for (const [index, chord] of chords.entries()) {
+            if (index > 0) {
+                await sleep(delayMs);
+            }
+
+            const held = { ctrlKey: false, shiftKey: false, altKey: false, metaKey: false };
+            const fire = (type, key, code) => target.dispatchEvent(new KeyboardEvent(type, {
+                key, code, ...held, bubbles: true, cancelable: true, composed: true
+            }));
+            const flag = (modifier) => `${modifier === 'Control' ? 'ctrl' : modifier.toLowerCase()}Key`;
+
+            for (const modifier of chord.modifiers) {
+                held[flag(modifier)] = true;
+                fire('keydown', modifier, MODIFIER_CODES[modifier]);
+            }
+            if (chord.key !== null) {
+                cancelled.push(!fire('keydown', chord.key, chord.code ?? ''));
+                await sleep(holdMs);
+                fire('keyup', chord.key, chord.code ?? '');
+            }
+            else {
+                cancelled.push(false);
+                await sleep(holdMs);
+            }
+            for (const modifier of [...chord.modifiers].reverse()) {
+                held[flag(modifier)] = false;
+                fire('keyup', modifier, MODIFIER_CODES[modifier]);
+            }
+        }
+        return cancelled;
+    }
+
+    async function handlePressKeysRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received press-keys, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+
+        try {
+            const { selector, chords, holdMs = 30, delayMs = 50 } = event.payload;
+            // Without a selector, keys go where a real key press would: the focused element
+            const target = selector ? resolveElement(selector) : (document.activeElement ?? document.body);
+            const cancelled = await dispatchKeyChords(target, chords, holdMs, delayMs);
+            await emit('press-keys-response', {
+                requestId,
+                success: true,
+                data: {
+                    target: { tag: target.tagName, id: target.id || null, ref: refForElement(target) },
+                    cancelled
+                }
+            });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling press-keys request', error);
+            await emit('press-keys-response', {
+                requestId,
+                success: false,
+                error: error instanceof Error ? error.toString() : String(error)
+            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        }
+    }
+    // ========== Element Clicks ==========
+
+    class ElementCoveredError extends Error {
+        constructor(message, details) {
+            super(message);
+            this.name = 'ElementCoveredError';
+            this.details = details;
+        }
+    }
+
+    // MouseEvent.button, and .buttons while pressed, of each button click_element can use
+    const MOUSE_BUTTONS = {
+        left: { button: 0, buttons: 1 },
+        middle: { button: 1, buttons: 4 },
+        right: { button: 2, buttons: 2 }
+    };
+
+    // Elements a real mousedown moves focus to
+    const FOCUSABLE_SELECTOR = 'a[href], button, input, select, textarea, summary, [tabindex], [contenteditable]:not([contenteditable="false"])';
+
+    // How long an OS click may take to arrive before its record is dropped
+    const ARMED_CLICK_TTL_MS = 10000;
+    // How long the confirmation waits for an OS click that hasn't arrived yet
+    const CLICK_CONFIRM_WAIT_MS = 1000;
+
+    // OS clicks waiting for the real pointer, by request id: the first element that got a trusted mousedown
+    const armedClicks = new Map();
+
+    function describeClickTarget(element) {
+        if (!element) {
+            return null;
+        }
+        return {
+            ref: refForElement(element),
+            tag: element.tagName.toLowerCase(),
+            id: element.id || null,
+            text: truncateText(collapseWhitespace(element.innerText ?? element.textContent ?? ''))
+        };
+    }
+
+    /**
+     * Scrolls the element into view and hit-tests its center. Unless `force` is set, a center
+     * covered by another element (or outside the viewport) fails with an ElementCoveredError.
+     */
+    function prepareClick(element, force) {
+        element.scrollIntoView({ block: 'center', inline: 'center' });
+        const rect = element.getBoundingClientRect();
+        const x = rect.left + rect.width / 2;
+        const y = rect.top + rect.height / 2;
+        // Hit-test in the element's own tree so shadow DOM content isn't reported as its host
+        const root = element.getRootNode();
+        const hit = root.elementFromPoint(x, y);
+        const covered = hit === null || (hit !== element && !element.contains(hit));
+        if (covered && !force) {
+            const by = hit ? `<${hit.tagName.toLowerCase()}${hit.id ? `#${hit.id}` : ''}>` : 'nothing (the center is outside the viewport)';
+            throw new ElementCoveredError(`Element is covered at its center by ${by}; pass force to click it anyway`, {
+                code: 'element_covered',
+                target: describeClickTarget(element),
+                hit: describeClickTarget(hit),
+                point: { x, y }
+            });
+        }
+        return { x, y, hit, covered };
+    }
+
+    /**
+     * Fires the pointer and mouse events of `clickCount` clicks at (x, y) on `target`, focusing it
+     * like a real mousedown would. Returns whether the page cancelled the last click event.
+     */
+    function dispatchClicks(target, x, y, button, clickCount) {
+        const { button: code, buttons } = MOUSE_BUTTONS[button] ?? MOUSE_BUTTONS.left;
+        const init = {
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            view: target.ownerDocument.defaultView,
+            clientX: x,
+            clientY: y,
+            button: code
+        };
+        const pointer = { pointerId: 1, pointerType: 'mouse', isPrimary: true };
+        const clickType = button === 'left' ? 'click' : button === 'right' ? 'contextmenu' : 'auxclick';
+
+        let cancelled = false;
+        for (let detail = 1; detail <= clickCount; detail++) {
+            target.dispatchEvent(new PointerEvent('pointerdown', { ...init, ...pointer, buttons, detail }));
+            if (target.dispatchEvent(new MouseEvent('mousedown', { ...init, buttons, detail })) && detail === 1) {
+                target.closest(FOCUSABLE_SELECTOR)?.focus();
+            }
+            target.dispatchEvent(new PointerEvent('pointerup', { ...init, ...pointer, buttons: 0, detail }));
+            target.dispatchEvent(new MouseEvent('mouseup', { ...init, buttons: 0, detail }));
+            cancelled = !target.dispatchEvent(new MouseEvent(clickType, { ...init, buttons: 0, detail }));
+            if (detail === 2 && button === 'left') {
+                target.dispatchEvent(new MouseEvent('dblclick', { ...init, buttons: 0, detail }));
+            }
+        }
+        return cancelled;
+    }
+
+    // Start recording which element the coming OS click lands on, in the element's own frame
+    function armClick(requestId, element) {
+        const view = element.ownerDocument.defaultView ?? window;
+        const record = { received: null, disarm: () => { } };
+        const listener = (event) => {
+            if (event.isTrusted && record.received === null) {
+                record.received = event.composedPath()[0] ?? event.target;
+            }
+        };
+        view.addEventListener('mousedown', listener, true);
+        const expiry = setTimeout(() => record.disarm(), ARMED_CLICK_TTL_MS);
+        record.disarm = () => {
+            clearTimeout(expiry);
+            view.removeEventListener('mousedown', listener, true);
+            armedClicks.delete(requestId);
+        };
+        armedClicks.set(requestId, record);
+    }
+
+    async function handleClickElementRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received click-element, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+
+        try {
+            const { selector, mode = 'dom', button = 'left', clickCount = 1, autoWaitMs = 0, force = false } = event.payload;
+            const element = await targetElement(selector, autoWaitMs, { hitTarget: !force });
+            const { x, y, hit, covered } = prepareClick(element, force);
+
+            const data = {
+                target: describeClickTarget(element),
+                hit: describeClickTarget(hit),
+                covered,
+                // Where the OS pointer should go, measured from the webview's top-left corner
+                ...topViewportPoint(element, x, y)
+            };
+            if (mode === 'dom') {
+                // Events go where a real click would land, unless force asks for the covered element itself
+                const receiver = covered || !hit ? element : hit;
+                data.cancelled = dispatchClicks(receiver, x, y, button, clickCount);
+                data.received = describeClickTarget(receiver);
+            }
+            else {
+                armClick(requestId, element);
+            }
+
+            await emit('click-element-response', { requestId, success: true, data });
+        }
+        catch (error) {
+            console.error('TAURI-PLUGIN-MCP: Error handling click-element request', error);
+            await emit('click-element-response', {
+                requestId,
+                success: false,
+                error: error instanceof Error ? error.toString() : String(error),
+                details: errorDetails(error)
+            }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
+        }
+    }
+
+    // After an OS click, report the element that received it and stop recording
+    async function handleConfirmClickRequest(event) {
+        const { requestId, clickId } = event.payload;
+        const record = armedClicks.get(clickId);
+        const deadline = Date.now() + CLICK_CONFIRM_WAIT_MS;
+        while (record && record.received === null && Date.now() < deadline) {
+            await new Promise(resolve => setTimeout(resolve, 20));
+        }
+        record?.disarm();
+
+        await emit('confirm-click-response', {
+            requestId,
+            success: true,
+            data: { received: describeClickTarget(record?.received ?? null) }
+        }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting confirm-click response', e));
+    }
+
+    async function handleSendTextToElementRequest(event) {
+        console.log('TAURI-PLUGIN-MCP: Received send-text-to-element, payload:', event.payload);
+        const requestId = event.payload?.requestId;
+        try {
+            const { selector, text, delayMs = 20, autoWaitMs = 0 } = event.payload;
+            // Find the element with the shared selector engine, waiting until it is actionable if asked to
+            const element = await targetElement(selector, autoWaitMs, { editable: true });
             // Check if the element is an input field, textarea, or has contentEditable
             const isEditableElement = element instanceof HTMLInputElement ||
                 element instanceof HTMLTextAreaElement ||
@@ -3866,6 +5459,7 @@
                 console.warn('TAURI-PLUGIN-MCP: Element is not an input, textarea, or contentEditable. Text was set directly but may not behave as expected.');
             }
             await emit('send-text-to-element-response', {
+                requestId,
                 success: true,
                 data: {
                     element: {
@@ -3882,8 +5476,10 @@
         catch (error) {
             console.error('TAURI-PLUGIN-MCP: Error handling send-text-to-element request', error);
             await emit('send-text-to-element-response', {
+                requestId,
                 success: false,
-                error: error instanceof Error ? error.toString() : String(error)
+                error: error instanceof Error ? error.toString() : String(error),
+                details: errorDetails(error)
             }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
         }
     }
@@ -4203,11 +5799,13 @@
     // This approach doesn't require Screen Recording permissions or window focus.
     async function handleCaptureScreenshotRequest(event) {
         console.log('TAURI-PLUGIN-MCP: Received capture-screenshot request');
+        const requestId = event.payload?.requestId;
         try {
             const { quality = 85, maxWidth = 1920 } = event.payload || {};
             // Capture the screenshot using canvas
             const dataUrl = await capturePageAsImage(quality, maxWidth);
             await emit('capture-screenshot-response', {
+                requestId,
                 success: true,
                 data: dataUrl
             });
@@ -4216,6 +5814,7 @@
         catch (error) {
             console.error('TAURI-PLUGIN-MCP: Error capturing screenshot:', error);
             await emit('capture-screenshot-response', {
+                requestId,
                 success: false,
                 error: error instanceof Error ? error.toString() : String(error)
             }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
@@ -4223,11 +5822,12 @@
     }
     async function handleIframeRpcRequest(event) {
         console.log('TAURI-PLUGIN-MCP: Received iframe-rpc, payload:', event.payload);
+        const requestId = event.payload?.requestId;
         try {
             const { method, args } = event.payload;
             const iframe = document.getElementById('moss-preview-iframe');
             if (!iframe?.contentWindow) {
-                await emit('iframe-rpc-response', { success: false, error: 'No iframe found' });
+                await emit('iframe-rpc-response', { requestId, success: false, error: 'No iframe found' });
                 return;
             }
             const id = `rpc-${Date.now()}-${Math.random().toString(36).substr(2, 9)}`;
@@ -4250,12 +5850,13 @@
             iframe.contentWindow.postMessage({ type: 'moss-rpc-call', id, method, args }, '*');
             // Wait for response and emit back to Rust
             const response = await responsePromise;
-            await emit('iframe-rpc-response', response);
+            await emit('iframe-rpc-response', { requestId, ...response });
             console.log('TAURI-PLUGIN-MCP: Emitted iframe-rpc-response');
         }
         catch (error) {
             console.error('TAURI-PLUGIN-MCP: Error handling iframe-rpc request', error);
             await emit('iframe-rpc-response', {
+                requestId,
                 success: false,
                 error: error instanceof Error ? error.toString() : String(error)
             }).catch(e => console.error('TAURI-PLUGIN-MCP: Error emitting error response', e));
@@ -0,0 +1,275 @@
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::Schema;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// How `selector_value` is matched against the page's elements.
+/// Parsed case-insensitively, so "ID", "XPath" and "testId" are accepted too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectorType {
+    /// The element's id attribute
+    Id,
+    /// A class name the element carries
+    Class,
+    /// The element's tag name
+    Tag,
+    /// Visible text, placeholder, title or aria-label
+    Text,
+    /// Any CSS selector
+    Css,
+    /// An XPath expression that selects elements
+    Xpath,
+    /// An ARIA role, explicit or implied by the tag; narrow it with `name`
+    Role,
+    /// The element's data-testid attribute
+    TestId,
+    /// A ref such as "e12" from get_accessibility_snapshot
+    Ref,
+}
+
+const SELECTOR_TYPES: &[&str] = &["id", "class", "tag", "text", "css", "xpath", "role", "test_id", "ref"];
+
+impl<'de> Deserialize<'de> for SelectorType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        // Ignore case and word separators: "Class", "TEST_ID", "test-id" and "testId" all match
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '_' | '-'))
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        Ok(match normalized.as_str() {
+            "id" => SelectorType::Id,
+            "class" => SelectorType::Class,
+            "tag" => SelectorType::Tag,
+            "text" => SelectorType::Text,
+            "css" => SelectorType::Css,
+            "xpath" => SelectorType::Xpath,
+            "role" => SelectorType::Role,
+            "testid" => SelectorType::TestId,
+            "ref" => SelectorType::Ref,
+            _ => return Err(de::Error::unknown_variant(&name, SELECTOR_TYPES)),
+        })
+    }
+}
+
+// The selector as clients send it, checked before it becomes an `ElementSelector`
+#[derive(Deserialize, JsonSchema)]
+struct RawElementSelector {
+    /// How selector_value is matched: "id", "class", "tag", "text", "css", "xpath", "role", "test_id"
+    /// or "ref" (a ref from get_accessibility_snapshot)
+    selector_type: SelectorType,
+    /// Value to match for the selector type
+    selector_value: String,
+    /// Accessible name the element must have, for the "role" selector type
+    name: Option<String>,
+    /// Match text and accessible names exactly (whitespace-normalized) instead of as
+    /// case-insensitive substrings
+    #[serde(default)]
+    exact: bool,
+    /// Zero-based index of the match to use when the selector matches several elements.
+    /// Without it an ambiguous selector fails and lists the candidates.
+    nth: Option<usize>,
+    /// CSS selectors of the iframes, shadow hosts or containers to search inside, outermost
+    /// first; each must match exactly one element
+    #[serde(default)]
+    within: Vec<String>,
+}
+
+/// A validated element selector, resolved in the webview by the guest script's selector engine.
+///
+/// Commands that target an element flatten this into their payload, so every one of them
+/// accepts the same selector types and options.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawElementSelector")]
+pub struct ElementSelector {
+    pub selector_type: SelectorType,
+    pub selector_value: String,
+    pub name: Option<String>,
+    pub exact: bool,
+    pub nth: Option<usize>,
+    pub within: Vec<String>,
+}
+
+impl ElementSelector {
+    /// A selector matching `selector_value` as a CSS selector
+    pub fn css(selector_value: impl Into<String>) -> Self {
+        Self {
+            selector_type: SelectorType::Css,
+            selector_value: selector_value.into(),
+            name: None,
+            exact: false,
+            nth: None,
+            within: Vec::new(),
+        }
+    }
+
+    /// The selector as the guest script's `resolveElement` reads it
+    pub fn to_js(&self) -> Value {
+        json!({
+            "selectorType": self.selector_type,
+            "selectorValue": self.selector_value,
+            "name": self.name,
+            "exact": self.exact,
+            "nth": self.nth,
+            "within": self.within
+        })
+    }
+}
+
+impl TryFrom<RawElementSelector> for ElementSelector {
+    type Error = String;
+
+    fn try_from(raw: RawElementSelector) -> Result<Self, Self::Error> {
+        let value = raw.selector_value.trim();
+        if value.is_empty() {
+            return Err("selector_value must not be empty".to_string());
+        }
+
+        match raw.selector_type {
+            SelectorType::Id | SelectorType::Class if value.contains(char::is_whitespace) => {
+                return Err(format!(
+                    "\"{}\" contains whitespace; id and class selectors match a single name, use css to combine several",
+                    value
+                ));
+            }
+            SelectorType::Tag if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') => {
+                return Err(format!("\"{}\" is not a tag name", value));
+            }
+            SelectorType::Ref if !is_element_ref(value) => {
+                return Err(format!(
+                    "\"{}\" is not an element ref; refs look like \"e12\" and come from get_accessibility_snapshot",
+                    value
+                ));
+            }
+            _ => {}
+        }
+
+        if raw.name.is_some() && raw.selector_type != SelectorType::Role {
+            return Err("name can only be used with the \"role\" selector type".to_string());
+        }
+        if raw.exact && !matches!(raw.selector_type, SelectorType::Text | SelectorType::Role) {
+            return Err("exact can only be used with the \"text\" and \"role\" selector types".to_string());
+        }
+        if raw.within.iter().any(|step| step.trim().is_empty()) {
+            return Err("within must not contain empty selectors".to_string());
+        }
+
+        Ok(Self {
+            selector_type: raw.selector_type,
+            selector_value: value.to_string(),
+            name: raw.name,
+            exact: raw.exact,
+            nth: raw.nth,
+            within: raw.within,
+        })
+    }
+}
+
+// Payload schemas describe the fields clients send, so reuse the raw selector's
+impl JsonSchema for ElementSelector {
+    fn schema_name() -> String {
+        RawElementSelector::schema_name()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        RawElementSelector::json_schema(generator)
+    }
+}
+
+/// An element a command acted on, as the guest script describes it
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ElementRef {
+    /// Selects the element again with the "ref" selector type
+    #[serde(rename = "ref")]
+    element_ref: String,
+    tag: String,
+    id: Option<String>,
+    /// Visible text, truncated; only reported by some commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+// Refs handed out by get_accessibility_snapshot: "e" followed by a number
+fn is_element_ref(value: &str) -> bool {
+    value
+        .strip_prefix('e')
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: Value) -> Result<ElementSelector, String> {
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_parse_and_to_js() {
+        let selector = parse(json!({
+            "selector_type": "role",
+            "selector_value": "button",
+            "name": "Save",
+            "exact": true,
+            "within": ["#editor iframe"]
+        }))
+        .unwrap();
+
+        assert_eq!(selector.selector_type, SelectorType::Role);
+        assert_eq!(
+            selector.to_js(),
+            json!({
+                "selectorType": "role",
+                "selectorValue": "button",
+                "name": "Save",
+                "exact": true,
+                "nth": null,
+                "within": ["#editor iframe"]
+            })
+        );
+        assert_eq!(ElementSelector::css("#app").to_js()["selectorType"], "css");
+    }
+
+    #[test]
+    fn test_rejects_invalid_selectors() {
+        let cases = [
+            json!({ "selector_type": "css", "selector_value": "  " }),
+            json!({ "selector_type": "id", "selector_value": "save button" }),
+            json!({ "selector_type": "tag", "selector_value": "div.item" }),
+            json!({ "selector_type": "ref", "selector_value": "12" }),
+            json!({ "selector_type": "text", "selector_value": "Save", "name": "Save" }),
+            json!({ "selector_type": "css", "selector_value": "#save", "exact": true }),
+            json!({ "selector_type": "css", "selector_value": "#save", "within": [""] }),
+            json!({ "selector_type": "label", "selector_value": "Save" }),
+        ];
+
+        for case in cases {
+            assert!(parse(case.clone()).is_err(), "accepted {}", case);
+        }
+        assert!(parse(json!({ "selector_type": "ref", "selector_value": "e12" })).is_ok());
+        assert!(parse(json!({ "selector_type": "test_id", "selector_value": "save-button" })).is_ok());
+    }
+
+    #[test]
+    fn test_selector_type_ignores_case() {
+        for (name, expected) in [
+            ("ID", SelectorType::Id),
+            ("Class", SelectorType::Class),
+            ("XPath", SelectorType::Xpath),
+            ("testId", SelectorType::TestId),
+            ("TEST-ID", SelectorType::TestId),
+        ] {
+            let selector = parse(json!({ "selector_type": name, "selector_value": "save" })).unwrap();
+            assert_eq!(selector.selector_type, expected, "{}", name);
+        }
+
+        // The guest script still gets the canonical name
+        let selector = parse(json!({ "selector_type": "TestId", "selector_value": "save" })).unwrap();
+        assert_eq!(selector.to_js()["selectorType"], "test_id");
+    }
+}
@@ -78,8 +78,10 @@ pub struct TextInputResult {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MouseMovementParams {
+    pub window_label: Option<String>,
     pub x: i32,
     pub y: i32,
+    pub unit: Option<crate::models::CoordinateUnit>,
     pub relative: Option<bool>,
     pub click: Option<bool>,
     pub button: Option<String>, // "left", "right", or "middle"
@@ -127,15 +129,60 @@ pub trait McpInterface {
 /// Command string constants for socket commands
 pub mod commands {
     pub const PING: &str = "ping";
+    pub const AUTH: &str = "auth"; // TCP handshake, must be the first request when a token is configured
     pub const TAKE_SCREENSHOT: &str = "take_screenshot";
     pub const CAPTURE_SCREENSHOT: &str = "capture_screenshot"; // JS-based screenshot (no permissions/focus needed)
     pub const GET_DOM: &str = "get_dom";
-    pub const MANAGE_LOCAL_STORAGE: &str = "manage_local_storage";
+    pub const GET_ACCESSIBILITY_SNAPSHOT: &str = "get_accessibility_snapshot"; // Pruned role tree with element refs
+    pub const MANAGE_STORAGE: &str = "manage_storage"; // localStorage, sessionStorage, cookies and IndexedDB
     pub const EXECUTE_JS: &str = "execute_js";
+    pub const GET_CONSOLE_LOGS: &str = "get_console_logs"; // Buffered console output and uncaught errors
+    pub const GET_NETWORK_REQUESTS: &str = "get_network_requests"; // Recorded fetch, XHR and invoke calls
+    pub const CLEAR_NETWORK_REQUESTS: &str = "clear_network_requests";
     pub const IFRAME_RPC: &str = "iframe_rpc";
     pub const MANAGE_WINDOW: &str = "manage_window";
+    pub const LIST_WINDOWS: &str = "list_windows"; // Every window and webview with state and geometry
     pub const SIMULATE_TEXT_INPUT: &str = "simulate_text_input";
+    pub const PRESS_KEYS: &str = "press_keys"; // Key chords such as "Mod+S", via enigo or DOM events
     pub const SIMULATE_MOUSE_MOVEMENT: &str = "simulate_mouse_movement";
+    pub const POINTER_ACTION: &str = "pointer_action"; // Clicks, drags, hover, press/hold and scrolling via enigo
     pub const GET_ELEMENT_POSITION: &str = "get_element_position";
     pub const SEND_TEXT_TO_ELEMENT: &str = "send_text_to_element";
+    pub const CLICK_ELEMENT: &str = "click_element"; // Scroll, hit-test and click an element via DOM events or the OS pointer
+    pub const WAIT_FOR: &str = "wait_for"; // Poll the webview until a condition holds
+    pub const GET_CAPABILITIES: &str = "get_capabilities"; // Commands and the active command policy
+    pub const DESCRIBE: &str = "describe"; // Versions, transport, command schemas and platform capabilities
+
+    /// Alias of `DESCRIBE`, for clients that open with a handshake
+    pub const HELLO: &str = "hello";
+
+    /// Former name of `MANAGE_STORAGE`, still accepted and treated as it
+    pub const MANAGE_LOCAL_STORAGE: &str = "manage_local_storage";
+
+    /// Every command routed by `tools::handle_command`
+    pub const ALL: &[&str] = &[
+        PING,
+        TAKE_SCREENSHOT,
+        CAPTURE_SCREENSHOT,
+        GET_DOM,
+        GET_ACCESSIBILITY_SNAPSHOT,
+        MANAGE_STORAGE,
+        EXECUTE_JS,
+        GET_CONSOLE_LOGS,
+        GET_NETWORK_REQUESTS,
+        CLEAR_NETWORK_REQUESTS,
+        IFRAME_RPC,
+        MANAGE_WINDOW,
+        LIST_WINDOWS,
+        SIMULATE_TEXT_INPUT,
+        PRESS_KEYS,
+        SIMULATE_MOUSE_MOVEMENT,
+        POINTER_ACTION,
+        GET_ELEMENT_POSITION,
+        SEND_TEXT_TO_ELEMENT,
+        CLICK_ELEMENT,
+        WAIT_FOR,
+        GET_CAPABILITIES,
+        DESCRIBE,
+    ];
 }
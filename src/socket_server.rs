@@ -1,58 +1,48 @@
-use interprocess::TryClone;
-use interprocess::local_socket::{
-    GenericFilePath, GenericNamespaced, Listener as IpcListener, ListenerOptions, Name, Stream as IpcStream, ToFsName,
-    ToNsName, prelude::*,
-};
+use futures::FutureExt;
+use interprocess::local_socket::tokio::{Listener as IpcListener, prelude::*};
+use interprocess::local_socket::{GenericFilePath, GenericNamespaced, ListenerOptions, Name, ToFsName, ToNsName};
 use serde_json::Value;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use std::net::TcpListener as StdTcpListener;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Runtime};
-use log::{info, error};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc, oneshot, watch};
+use tokio::task::JoinSet;
+use log::{info, error, warn};
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::Error;
+use crate::auth::{TcpAuth, read_handshake};
+use crate::discovery::{self, InstanceRecord};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::models::SocketServerStatus;
 use crate::tools;
 use crate::SocketType;
 
-/// A wrapper stream that logs all reads and writes for debugging
-struct LoggingStream<S: Write + Read> {
-    inner: S,
-}
-
-impl<S: Write + Read> LoggingStream<S> {
-    fn new(inner: S) -> Self {
-        Self { inner }
-    }
-}
-
-impl<S: Write + Read> Write for LoggingStream<S> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        info!("[TAURI_MCP] Writing: {}", String::from_utf8_lossy(buf));
-        self.inner.write(buf)
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.inner.flush()
-    }
-}
-
-impl<S: Write + Read> Read for LoggingStream<S> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let n = self.inner.read(buf)?;
-        info!(
-            "[TAURI_MCP] Read: {}",
-            String::from_utf8_lossy(&buf[..n])
-        );
-        Ok(n)
-    }
-}
+/// Commands that may run at once when `PluginConfig::max_concurrent_commands` is unset
+pub const DEFAULT_MAX_CONCURRENT_COMMANDS: usize = 16;
+// Longest request line accepted; a longer line gets an error response and the connection is closed
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+// Responses queued per connection before finished commands wait for the writer
+const RESPONSE_QUEUE: usize = 32;
+// Pause after a failed accept so a persistent error doesn't spin
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+// How long stopping waits for in-flight commands before closing their connections
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+// Extra time a drain allows the tasks to close connections and release the socket
+const STOP_GRACE: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SocketRequest {
+    /// Optional client-supplied identifier, echoed back on the matching response
+    #[serde(default)]
+    id: Option<Value>,
     command: String,
     payload: Value,
 }
@@ -62,47 +52,11 @@ struct SocketRequest {
 pub struct SocketResponse {
     pub success: bool,
     pub data: Option<Value>,
-    pub error: Option<String>,
-}
-
-/// Unified stream type that can handle both IPC and TCP
-enum UnifiedStream {
-    Ipc(IpcStream),
-    Tcp(TcpStream),
-}
-
-impl Read for UnifiedStream {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        match self {
-            UnifiedStream::Ipc(stream) => stream.read(buf),
-            UnifiedStream::Tcp(stream) => stream.read(buf),
-        }
-    }
-}
-
-impl Write for UnifiedStream {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        match self {
-            UnifiedStream::Ipc(stream) => stream.write(buf),
-            UnifiedStream::Tcp(stream) => stream.write(buf),
-        }
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        match self {
-            UnifiedStream::Ipc(stream) => stream.flush(),
-            UnifiedStream::Tcp(stream) => stream.flush(),
-        }
-    }
-}
-
-impl UnifiedStream {
-    fn try_clone(&self) -> std::io::Result<Self> {
-        match self {
-            UnifiedStream::Ipc(stream) => Ok(UnifiedStream::Ipc(stream.try_clone()?)),
-            UnifiedStream::Tcp(stream) => Ok(UnifiedStream::Tcp(stream.try_clone()?)),
-        }
-    }
+    /// Why the request failed: `{code, message, details, retryable}`
+    pub error: Option<ErrorInfo>,
+    /// The `id` of the request this response answers, if the client supplied one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
 }
 
 /// Unified listener type that can handle both IPC and TCP
@@ -111,29 +65,62 @@ enum UnifiedListener {
     Tcp(TcpListener),
 }
 
+/// Socket server for the Node bridge and other clients.
+///
+/// Accepting, reading and writing all run as tasks on Tauri's shared tokio runtime. Requests
+/// that carry an `id` run concurrently and are answered in completion order; requests without
+/// one are answered in order. A semaphore bounds the commands in flight across all connections.
+///
+/// The server can be stopped and started again (on another socket, with `set_socket_type`)
+/// any number of times within one process. While running it keeps a discovery record so
+/// clients can find it with [`discovery::list_instances`].
 pub struct SocketServer<R: Runtime> {
-    listener: Option<Arc<Mutex<UnifiedListener>>>,
     socket_type: SocketType,
     app: AppHandle<R>,
-    running: Arc<Mutex<bool>>,
+    application_name: String,
+    auth: TcpAuth,
+    permits: Arc<Semaphore>,
+    max_concurrent_commands: usize,
+    connections: Arc<AtomicUsize>,
+    running: Option<RunningServer>,
+}
+
+// Handles to a started server's accept loop
+struct RunningServer {
+    shutdown: watch::Sender<bool>,
+    // Closes once the accept loop and every connection task have finished
+    stopped: oneshot::Receiver<()>,
+}
+
+/// The rest of a [`SocketServer::stop`]: in-flight commands finishing and connections closing.
+///
+/// Await [`ServerDrain::wait`] before binding the same socket again. Dropping it leaves the
+/// server to finish stopping in the background.
+pub struct ServerDrain {
+    stopped: Option<oneshot::Receiver<()>>,
+}
+
+impl ServerDrain {
+    /// Wait for the stopped server's tasks to finish, up to 6 seconds
+    pub async fn wait(self) {
+        let Some(stopped) = self.stopped else {
+            return;
+        };
+        // The sender side is dropped, never used, once everything has closed
+        if tokio::time::timeout(DRAIN_TIMEOUT + STOP_GRACE, stopped).await.is_err() {
+            warn!("[TAURI_MCP] Socket server tasks did not finish within the shutdown deadline");
+        }
+        info!("[TAURI_MCP] Socket server stopped");
+    }
 }
 
 impl<R: Runtime> SocketServer<R> {
-    pub fn new(app: AppHandle<R>, socket_type: SocketType) -> Self {
+    pub fn new(app: AppHandle<R>, application_name: String, socket_type: SocketType, auth: TcpAuth) -> Self {
         match &socket_type {
             SocketType::Ipc { path } => {
-                let socket_path = if let Some(path) = path {
-                    path.to_string_lossy().to_string()
-                } else {
-                    let temp_dir = std::env::temp_dir();
-                    temp_dir
-                        .join("tauri-mcp.sock")
-                        .to_string_lossy()
-                        .to_string()
-                };
                 info!(
                     "[TAURI_MCP] Initializing IPC socket server at: {}",
-                    socket_path
+                    socket_path(path, &application_name).display()
                 );
             }
             SocketType::Tcp { host, port } => {
@@ -145,440 +132,539 @@ impl<R: Runtime> SocketServer<R> {
         }
 
         SocketServer {
-            listener: None,
             socket_type,
             app,
-            running: Arc::new(Mutex::new(false)),
+            application_name,
+            auth,
+            permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_COMMANDS)),
+            max_concurrent_commands: DEFAULT_MAX_CONCURRENT_COMMANDS,
+            connections: Arc::new(AtomicUsize::new(0)),
+            running: None,
+        }
+    }
+
+    /// Bound the commands running at once across all connections (`None` keeps the default)
+    pub fn max_concurrent_commands(mut self, limit: Option<usize>) -> Self {
+        self.max_concurrent_commands = limit.unwrap_or(DEFAULT_MAX_CONCURRENT_COMMANDS).max(1);
+        self.permits = Arc::new(Semaphore::new(self.max_concurrent_commands));
+        self
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Listen on `socket_type` the next time the server starts
+    pub fn set_socket_type(&mut self, socket_type: SocketType) {
+        self.socket_type = socket_type;
+    }
+
+    pub fn status(&self) -> SocketServerStatus {
+        let (transport, address) = match &self.socket_type {
+            SocketType::Ipc { path } => ("ipc", discovery::ipc_address(&socket_path(path, &self.application_name))),
+            SocketType::Tcp { host, port } => ("tcp", format!("{}:{}", host, port)),
+        };
+        SocketServerStatus {
+            running: self.is_running(),
+            transport: transport.to_string(),
+            address,
+            connections: self.connections.load(Ordering::SeqCst),
+            commands_in_flight: self.max_concurrent_commands - self.permits.available_permits(),
         }
     }
 
+    /// Bind the socket and start accepting clients. Does nothing if the server is already running.
     pub fn start(&mut self) -> crate::Result<()> {
         eprintln!("[TAURI_MCP] SocketServer::start() called");
+        if self.is_running() {
+            info!("[TAURI_MCP] Socket server is already running");
+            return Ok(());
+        }
         info!("[TAURI_MCP] Starting socket server...");
 
-        let listener = match &self.socket_type {
+        // Tokio listeners register with the runtime that polls them
+        let runtime = tauri::async_runtime::handle();
+        let _guard = runtime.inner().enter();
+
+        let (listener, record) = match &self.socket_type {
             SocketType::Ipc { path } => {
                 eprintln!("[TAURI_MCP] Creating IPC listener...");
-                // Create a name for our socket based on the platform
-                let socket_name = self.get_socket_name(path)?;
+                let path = socket_path(path, &self.application_name);
+                // A crashed run leaves its socket file behind; reclaim it unless a server still answers
+                discovery::clear_stale_socket(&path)?;
 
                 // Configure and create the IPC listener
-                let opts = ListenerOptions::new().name(socket_name);
-                let ipc_listener = opts.create_sync()
+                let opts = ListenerOptions::new().name(socket_name(&path)?);
+                let ipc_listener = opts.create_tokio()
                     .map_err(|e| {
                         eprintln!("[TAURI_MCP] ERROR creating IPC socket: {}", e);
                         info!("[TAURI_MCP] Error creating IPC socket listener: {}", e);
                         if e.kind() == std::io::ErrorKind::AddrInUse {
-                            Error::Io(format!("Socket address already in use. Try: rm -f {}",
-                                path.as_ref().map(|p| p.display().to_string()).unwrap_or_default()))
+                            Error::Io(format!("Socket {} is in use by another running instance", path.display()))
                         } else {
                             Error::Io(format!("Failed to create local socket: {}", e))
                         }
                     })?;
+                discovery::restrict_socket(&path)?;
 
-                // Verify socket file was created (Unix only)
-                if let Some(p) = path {
-                    if p.exists() {
-                        eprintln!("[TAURI_MCP] Socket file created successfully: {}", p.display());
-                    } else {
-                        eprintln!("[TAURI_MCP] WARNING: Socket file not found after creation: {}", p.display());
-                    }
-                }
-
-                UnifiedListener::Ipc(ipc_listener)
+                let address = discovery::ipc_address(&path);
+                let record = InstanceRecord::new(&self.application_name, "ipc", address);
+                (UnifiedListener::Ipc(ipc_listener), record)
             }
             SocketType::Tcp { host, port } => {
                 eprintln!("[TAURI_MCP] Creating TCP listener at {}:{}...", host, port);
-                // Create TCP listener
+                // Bind synchronously so errors reach the caller, then hand the socket to tokio
                 let addr = format!("{}:{}", host, port);
-                let tcp_listener = TcpListener::bind(&addr)
+                let tcp_listener = StdTcpListener::bind(&addr)
+                    .and_then(|listener| {
+                        warn_if_exposed(&listener, &self.auth);
+                        listener.set_nonblocking(true)?;
+                        TcpListener::from_std(listener)
+                    })
                     .map_err(|e| {
                         eprintln!("[TAURI_MCP] ERROR creating TCP socket: {}", e);
                         info!("[TAURI_MCP] Error creating TCP socket listener: {}", e);
                         Error::Io(format!("Failed to bind to {}: {}", addr, e))
                     })?;
-                UnifiedListener::Tcp(tcp_listener)
+
+                // The bound address, in case port 0 picked one
+                let address = tcp_listener.local_addr().map(|a| a.to_string()).unwrap_or(addr);
+                let record = InstanceRecord::new(&self.application_name, "tcp", address);
+                (UnifiedListener::Tcp(tcp_listener), record)
             }
         };
 
-        let listener = Arc::new(Mutex::new(listener));
-        self.listener = Some(listener.clone());
-
-        *self.running.lock().unwrap() = true;
-        eprintln!("[TAURI_MCP] Set running flag to true");
-        info!("[TAURI_MCP] Set running flag to true");
-
-        let app = self.app.clone();
-        let running = self.running.clone();
-        let socket_type = self.socket_type.clone();
-
-        // Spawn a thread to handle socket connections
-        eprintln!("[TAURI_MCP] Spawning listener thread");
-        info!("[TAURI_MCP] Spawning listener thread");
-        thread::spawn(move || {
-            match &socket_type {
-                SocketType::Ipc { .. } => {
-                    eprintln!("[TAURI_MCP] Listener thread started for IPC socket");
-                    info!("[TAURI_MCP] Listener thread started for IPC socket");
-                }
-                SocketType::Tcp { host, port } => {
-                    eprintln!("[TAURI_MCP] Listener thread started for TCP socket at {}:{}", host, port);
-                    info!("[TAURI_MCP] Listener thread started for TCP socket at {}:{}", host, port);
-                }
+        // Discovery is best effort; clients can still be pointed at the socket directly
+        match record.write() {
+            Ok(path) => info!("[TAURI_MCP] Wrote discovery record: {}", path.display()),
+            Err(e) => warn!("[TAURI_MCP] Failed to write discovery record: {}", e),
+        }
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (stopped_tx, stopped_rx) = oneshot::channel::<()>();
+        self.running = Some(RunningServer {
+            shutdown: shutdown_tx,
+            stopped: stopped_rx,
+        });
+
+        eprintln!("[TAURI_MCP] Spawning accept loop");
+        info!("[TAURI_MCP] Spawning accept loop");
+        let server = Server {
+            app: self.app.clone(),
+            auth: self.auth.clone(),
+            permits: self.permits.clone(),
+            connections: self.connections.clone(),
+            shutdown: shutdown_rx,
+        };
+        // Dropping the listener reclaims the socket file; remove it ourselves if that didn't happen
+        let socket_file = match &self.socket_type {
+            SocketType::Ipc { path } if !cfg!(target_os = "windows") => {
+                Some(socket_path(path, &self.application_name))
             }
+            _ => None,
+        };
+        tauri::async_runtime::spawn(async move {
+            accept_loop(listener, server, socket_file).await;
+            drop(stopped_tx);
+        });
 
-            // Set panic handler to suppress specific Windows named pipe errors
-            let original_hook = std::panic::take_hook();
-            std::panic::set_hook(Box::new(move |panic_info| {
-                let panic_payload = panic_info.payload();
-                let is_pipe_error = if let Some(s) = panic_payload.downcast_ref::<String>() {
-                    s.contains("No process is on the other end of the pipe")
-                } else if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                    s.contains("No process is on the other end of the pipe")
-                } else {
-                    false
-                };
-
-                // If it's not the Windows pipe disconnection error, pass to the original handler
-                if !is_pipe_error {
-                    original_hook(panic_info);
-                } else {
-                    // Just log the error instead of panicking
-                    info!(
-                        "[TAURI_MCP] Handled pipe disconnection (normal client disconnect)"
-                    );
-                }
-            }));
+        info!(
+            "[TAURI_MCP] Socket server started successfully at {}",
+            self.status().address
+        );
+        Ok(())
+    }
 
-            let listener_guard = listener.lock().unwrap();
+    /// Stop accepting clients and tell every connection to finish up. Returns at once; the
+    /// returned [`ServerDrain`] resolves when in-flight commands have finished (up to 5 seconds),
+    /// every connection is closed and the socket is released. Does nothing if stopped.
+    pub fn stop(&mut self) -> ServerDrain {
+        let Some(running) = self.running.take() else {
+            return ServerDrain { stopped: None };
+        };
+        info!("[TAURI_MCP] Stopping socket server");
+        let _ = running.shutdown.send(true);
+        discovery::remove_record();
+        ServerDrain {
+            stopped: Some(running.stopped),
+        }
+    }
+}
 
-            loop {
-                if !*running.lock().unwrap() {
-                    break;
-                }
+// State shared by the accept loop and connection tasks of one server run
+struct Server<R: Runtime> {
+    app: AppHandle<R>,
+    auth: TcpAuth,
+    permits: Arc<Semaphore>,
+    connections: Arc<AtomicUsize>,
+    shutdown: watch::Receiver<bool>,
+}
 
-                match &*listener_guard {
-                    UnifiedListener::Ipc(ipc_listener) => {
-                        // Handle IPC connections
-                        for conn in ipc_listener.incoming() {
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-
-                            match conn {
-                                Ok(stream) => {
-                                    info!("[TAURI_MCP] Accepted new IPC connection");
-                                    let app_clone = app.clone();
-                                    let unified_stream = UnifiedStream::Ipc(stream);
-
-                                    // Spawn a new thread with its own panic handler for client handling
-                                    thread::spawn(move || {
-                                        // Set a similar panic handler for the client handler thread
-                                        let client_hook = std::panic::take_hook();
-                                        std::panic::set_hook(Box::new(move |panic_info| {
-                                            let panic_payload = panic_info.payload();
-                                            let is_pipe_error = if let Some(s) =
-                                                panic_payload.downcast_ref::<String>()
-                                            {
-                                                s.contains("No process is on the other end of the pipe")
-                                            } else if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                                                s.contains("No process is on the other end of the pipe")
-                                            } else {
-                                                false
-                                            };
-
-                                            if !is_pipe_error {
-                                                client_hook(panic_info);
-                                            } else {
-                                                info!(
-                                                    "[TAURI_MCP] Handled pipe disconnection in client thread"
-                                                );
-                                            }
-                                        }));
-
-                                        // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
-                                            if e.to_string()
-                                                .contains("No process is on the other end of the pipe")
-                                            {
-                                                info!("[TAURI_MCP] Client disconnected normally");
-                                            } else {
-                                                error!("[TAURI_MCP] Error handling client: {}", e);
-                                            }
-                                        }
-                                    });
-                                }
-                                Err(e) => {
-                                    error!("[TAURI_MCP] Error accepting IPC connection: {}", e);
-                                    // Short sleep to avoid busy waiting in case of persistent errors
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                            }
-
-                            // Check the running flag after each connection
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-                        }
-                    }
-                    UnifiedListener::Tcp(tcp_listener) => {
-                        // Handle TCP connections
-                        // Set non-blocking mode to allow checking the running flag
-                        tcp_listener.set_nonblocking(true).ok();
-                        
-                        loop {
-                            if !*running.lock().unwrap() {
-                                break;
-                            }
-
-                            match tcp_listener.accept() {
-                                Ok((mut stream, addr)) => {
-                                    info!("[TAURI_MCP] Accepted new TCP connection from: {}", addr);
-                                    
-                                    // Set the stream back to blocking mode for normal I/O operations
-                                    if let Err(e) = stream.set_nonblocking(false) {
-                                        error!("[TAURI_MCP] Failed to set stream to blocking mode: {}", e);
-                                        continue;
-                                    }
-                                    
-                                    let app_clone = app.clone();
-                                    let unified_stream = UnifiedStream::Tcp(stream);
-
-                                    // Spawn a new thread for client handling
-                                    thread::spawn(move || {
-                                        // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
-                                            error!("[TAURI_MCP] Error handling TCP client: {}", e);
-                                        }
-                                    });
-                                }
-                                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                                    // No connection available, sleep briefly
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                                Err(e) => {
-                                    error!("[TAURI_MCP] Error accepting TCP connection: {}", e);
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            info!("[TAURI_MCP] Listener thread ending");
-        });
+// Manual impl: deriving would require `R: Clone`
+impl<R: Runtime> Clone for Server<R> {
+    fn clone(&self) -> Self {
+        Server {
+            app: self.app.clone(),
+            auth: self.auth.clone(),
+            permits: self.permits.clone(),
+            connections: self.connections.clone(),
+            shutdown: self.shutdown.clone(),
+        }
+    }
+}
 
-        match &self.socket_type {
-            SocketType::Ipc { path } => {
-                let display_path = if let Some(p) = path {
-                    p.to_string_lossy().to_string()
-                } else {
-                    std::env::temp_dir().join("tauri-mcp.sock").to_string_lossy().to_string()
-                };
-                info!(
-                    "[TAURI_MCP] Socket server started successfully at {}",
-                    display_path
-                );
+// The configured IPC path, or the app's default one
+fn socket_path(path: &Option<PathBuf>, application_name: &str) -> PathBuf {
+    path.clone().unwrap_or_else(|| discovery::default_socket_path(application_name))
+}
+
+/// Build the platform socket name for an IPC path
+pub(crate) fn socket_name(path: &Path) -> Result<Name<'static>, Error> {
+    let socket_path = path.to_string_lossy().to_string();
+
+    if cfg!(target_os = "windows") {
+        // Use named pipe on Windows
+        socket_path
+            .to_ns_name::<GenericNamespaced>()
+            .map_err(|e| Error::Io(format!("Failed to create pipe name: {}", e)))
+    } else {
+        // Use file-based socket on Unix platforms
+        socket_path
+            .to_fs_name::<GenericFilePath>()
+            .map_err(|e| Error::Io(format!("Failed to create file socket name: {}", e)))
+    }
+}
+
+/// Warn when a TCP listener is reachable from other machines without a token
+pub(crate) fn warn_if_exposed(listener: &StdTcpListener, auth: &TcpAuth) {
+    if let Ok(addr) = listener.local_addr()
+        && !addr.ip().is_loopback()
+        && !auth.token_required()
+    {
+        warn!(
+            "[TAURI_MCP] TCP socket on {} accepts unauthenticated connections from the network; configure an auth token",
+            addr
+        );
+    }
+}
+
+// Accept connections until shutdown is signalled, serving each on its own task. Returns once
+// the listener is closed and every connection has been drained and closed.
+async fn accept_loop<R: Runtime>(listener: UnifiedListener, server: Server<R>, socket_file: Option<PathBuf>) {
+    let mut shutdown = server.shutdown.clone();
+    let mut clients = JoinSet::new();
+    loop {
+        let accepted = tokio::select! {
+            _ = shutdown.changed() => break,
+            accepted = accept(&listener, &server.auth) => accepted,
+        };
+        // Forget clients that have already gone
+        while clients.try_join_next().is_some() {}
+
+        match accepted {
+            Ok(Some(connection)) => {
+                let server = server.clone();
+                clients.spawn(async move {
+                    let _counted = ConnectionCount::enter(&server.connections);
+                    let result = match connection {
+                        Connection::Ipc(stream) => handle_client(stream, &server, None).await,
+                        Connection::Tcp(stream, auth) => handle_client(stream, &server, Some(auth)).await,
+                    };
+                    match result {
+                        Ok(()) => info!("[TAURI_MCP] Client disconnected cleanly"),
+                        Err(e) if is_disconnect(&e) => info!("[TAURI_MCP] Client disconnected: {}", e),
+                        Err(e) => error!("[TAURI_MCP] Error handling client: {}", e),
+                    }
+                });
             }
-            SocketType::Tcp { host, port } => {
-                info!(
-                    "[TAURI_MCP] Socket server started successfully at {}:{}",
-                    host, port
-                );
+            // Rejected peer
+            Ok(None) => {}
+            Err(e) => {
+                error!("[TAURI_MCP] Error accepting connection: {}", e);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
             }
         }
-        Ok(())
     }
 
-    pub fn stop(&self) -> crate::Result<()> {
-        info!("[TAURI_MCP] Stopping socket server");
-        // Set running flag to false to stop the server thread
-        *self.running.lock().unwrap() = false;
+    // Release the socket before waiting on clients so it can be rebound right away
+    drop(listener);
+    if let Some(path) = socket_file
+        && path.exists()
+    {
+        let _ = std::fs::remove_file(&path);
+    }
+    info!("[TAURI_MCP] Accept loop ending, closing {} connection(s)", clients.len());
+    while clients.join_next().await.is_some() {}
+}
 
-        // The interprocess crate automatically cleans up the socket file on drop for Unix platforms
-        info!("[TAURI_MCP] Socket server stopped");
-        Ok(())
+// Counts a connection for `status` until dropped
+struct ConnectionCount(Arc<AtomicUsize>);
+
+impl ConnectionCount {
+    fn enter(count: &Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        ConnectionCount(count.clone())
     }
+}
 
-    #[cfg(desktop)]
-    fn get_socket_name(&self, path: &Option<std::path::PathBuf>) -> Result<Name, Error> {
-        let socket_path = if let Some(p) = path {
-            p.to_string_lossy().to_string()
-        } else {
-            let temp_dir = std::env::temp_dir();
-            temp_dir.join("tauri-mcp.sock").to_string_lossy().to_string()
-        };
+impl Drop for ConnectionCount {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+enum Connection {
+    Ipc(interprocess::local_socket::tokio::Stream),
+    Tcp(tokio::net::TcpStream, TcpAuth),
+}
 
-        if cfg!(target_os = "windows") {
-            // Use named pipe on Windows
-            socket_path
-                .to_ns_name::<GenericNamespaced>()
-                .map_err(|e| Error::Io(format!("Failed to create pipe name: {}", e)))
-        } else {
-            // Use file-based socket on Unix platforms
-            socket_path
-                .clone()
-                .to_fs_name::<GenericFilePath>()
-                .map_err(|e| Error::Io(format!("Failed to create file socket name: {}", e)))
+async fn accept(listener: &UnifiedListener, auth: &TcpAuth) -> std::io::Result<Option<Connection>> {
+    match listener {
+        UnifiedListener::Ipc(listener) => {
+            let stream = listener.accept().await?;
+            info!("[TAURI_MCP] Accepted new IPC connection");
+            Ok(Some(Connection::Ipc(stream)))
+        }
+        UnifiedListener::Tcp(listener) => {
+            let (stream, addr) = listener.accept().await?;
+            if !auth.peer_allowed(&addr) {
+                warn!("[TAURI_MCP] Rejected TCP connection from disallowed peer: {}", addr);
+                return Ok(None);
+            }
+            info!("[TAURI_MCP] Accepted new TCP connection from: {}", addr);
+            Ok(Some(Connection::Tcp(stream, auth.clone())))
         }
     }
 }
 
-fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate::Result<()> {
+async fn handle_client<R: Runtime, S>(stream: S, server: &Server<R>, auth: Option<TcpAuth>) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
     info!("[TAURI_MCP] Handling new client connection");
-    // Use tokio runtime to handle async functions
-    let rt = tokio::runtime::Runtime::new()
-        .map_err(|e| Error::Anyhow(format!("Failed to create runtime: {}", e)))?;
-
-    rt.block_on(async {
-        // Create a buffered reader and separate writer for the socket
-        let stream_clone = match stream.try_clone() {
-            Ok(clone) => clone,
-            Err(e) => {
-                // This might be a disconnection error on Windows
-                if e.to_string()
-                    .contains("No process is on the other end of the pipe")
-                {
-                    info!("[TAURI_MCP] Client already disconnected (pipe error)");
-                    return Ok(());
-                }
-                return Err(Error::Io(format!("Failed to clone stream: {}", e)));
+    let (reader, writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    // One task owns the write half; finished commands queue their responses to it
+    let (responses, queue) = mpsc::channel(RESPONSE_QUEUE);
+    let mut writer = tauri::async_runtime::spawn(write_responses(writer, queue));
+
+    // TCP clients must authenticate before any command is processed
+    let authenticated = match auth.filter(TcpAuth::token_required) {
+        Some(auth) => match read_handshake(&mut reader).await {
+            Some(line) => {
+                let (authenticated, response) = auth.check_handshake(&line);
+                let _ = responses.send(response).await;
+                authenticated
             }
-        };
+            None => false,
+        },
+        None => true,
+    };
+
+    let mut requests = JoinSet::new();
+    let result = if authenticated {
+        serve_requests(&mut reader, server, &responses, &mut requests).await
+    } else {
+        info!("[TAURI_MCP] Closing unauthenticated connection");
+        Ok(())
+    };
+
+    // Let running commands finish and the writer drain what's queued, then close. On shutdown
+    // that gets a deadline; commands still running after it are cancelled.
+    drop(responses);
+    let drained = async {
+        while requests.join_next().await.is_some() {}
+        (&mut writer).await
+    };
+    let written = if *server.shutdown.borrow() {
+        match tokio::time::timeout(DRAIN_TIMEOUT, drained).await {
+            Ok(written) => written,
+            Err(_) => {
+                warn!("[TAURI_MCP] Cancelling {} command(s) still running at shutdown", requests.len());
+                requests.abort_all();
+                writer.abort();
+                return result;
+            }
+        }
+    } else {
+        drained.await
+    };
+    let written = written.unwrap_or_else(|e| Err(std::io::Error::other(format!("Response writer failed: {}", e))));
+    result.and(written)
+}
 
-        // Wrap the streams with our logging wrapper
-        let logging_reader = LoggingStream::new(stream_clone);
-        let mut reader = BufReader::new(logging_reader);
-        let mut writer = LoggingStream::new(stream);
-
-        // Keep handling requests until the client disconnects
-        loop {
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // End of stream, client disconnected
-                    info!("[TAURI_MCP] Client disconnected cleanly");
-                    return Ok(());
-                }
-                Ok(_) => {
-                    info!("[TAURI_MCP] Received command: {}", line.trim());
-                }
-                Err(e) => {
-                    // Check if this is a pipe disconnection error
-                    if e.to_string()
-                        .contains("No process is on the other end of the pipe")
-                        || e.kind() == std::io::ErrorKind::BrokenPipe
-                    {
-                        info!("[TAURI_MCP] Client disconnected during read (pipe error)");
-                        return Ok(());
-                    }
-                    return Err(Error::Io(format!("Error reading from socket: {}", e)));
-                }
-            };
+// Read requests until the client disconnects or the server shuts down. Requests with an id run
+// as their own tasks; the next line is only read once a command slot is free, so a busy server
+// pushes back.
+async fn serve_requests<R: Runtime, B: AsyncBufRead + Unpin>(
+    reader: &mut B,
+    server: &Server<R>,
+    responses: &mpsc::Sender<SocketResponse>,
+    requests: &mut JoinSet<()>,
+) -> std::io::Result<()> {
+    let mut shutdown = server.shutdown.clone();
+    loop {
+        // Forget commands that have already answered
+        while requests.try_join_next().is_some() {}
+
+        let read = tokio::select! {
+            _ = shutdown.changed() => return Ok(()),
+            read = read_line(reader, MAX_REQUEST_BYTES) => read,
+        };
+        let line = match read {
+            Ok(Some(line)) => line,
+            Ok(None) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                let _ = responses.send(invalid_request(e.to_string(), None)).await;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        info!("[TAURI_MCP] Received command: {}", line.trim());
 
         // Parse and process the request
         let request: SocketRequest = match serde_json::from_str(&line) {
-            Ok(req) => req,
+            Ok(request) => request,
             Err(e) => {
-                let error_msg = format!("Invalid request format: {}", e);
-                info!("[TAURI_MCP] {}", error_msg);
-
-                // Create and send an error response
-                let error_response = SocketResponse {
-                    success: false,
-                    data: None,
-                    error: Some(error_msg),
-                };
-
-                let error_json = match serde_json::to_string(&error_response) {
-                    Ok(json) => json + "\n",
-                    Err(_) => {
-                        return Err(Error::Anyhow(
-                            "Failed to serialize error response".to_string(),
-                        ));
-                    }
-                };
-
-                match writer.write_all(error_json.as_bytes()) {
-                    Ok(_) => {
-                        if let Err(e) = writer.flush() {
-                            return Err(Error::Io(format!("Error flushing error response: {}", e)));
-                        }
-                    }
-                    Err(e) => {
-                        return Err(Error::Io(format!("Error writing error response: {}", e)));
-                    }
-                }
-
-                // Clear the line and continue to the next iteration
-                line.clear();
+                // Keep the id if the line had one
+                let id = serde_json::from_str::<Value>(&line)
+                    .ok()
+                    .and_then(|v| v.get("id").cloned());
+                let _ = responses.send(invalid_request(format!("Invalid request format: {}", e), id)).await;
                 continue;
             }
         };
 
-        info!("[TAURI_MCP] Processing command: {}", request.command);
+        let permit = tokio::select! {
+            _ = shutdown.changed() => return Ok(()),
+            permit = server.permits.clone().acquire_owned() => permit.map_err(std::io::Error::other)?,
+        };
+        let has_id = request.id.is_some();
+        let task = run_request(server.app.clone(), request.command, request.payload, request.id, responses.clone(), permit);
+        if has_id {
+            requests.spawn(task);
+            continue;
+        }
 
-        // Use the centralized command handler from tools module
-        let response = match tools::handle_command(&app, &request.command, request.payload).await {
-            Ok(resp) => resp,
-            Err(e) => {
-                // Convert the error into a response structure
-                info!("[TAURI_MCP] Command error: {}", e);
-                SocketResponse {
-                    success: false,
-                    data: None,
-                    error: Some(e.to_string()),
+        // Answer in order: finish this command before reading the next request
+        let mut task = std::pin::pin!(task);
+        tokio::select! {
+            _ = &mut task => {}
+            _ = shutdown.changed() => {
+                if tokio::time::timeout(DRAIN_TIMEOUT, task).await.is_err() {
+                    warn!("[TAURI_MCP] Cancelling a command still running at shutdown");
                 }
+                return Ok(());
             }
-        };
+        }
+    }
+}
+
+async fn run_request<R: Runtime>(
+    app: AppHandle<R>,
+    command: String,
+    payload: Value,
+    id: Option<Value>,
+    responses: mpsc::Sender<SocketResponse>,
+    _permit: OwnedSemaphorePermit,
+) {
+    info!("[TAURI_MCP] Processing command: {}", command);
+
+    // Use the centralized command handler from tools module; a panicking handler fails only its request
+    let result = AssertUnwindSafe(tools::handle_command(&app, &command, payload))
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|_| Err(Error::new(ErrorCode::Internal, format!("Command {} panicked", command))));
+    let mut response = match result {
+        Ok(resp) => resp,
+        Err(e) => {
+            // Convert the error into a response structure
+            info!("[TAURI_MCP] Command error: {}", e);
+            SocketResponse {
+                success: false,
+                data: None,
+                error: Some(e.into_info()),
+                id: None,
+            }
+        }
+    };
+
+    // Echo the client's request id so it can match responses to requests
+    response.id = id;
+
+    // The writer is gone only when the client disconnected
+    let _ = responses.send(response).await;
+}
 
-        // When writing the response, handle pipe errors gracefully
-        let response_json = serde_json::to_string(&response)
-            .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?
-            + "\n";
+async fn write_responses<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    mut queue: mpsc::Receiver<SocketResponse>,
+) -> std::io::Result<()> {
+    while let Some(response) = queue.recv().await {
+        let response_json = serde_json::to_string(&response).map_err(std::io::Error::other)? + "\n";
         info!(
             "[TAURI_MCP] Sending response: length = {} bytes",
             response_json.len()
         );
+        writer.write_all(response_json.as_bytes()).await?;
+        writer.flush().await?;
+    }
+    writer.shutdown().await
+}
 
-        // Write the response directly without chunking
-        match writer.write_all(response_json.as_bytes()) {
-            Ok(_) => {
-                match writer.flush() {
-                    Ok(_) => {
-                        info!("[TAURI_MCP] Response sent successfully");
-                        // Continue to the next iteration of the loop
-                    }
-                    Err(e) => {
-                        if e.to_string()
-                            .contains("No process is on the other end of the pipe")
-                            || e.kind() == std::io::ErrorKind::BrokenPipe
-                        {
-                            info!(
-                                "[TAURI_MCP] Client disconnected during flush (pipe error)"
-                            );
-                            return Ok(()); // Return success for expected client disconnect
-                        } else {
-                            return Err(Error::Io(format!("Error flushing response: {}", e)));
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                if e.to_string()
-                    .contains("No process is on the other end of the pipe")
-                    || e.kind() == std::io::ErrorKind::BrokenPipe
-                {
-                    info!("[TAURI_MCP] Client disconnected during write (pipe error)");
-                    return Ok(()); // Return success for expected client disconnect
-                } else {
-                    return Err(Error::Io(format!("Error writing response: {}", e)));
-                }
-            }
-        }
-        
-        // Clear the line for the next command
-        line.clear();
-        } // End of loop
-    })
+fn invalid_request(error: String, id: Option<Value>) -> SocketResponse {
+    info!("[TAURI_MCP] {}", error);
+    SocketResponse {
+        success: false,
+        data: None,
+        error: Some(ErrorInfo::new(ErrorCode::InvalidRequest, error)),
+        id,
+    }
+}
+
+// Read one newline-terminated line of at most `limit` bytes; `None` at end of stream
+pub(crate) async fn read_line<B: AsyncBufRead + Unpin>(reader: &mut B, limit: usize) -> std::io::Result<Option<String>> {
+    let mut line = Vec::new();
+    if reader.take(limit as u64 + 1).read_until(b'\n', &mut line).await? == 0 {
+        return Ok(None);
+    }
+    if line.len() > limit && line.last() != Some(&b'\n') {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Request line exceeds {} bytes", limit),
+        ));
+    }
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+// Errors that just mean the client went away
+fn is_disconnect(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::UnexpectedEof
+    ) || e.to_string().contains("No process is on the other end of the pipe")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_line_frames_and_limits() {
+        let mut input: &[u8] = b"{\"command\":\"ping\"}\n\nlast";
+        assert_eq!(read_line(&mut input, 64).await.unwrap().as_deref(), Some("{\"command\":\"ping\"}\n"));
+        assert_eq!(read_line(&mut input, 64).await.unwrap().as_deref(), Some("\n"));
+        assert_eq!(read_line(&mut input, 64).await.unwrap().as_deref(), Some("last"));
+        assert_eq!(read_line(&mut input, 64).await.unwrap(), None);
+
+        let mut input: &[u8] = b"0123456789\n";
+        assert_eq!(read_line(&mut input, 11).await.unwrap().as_deref(), Some("0123456789\n"));
+        let mut input: &[u8] = b"0123456789\n";
+        let error = read_line(&mut input, 5).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
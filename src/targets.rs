@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+/// How the labels clients send map to the app's real window and webview labels.
+///
+/// Every tool resolves its `window_label` through this map: the label itself is tried
+/// first, then its aliases in order. Requests without a label use the default target.
+#[derive(Clone, Debug)]
+pub struct TargetMap {
+    default_target: String,
+    aliases: HashMap<String, Vec<String>>,
+}
+
+impl Default for TargetMap {
+    fn default() -> Self {
+        Self {
+            default_target: "main".to_string(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl TargetMap {
+    /// Label used when a request doesn't name a window (default `"main"`)
+    pub fn default_target(mut self, label: impl Into<String>) -> Self {
+        self.default_target = label.into();
+        self
+    }
+
+    /// Try `targets`, in order, when no window or webview is labelled `label`
+    pub fn alias<I, S>(mut self, label: impl Into<String>, targets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.aliases
+            .entry(label.into())
+            .or_default()
+            .extend(targets.into_iter().map(Into::into));
+        self
+    }
+
+    /// The requested label, or the default target when none was given
+    pub fn label_or_default(&self, label: Option<&str>) -> String {
+        label.unwrap_or(&self.default_target).to_string()
+    }
+
+    /// Labels to look up for `label`, in the order they are tried
+    pub fn candidates<'a>(&'a self, label: &'a str) -> Vec<&'a str> {
+        let mut candidates = vec![label];
+        if let Some(aliases) = self.aliases.get(label) {
+            candidates.extend(aliases.iter().map(String::as_str));
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_try_label_then_aliases() {
+        let targets = TargetMap::default()
+            .alias("main", ["preview"])
+            .alias("main", ["editor"]);
+
+        assert_eq!(targets.candidates("main"), vec!["main", "preview", "editor"]);
+        assert_eq!(targets.candidates("settings"), vec!["settings"]);
+    }
+
+    #[test]
+    fn test_default_target() {
+        assert_eq!(TargetMap::default().label_or_default(None), "main");
+
+        let targets = TargetMap::default().default_target("editor");
+        assert_eq!(targets.label_or_default(None), "editor");
+        assert_eq!(targets.label_or_default(Some("settings")), "settings");
+    }
+}
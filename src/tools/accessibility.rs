@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value, json};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::webview_request::PendingResponse;
+
+// Large documents take a while to walk
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct GetAccessibilitySnapshotRequest {
+    /// Label of the webview to snapshot (defaults to the configured default target)
+    window_label: Option<String>,
+    /// CSS selector of the subtree to snapshot (the whole body when omitted)
+    root: Option<String>,
+    /// Only keep interactive elements (buttons, links, inputs, ...), dropping text and structure
+    #[serde(default)]
+    interactive_only: bool,
+    /// Maximum nesting depth of the returned tree
+    max_depth: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilitySnapshot {
+    /// URL of the page the snapshot was taken of
+    url: String,
+    tree: SnapshotNode,
+    /// Nodes in the tree
+    node_count: usize,
+}
+
+/// One node of the accessibility tree; states are only present when they apply
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SnapshotNode {
+    /// Element reference usable as a `ref` selector, on interactive elements
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    element_ref: Option<String>,
+    /// ARIA role, explicit or implied by the element
+    role: String,
+    /// Accessible name, or the text of a text node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    /// Heading level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checked: Option<CheckedState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expanded: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pressed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    focused: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    readonly: Option<bool>,
+    /// Position and size in CSS pixels of the top-level viewport
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bounds: Option<NodeBounds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<SnapshotNode>>,
+}
+
+/// A checkbox's state: checked, unchecked or "mixed"
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum CheckedState {
+    Checked(bool),
+    Mixed(Mixed),
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Mixed {
+    Mixed,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NodeBounds {
+    x: Number,
+    y: Number,
+    width: Number,
+    height: Number,
+}
+
+impl GetAccessibilitySnapshotRequest {
+    // The options as the guest script reads them
+    fn js_payload(&self, request_id: &str) -> Value {
+        json!({
+            "requestId": request_id,
+            "root": self.root,
+            "interactiveOnly": self.interactive_only,
+            "maxDepth": self.max_depth
+        })
+    }
+}
+
+pub async fn handle_get_accessibility_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: GetAccessibilitySnapshotRequest = if payload.is_null() {
+        GetAccessibilitySnapshotRequest::default()
+    } else {
+        parse_payload(commands::GET_ACCESSIBILITY_SNAPSHOT, payload)?
+    };
+
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "get-accessibility-snapshot-response");
+
+    app.emit_to(
+        &resolved_label,
+        "get-accessibility-snapshot",
+        request.js_payload(pending.request_id()),
+    )
+    .map_err(|e| Error::new(ErrorCode::WebviewError, format!("Failed to emit get-accessibility-snapshot event: {}", e)))?;
+
+    match pending.wait(RESPONSE_TIMEOUT).await {
+        Ok(mut result) if result["success"].as_bool() == Some(true) => {
+            let snapshot: AccessibilitySnapshot =
+                parse_guest_data(commands::GET_ACCESSIBILITY_SNAPSHOT, result["data"].take())?;
+            let data = serde_json::to_value(snapshot)
+                .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+            Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+                id: None,
+            })
+        }
+        Ok(result) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(ErrorInfo::from_guest(
+                &result,
+                ErrorCode::WebviewError,
+                "Unknown error building accessibility snapshot",
+            )),
+            id: None,
+        }),
+        Err(e) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.into_info("Timeout waiting for accessibility snapshot")),
+            id: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_js_payload_uses_guest_option_names() {
+        let request: GetAccessibilitySnapshotRequest =
+            serde_json::from_value(json!({ "root": "#sidebar", "interactive_only": true })).unwrap();
+
+        assert_eq!(
+            request.js_payload("req-1"),
+            json!({ "requestId": "req-1", "root": "#sidebar", "interactiveOnly": true, "maxDepth": null })
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_guest_tree() {
+        let data = json!({
+            "url": "tauri://localhost/",
+            "tree": {
+                "role": "main",
+                "children": [
+                    { "ref": "e1", "role": "checkbox", "name": "All", "checked": "mixed" },
+                    { "ref": "e2", "role": "button", "name": "Save", "disabled": true,
+                      "bounds": { "x": 10.5, "y": 20, "width": 80.25, "height": 24 } }
+                ]
+            },
+            "nodeCount": 3
+        });
+
+        let snapshot: AccessibilitySnapshot = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(serde_json::to_value(snapshot).unwrap(), data);
+    }
+}
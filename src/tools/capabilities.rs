@@ -0,0 +1,24 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::Error;
+use crate::policy::CommandPolicy;
+use crate::socket_server::SocketResponse;
+
+pub fn handle_get_capabilities<R: Runtime>(
+    app: &AppHandle<R>,
+    _payload: Value,
+) -> Result<SocketResponse, Error> {
+    // Without managed state nothing is restricted
+    let data = match app.try_state::<CommandPolicy>() {
+        Some(policy) => policy.describe(),
+        None => CommandPolicy::default().describe(),
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
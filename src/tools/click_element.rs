@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, resolve_window, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::models::CoordinateUnit;
+use crate::selector::{ElementRef, ElementSelector};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::mouse_movement::ScreenOrigin;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::pointer_actions::{PointerButton, os_click};
+use crate::tools::run_blocking;
+use crate::tools::wait_for::check_wait_ms;
+use crate::tools::webview_request::PendingResponse;
+
+const MAX_CLICK_COUNT: u32 = 3;
+// The guest script waits up to a second for an OS click to arrive before answering
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickMode {
+    /// Dispatch pointer and mouse events in the page; needs no focus and leaves the OS pointer alone
+    #[default]
+    Dom,
+    /// Move the real OS pointer to the element and click with enigo
+    Os,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ClickElementRequest {
+    /// Label of the webview containing the element (defaults to the configured default target)
+    window_label: Option<String>,
+    #[serde(flatten)]
+    selector: ElementSelector,
+    /// How to click: "dom" events (default) or the "os" pointer
+    #[serde(default)]
+    mode: ClickMode,
+    /// Mouse button to use (default "left")
+    #[serde(default)]
+    button: PointerButton,
+    /// Number of clicks, 2 for a double click (default 1, at most 3)
+    click_count: Option<u32>,
+    /// Click even when another element covers the element's center; "dom" clicks then go to the element itself
+    #[serde(default)]
+    force: bool,
+    /// Wait up to this many milliseconds for the element to be visible, stable, enabled and uncovered
+    auto_wait_ms: Option<u64>,
+}
+
+/// The guest script reports the click's target and point; the plugin adds the mode, the OS
+/// pointer position and the duration
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickElementResponse {
+    #[serde(default)]
+    mode: ClickMode,
+    /// The element the selector found
+    target: ElementRef,
+    /// Topmost element at the click point, null when there is none
+    hit: Option<ElementRef>,
+    /// Whether another element covers the target's center; only possible with `force`
+    covered: bool,
+    /// Click point in logical pixels from the webview's top-left corner
+    x: f64,
+    y: f64,
+    /// Whether a listener cancelled the click; "dom" mode only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cancelled: Option<bool>,
+    /// Where the OS pointer clicked, in physical screen pixels; "os" mode only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<(i32, i32)>,
+    /// Element that received the click, null when an "os" click was not seen by the page
+    #[serde(default)]
+    received: Option<ElementRef>,
+    #[serde(default)]
+    duration_ms: u64,
+}
+
+impl ClickElementRequest {
+    fn click_count(&self) -> u32 {
+        self.click_count.unwrap_or(1)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CLICK_COUNT).contains(&self.click_count()) {
+            return Err(format!("click_count must be between 1 and {}", MAX_CLICK_COUNT));
+        }
+        check_wait_ms("auto_wait_ms", self.auto_wait_ms.unwrap_or(0))
+    }
+
+    // The request as the guest script reads it
+    fn js_payload(&self, request_id: &str) -> Value {
+        json!({
+            "requestId": request_id,
+            "selector": self.selector.to_js(),
+            "mode": self.mode,
+            "button": self.button,
+            "clickCount": self.click_count(),
+            "force": self.force,
+            "autoWaitMs": self.auto_wait_ms.unwrap_or(0)
+        })
+    }
+}
+
+pub async fn handle_click_element<R: Runtime>(app: &AppHandle<R>, payload: Value) -> Result<SocketResponse, Error> {
+    let request: ClickElementRequest = parse_payload(commands::CLICK_ELEMENT, payload)?;
+    request.validate().map_err(Error::invalid_payload)?;
+
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
+    let start_time = Instant::now();
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "click-element-response");
+    let click_id = pending.request_id().to_string();
+
+    app.emit_to(&resolved_label, "click-element", request.js_payload(&click_id))
+        .map_err(|e| Error::new(ErrorCode::WebviewError, format!("Failed to emit click-element event: {}", e)))?;
+
+    // The guest scrolls, hit-tests and, for "dom", clicks; "os" clicks happen below
+    let response_timeout = Duration::from_secs(5) + Duration::from_millis(request.auto_wait_ms.unwrap_or(0));
+    let result = match pending.wait(response_timeout).await {
+        Ok(result) => result,
+        Err(e) => return Ok(failure(e.into_info("Timeout waiting for element to click"))),
+    };
+    if result["success"].as_bool() != Some(true) {
+        return Ok(failure(ErrorInfo::from_guest(
+            &result,
+            ErrorCode::WebviewError,
+            "Unknown error clicking element",
+        )));
+    }
+
+    let mut response: ClickElementResponse = match parse_guest_data(commands::CLICK_ELEMENT, result["data"].clone()) {
+        Ok(response) => response,
+        Err(error) => return Ok(failure(error)),
+    };
+    if request.mode == ClickMode::Os {
+        let point = ScreenOrigin::resolve(app, &resolved_label)?.screen_point(
+            response.x,
+            response.y,
+            CoordinateUnit::Logical,
+        );
+
+        // OS pointer events go to the window under the cursor, which should be ours
+        resolve_window(app, &resolved_label)?.set_focus()?;
+        let (button, clicks) = (request.button, request.click_count());
+        match run_blocking(move || os_click(point, button, clicks)).await {
+            Ok(position) => response.position = Some(position),
+            Err(error) => return Ok(failure(error)),
+        }
+        response.received = confirm_click(app, &resolved_label, &click_id).await;
+    }
+
+    response.mode = request.mode;
+    response.duration_ms = start_time.elapsed().as_millis() as u64;
+    let data = serde_json::to_value(response)
+        .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+// Ask the guest script which element the OS click landed on; None when none was seen
+async fn confirm_click<R: Runtime>(app: &AppHandle<R>, label: &str, click_id: &str) -> Option<ElementRef> {
+    let pending = PendingResponse::listen(app, "confirm-click-response");
+    let js_payload = json!({ "requestId": pending.request_id(), "clickId": click_id });
+    app.emit_to(label, "confirm-click", js_payload).ok()?;
+    let result = pending.wait(CONFIRM_TIMEOUT).await.ok()?;
+    serde_json::from_value(result["data"]["received"].clone()).ok()
+}
+
+fn failure(error: ErrorInfo) -> SocketResponse {
+    SocketResponse {
+        success: false,
+        data: None,
+        error: Some(error),
+        id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_js_payload_and_defaults() {
+        let request: ClickElementRequest = serde_json::from_value(json!({
+            "selector_type": "role",
+            "selector_value": "button",
+            "name": "Save",
+            "mode": "os"
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+
+        let payload = request.js_payload("req-1");
+        assert_eq!(payload["mode"], "os");
+        assert_eq!(payload["button"], "left");
+        assert_eq!(payload["clickCount"], 1);
+        assert_eq!(payload["force"], false);
+        assert_eq!(payload["selector"]["name"], "Save");
+
+        let request: ClickElementRequest = serde_json::from_value(json!({
+            "selector_type": "css",
+            "selector_value": "li",
+            "click_count": 4
+        }))
+        .unwrap();
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+use crate::tools::webview_request::PendingResponse;
+
+// How long to wait for the guest script to hand over its buffer
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct GetConsoleLogsRequest {
+    /// Label of the webview whose console is read (defaults to the configured default target)
+    window_label: Option<String>,
+    /// Only return entries with one of these levels (all levels when omitted).
+    /// Uncaught errors and unhandled rejections are recorded as "error".
+    #[serde(default)]
+    levels: Vec<ConsoleLevel>,
+    /// Only return entries logged at or after this time, in milliseconds since the Unix epoch
+    since: Option<u64>,
+    /// Only return entries whose message contains this text (case-insensitive)
+    pattern: Option<String>,
+    /// Return at most this many entries, keeping the most recent
+    limit: Option<usize>,
+    /// Empty the webview's buffer after reading it (all entries, not just the returned ones)
+    #[serde(default)]
+    clear: bool,
+}
+
+/// One captured console call, uncaught error or unhandled rejection
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ConsoleEntry {
+    level: ConsoleLevel,
+    /// Milliseconds since the Unix epoch
+    timestamp: u64,
+    /// "console", "uncaught" or "unhandledrejection"
+    source: String,
+    /// The arguments joined as text, the way devtools prints them
+    message: String,
+    /// The arguments as JSON (see `execute_js` for how non-JSON values are tagged)
+    #[serde(default)]
+    args: Vec<Value>,
+    /// Stack of the call site for warnings and errors, or of the thrown error
+    stack: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetConsoleLogsResponse {
+    entries: Vec<ConsoleEntry>,
+    /// Entries in the buffer before filtering
+    buffered: usize,
+    /// Entries evicted because the buffer was full since it was last cleared
+    dropped: u64,
+}
+
+pub async fn handle_get_console_logs<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: GetConsoleLogsRequest = if payload.is_null() {
+        GetConsoleLogsRequest::default()
+    } else {
+        parse_payload(commands::GET_CONSOLE_LOGS, payload)?
+    };
+
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "get-console-logs-response");
+
+    let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
+        "clear": request.clear
+    });
+
+    if let Err(e) = app.emit_to(&resolved_label, "get-console-logs", &js_payload) {
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(ErrorInfo::new(
+                ErrorCode::WebviewError,
+                format!("Failed to emit get-console-logs event: {}", e),
+            )),
+            id: None,
+        });
+    }
+
+    let response = match pending.wait(RESPONSE_TIMEOUT).await {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(e.into_info(&format!("Timeout waiting for console logs from '{}'", resolved_label))),
+                id: None,
+            });
+        }
+    };
+
+    let entries: Vec<ConsoleEntry> = serde_json::from_value(response["entries"].clone())
+        .map_err(|e| ErrorInfo::new(ErrorCode::WebviewError, format!("Invalid console entries from guest script: {}", e)))?;
+    let buffered = entries.len();
+
+    let data = serde_json::to_value(GetConsoleLogsResponse {
+        entries: filter_entries(entries, &request),
+        buffered,
+        dropped: response["dropped"].as_u64().unwrap_or(0),
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+// Apply the request's level, time and text filters, then the limit
+fn filter_entries(entries: Vec<ConsoleEntry>, request: &GetConsoleLogsRequest) -> Vec<ConsoleEntry> {
+    let pattern = request.pattern.as_deref().map(str::to_lowercase);
+
+    let mut entries: Vec<ConsoleEntry> = entries
+        .into_iter()
+        .filter(|entry| request.levels.is_empty() || request.levels.contains(&entry.level))
+        .filter(|entry| request.since.is_none_or(|since| entry.timestamp >= since))
+        .filter(|entry| {
+            pattern
+                .as_deref()
+                .is_none_or(|pattern| entry.message.to_lowercase().contains(pattern))
+        })
+        .collect();
+
+    if let Some(limit) = request.limit
+        && entries.len() > limit
+    {
+        entries.drain(..entries.len() - limit);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entries() -> Vec<ConsoleEntry> {
+        serde_json::from_value(json!([
+            { "level": "log", "timestamp": 1000, "source": "console", "message": "App started", "args": ["App started"], "stack": null },
+            { "level": "warn", "timestamp": 2000, "source": "console", "message": "Slow render", "args": [], "stack": "at render" },
+            { "level": "error", "timestamp": 3000, "source": "uncaught", "message": "TypeError: x is undefined", "stack": "at load" },
+            { "level": "error", "timestamp": 4000, "source": "unhandledrejection", "message": "Fetch failed", "stack": null }
+        ]))
+        .unwrap()
+    }
+
+    fn messages(entries: &[ConsoleEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter_by_level_since_and_pattern() {
+        let request: GetConsoleLogsRequest = serde_json::from_value(json!({ "levels": ["warn", "error"] })).unwrap();
+        assert_eq!(
+            messages(&filter_entries(entries(), &request)),
+            vec!["Slow render", "TypeError: x is undefined", "Fetch failed"]
+        );
+
+        let request: GetConsoleLogsRequest =
+            serde_json::from_value(json!({ "since": 2000, "pattern": "UNDEFINED" })).unwrap();
+        assert_eq!(messages(&filter_entries(entries(), &request)), vec!["TypeError: x is undefined"]);
+    }
+
+    #[test]
+    fn test_limit_keeps_most_recent() {
+        let request: GetConsoleLogsRequest = serde_json::from_value(json!({ "limit": 2 })).unwrap();
+        assert_eq!(
+            messages(&filter_entries(entries(), &request)),
+            vec!["TypeError: x is undefined", "Fetch failed"]
+        );
+
+        assert_eq!(filter_entries(entries(), &GetConsoleLogsRequest::default()).len(), 4);
+    }
+}
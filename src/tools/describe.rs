@@ -0,0 +1,288 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::TauriMcpExt;
+use crate::discovery::PROTOCOL_VERSION;
+use crate::error::Error;
+use crate::mcp::tools::{find_tool, schema};
+use crate::models::{PingRequest, PingResponse, SocketServerStatus};
+use crate::policy::{CommandPolicy, PolicyDescription};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DescribeRequest {
+    /// Protocol version the client speaks; the response says whether it matches
+    protocol_version: Option<u32>,
+    /// Include payload and result JSON Schemas for every command (default true)
+    include_schemas: Option<bool>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DescribeResponse {
+    /// Version of tauri-plugin-mcp
+    plugin_version: String,
+    protocol_version: u32,
+    /// False when the client sent a protocol version other than this one
+    compatible: bool,
+    app: AppInfo,
+    /// Where the socket server listens, if it is available
+    transport: Option<SocketServerStatus>,
+    /// Commands the command policy allows
+    commands: Vec<CommandDescription>,
+    /// Commands the command policy blocks
+    disabled_commands: Vec<String>,
+    platform: PlatformCapabilities,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    name: String,
+    version: String,
+    pid: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandDescription {
+    name: String,
+    /// Title and description of the matching MCP tool, if there is one
+    title: Option<String>,
+    description: Option<String>,
+    /// Whether the command only reads state; unknown for commands without an MCP tool
+    read_only: Option<bool>,
+    /// Permitted values per payload field under the command policy
+    argument_restrictions: BTreeMap<String, Vec<Value>>,
+    /// JSON Schema of the payload
+    payload_schema: Option<Value>,
+    /// JSON Schema of the response `data`; null when the result has no fixed shape (manage_storage)
+    result_schema: Option<Value>,
+}
+
+/// What this OS and session support, so clients can avoid commands that would fail
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformCapabilities {
+    /// As in Rust's `std::env::consts::OS`, e.g. "macos", "windows" or "linux"
+    os: String,
+    arch: String,
+    /// OS-level window capture (`take_screenshot`)
+    native_screenshot: Capability,
+    /// Webview-rendered capture (`capture_screenshot`)
+    webview_screenshot: Capability,
+    /// Real keyboard and pointer input: `simulate_text_input`, `simulate_mouse_movement`,
+    /// `pointer_action` and the "os" modes of `press_keys` and `click_element`
+    os_input: Capability,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Capability {
+    available: bool,
+    /// Why it is unavailable, or what it needs to work
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+impl Capability {
+    fn available(note: Option<&str>) -> Self {
+        Capability {
+            available: true,
+            note: note.map(str::to_string),
+        }
+    }
+
+    fn unavailable(reason: &str) -> Self {
+        Capability {
+            available: false,
+            note: Some(reason.to_string()),
+        }
+    }
+}
+
+pub fn handle_describe<R: Runtime>(app: &AppHandle<R>, payload: Value) -> Result<SocketResponse, Error> {
+    let request: DescribeRequest = if payload.is_null() {
+        DescribeRequest::default()
+    } else {
+        parse_payload(commands::DESCRIBE, payload)?
+    };
+
+    let package = app.package_info();
+    let app_info = AppInfo {
+        name: package.name.clone(),
+        version: package.version.to_string(),
+        pid: std::process::id(),
+    };
+    let transport = app.tauri_mcp().server_status().ok();
+
+    // Without managed state nothing is restricted
+    let response = match app.try_state::<CommandPolicy>() {
+        Some(policy) => describe(&request, &policy, app_info, transport),
+        None => describe(&request, &CommandPolicy::default(), app_info, transport),
+    };
+    let data = serde_json::to_value(response)
+        .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+fn describe(
+    request: &DescribeRequest,
+    policy: &CommandPolicy,
+    app: AppInfo,
+    transport: Option<SocketServerStatus>,
+) -> DescribeResponse {
+    let include_schemas = request.include_schemas.unwrap_or(true);
+    let (allowed, disabled): (Vec<&str>, Vec<&str>) =
+        commands::ALL.iter().partition(|command| policy.is_command_allowed(command));
+
+    DescribeResponse {
+        plugin_version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: PROTOCOL_VERSION,
+        compatible: request.protocol_version.is_none_or(|version| version == PROTOCOL_VERSION),
+        app,
+        transport,
+        commands: allowed
+            .into_iter()
+            .map(|command| describe_command(command, policy, include_schemas))
+            .collect(),
+        disabled_commands: disabled.into_iter().map(str::to_string).collect(),
+        platform: platform_capabilities(),
+    }
+}
+
+fn describe_command(command: &str, policy: &CommandPolicy, include_schemas: bool) -> CommandDescription {
+    let tool = find_tool(command);
+    let (payload_schema, result_schema) = match (include_schemas, tool) {
+        (false, _) => (None, None),
+        (true, Some(tool)) => (Some(tool.input_schema()), tool.result_schema()),
+        (true, None) => socket_only_schemas(command),
+    };
+
+    CommandDescription {
+        name: command.to_string(),
+        title: tool.map(|tool| tool.title.to_string()),
+        description: tool.map(|tool| tool.description.to_string()),
+        read_only: tool.map(|tool| tool.read_only()),
+        argument_restrictions: policy.argument_restrictions(command),
+        payload_schema,
+        result_schema,
+    }
+}
+
+// Schemas for the commands that have no MCP tool
+fn socket_only_schemas(command: &str) -> (Option<Value>, Option<Value>) {
+    match command {
+        commands::PING => (Some(schema::<PingRequest>()), Some(schema::<PingResponse>())),
+        commands::DESCRIBE => (Some(schema::<DescribeRequest>()), Some(schema::<DescribeResponse>())),
+        commands::GET_CAPABILITIES => (Some(json!({ "type": "object" })), Some(schema::<PolicyDescription>())),
+        _ => (None, None),
+    }
+}
+
+fn platform_capabilities() -> PlatformCapabilities {
+    // On Linux, window capture and enigo both talk to an X11 server (XWayland included)
+    let has_display = !cfg!(target_os = "linux") || std::env::var_os("DISPLAY").is_some();
+    let no_display = "no X11 display; set DISPLAY or run under X11 or XWayland";
+
+    let native_screenshot = if cfg!(target_os = "macos") {
+        Capability::available(Some("needs the Screen Recording permission"))
+    } else if has_display {
+        Capability::available(None)
+    } else {
+        Capability::unavailable(no_display)
+    };
+    let os_input = if cfg!(target_os = "macos") {
+        Capability::available(Some("needs the Accessibility permission"))
+    } else if has_display {
+        Capability::available(None)
+    } else {
+        Capability::unavailable(no_display)
+    };
+
+    PlatformCapabilities {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        native_screenshot,
+        webview_screenshot: Capability::available(None),
+        os_input,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_info() -> AppInfo {
+        AppInfo {
+            name: "test".to_string(),
+            version: "0.1.0".to_string(),
+            pid: 1,
+        }
+    }
+
+    #[test]
+    fn test_describe_lists_allowed_commands_with_schemas() {
+        let request = DescribeRequest::default();
+        let response = describe(&request, &CommandPolicy::read_only(), app_info(), None);
+
+        assert_eq!(response.protocol_version, PROTOCOL_VERSION);
+        assert!(response.compatible);
+        assert_eq!(response.commands.len() + response.disabled_commands.len(), commands::ALL.len());
+        assert!(response.disabled_commands.contains(&commands::EXECUTE_JS.to_string()));
+
+        // Every allowed command says what it takes
+        for command in &response.commands {
+            assert!(command.payload_schema.is_some(), "payload schema for {}", command.name);
+        }
+
+        let value = serde_json::to_value(&response).unwrap();
+        let commands = value["commands"].as_array().unwrap();
+        let list_windows = commands.iter().find(|c| c["name"] == commands::LIST_WINDOWS).unwrap();
+        assert_eq!(list_windows["readOnly"], true);
+        assert_eq!(list_windows["resultSchema"]["properties"]["windows"]["type"], "array");
+        let storage = commands.iter().find(|c| c["name"] == commands::MANAGE_STORAGE).unwrap();
+        assert_eq!(
+            storage["argumentRestrictions"]["action"],
+            json!(["get", "keys", "databases", "object_stores"])
+        );
+        assert!(commands.iter().any(|c| c["name"] == commands::DESCRIBE));
+    }
+
+    #[test]
+    fn test_results_have_schemas_unless_free_form() {
+        // manage_storage answers in a shape that depends on its store and action
+        let free_form = [commands::MANAGE_STORAGE];
+        let response = describe(&DescribeRequest::default(), &CommandPolicy::default(), app_info(), None);
+
+        for command in &response.commands {
+            assert_eq!(
+                command.result_schema.is_none(),
+                free_form.contains(&command.name.as_str()),
+                "result schema for {}",
+                command.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_describe_version_check_and_schema_opt_out() {
+        let request: DescribeRequest =
+            serde_json::from_value(json!({ "protocol_version": 99, "include_schemas": false })).unwrap();
+        let response = describe(&request, &CommandPolicy::default(), app_info(), None);
+
+        assert!(!response.compatible);
+        assert!(response.disabled_commands.is_empty());
+        assert!(response.commands.iter().all(|c| c.payload_schema.is_none() && c.result_schema.is_none()));
+    }
+}
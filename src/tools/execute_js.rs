@@ -1,19 +1,21 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Runtime};
 
-use crate::desktop::resolve_webview;
-use crate::error::Error;
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
 use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+use crate::tools::webview_request::{PendingResponse, ResponseTimeout};
 
 // Define a custom error type for JavaScript execution operations
 #[derive(Debug)]
 pub enum ExecuteJsError {
     WebviewOperation(String),
-    JavaScriptError(String),
+    JavaScriptError { message: String, stack: Option<String> },
 
     Timeout(String),
 }
@@ -23,7 +25,14 @@ impl fmt::Display for ExecuteJsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ExecuteJsError::WebviewOperation(s) => write!(f, "JavaScript execution error: {}", s),
-            ExecuteJsError::JavaScriptError(s) => write!(f, "JavaScript error: {}", s),
+            ExecuteJsError::JavaScriptError { message, stack } => match stack {
+                // V8 stacks start with the message, WebKit stacks are frames only
+                Some(stack) if !stack.starts_with(message.as_str()) => {
+                    write!(f, "JavaScript error: {}\n{}", message, stack)
+                }
+                Some(stack) => write!(f, "JavaScript error: {}", stack),
+                None => write!(f, "JavaScript error: {}", message),
+            },
             ExecuteJsError::Timeout(s) => write!(f, "Operation timed out: {}", s),
         }
     }
@@ -39,9 +48,21 @@ impl Serialize for ExecuteJsError {
     }
 }
 
+impl From<ExecuteJsError> for ErrorInfo {
+    fn from(error: ExecuteJsError) -> Self {
+        match error {
+            ExecuteJsError::JavaScriptError { message, stack } => {
+                ErrorInfo::js_exception(format!("JavaScript error: {}", message), stack.as_deref())
+            }
+            ExecuteJsError::WebviewOperation(_) => ErrorInfo::new(ErrorCode::WebviewError, error.to_string()),
+            ExecuteJsError::Timeout(_) => ErrorInfo::new(ErrorCode::Timeout, error.to_string()),
+        }
+    }
+}
+
 // Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for ExecuteJsError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
+impl From<ResponseTimeout> for ExecuteJsError {
+    fn from(err: ResponseTimeout) -> Self {
         ExecuteJsError::Timeout(format!(
             "Timeout waiting for JavaScript execution response: {}",
             err
@@ -49,16 +70,29 @@ impl From<mpsc::RecvTimeoutError> for ExecuteJsError {
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExecuteJsRequest {
+    /// Label of the webview to run the code in (defaults to the configured default target)
     window_label: Option<String>,
+    /// JavaScript code to execute
     code: String,
+    /// Maximum time in milliseconds to wait for the result, including any returned Promise
     timeout_ms: Option<u64>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+// Timeout used when the request sets none
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+// Extra time the Rust side waits past the timeout, so the script's own
+// "did not settle" error arrives instead of a bare receive timeout
+const TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExecuteJsResponse {
-    result: String,
+    /// The (awaited) result as JSON. Values JSON can't represent directly, such as
+    /// cycles, DOM nodes, Maps, Sets and Errors, are tagged objects with a `$type` field.
+    result: Value,
+    /// `typeof` the result
     #[serde(rename = "type")]
     result_type: String,
 }
@@ -67,14 +101,10 @@ pub async fn handle_execute_js<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let request: ExecuteJsRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for executeJs: {}", e)))?;
+    let request: ExecuteJsRequest = parse_payload(commands::EXECUTE_JS, payload)?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
 
     // Verify the webview exists using resolve_webview (supports multi-webview architecture)
     let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
@@ -97,12 +127,14 @@ pub async fn handle_execute_js<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into()),
+            id: None,
         }),
     }
 }
@@ -113,65 +145,113 @@ async fn execute_js_in_window<R: Runtime>(
     params: ExecuteJsRequest,
 ) -> Result<ExecuteJsResponse, ExecuteJsError> {
     // Get window label
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    let window_label = target_label(&app, params.window_label.as_deref());
 
-    // Get timeout or use default (5 seconds)
-    let timeout = Duration::from_millis(params.timeout_ms.unwrap_or(5000));
+    // The script applies the timeout to returned Promises; we allow a little longer
+    let timeout_ms = params.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    let timeout = Duration::from_millis(timeout_ms) + TIMEOUT_GRACE;
 
-    // Set up channel and listener BEFORE emitting to avoid race condition
-    let (tx, rx) = mpsc::channel();
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(&app, "execute-js-response");
 
-    app.once("execute-js-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
+    let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
+        "code": params.code,
+        "timeoutMs": timeout_ms
     });
 
     // Emit event to execute the JavaScript in the specified window
-    app.emit_to(&window_label, "execute-js", &params.code)
+    app.emit_to(&window_label, "execute-js", &js_payload)
         .map_err(|e| {
             ExecuteJsError::WebviewOperation(format!("Failed to emit execute-js event: {}", e))
         })?;
 
     // Wait for the response with timeout
-    match rx.recv_timeout(timeout) {
-        Ok(result_string) => {
-            // Parse the response JSON
-            let response: Value = serde_json::from_str(&result_string).map_err(|e| {
-                ExecuteJsError::JavaScriptError(format!("Failed to parse response: {}", e))
-            })?;
-
-            // Check if result contains an error
-            if let Some(error) = response.get("error") {
-                if let Some(error_str) = error.as_str() {
-                    return Err(ExecuteJsError::JavaScriptError(error_str.to_string()));
-                } else {
-                    return Err(ExecuteJsError::JavaScriptError(
-                        "Unknown JavaScript execution error".to_string(),
-                    ));
-                }
-            }
+    match pending.wait(timeout).await {
+        Ok(response) => parse_js_response(response),
+        Err(e) => Err(e.into()),
+    }
+}
 
-            // Build the ExecuteJsResponse
-            let result = response
-                .get("result")
-                .and_then(|r| r.as_str())
-                .unwrap_or("[Result could not be stringified]")
-                .to_string();
-
-            let result_type = response
-                .get("type")
-                .and_then(|t| t.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            Ok(ExecuteJsResponse {
-                result,
-                result_type,
-            })
+// Turn the guest script's reply into a result, a timeout or a JavaScript error
+fn parse_js_response(mut response: Value) -> Result<ExecuteJsResponse, ExecuteJsError> {
+    // Check if result contains an error
+    if let Some(error) = response.get("error") {
+        // A returned Promise that didn't settle in time is marked with the timeout code
+        if response["details"]["code"] == "timeout" {
+            return Err(ExecuteJsError::Timeout(
+                error.as_str().unwrap_or("Promise did not settle in time").to_string(),
+            ));
         }
-        Err(e) => Err(e.into()),
+        return Err(ExecuteJsError::JavaScriptError {
+            message: error.as_str().unwrap_or("Unknown JavaScript execution error").to_string(),
+            stack: response.get("stack").and_then(Value::as_str).map(str::to_string),
+        });
+    }
+
+    let result_type = response
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(ExecuteJsResponse {
+        result: response.get_mut("result").map(Value::take).unwrap_or(Value::Null),
+        result_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_structured_result_is_kept_as_json() {
+        let response = parse_js_response(json!({
+            "requestId": "1",
+            "result": { "items": [1, 2], "seen": { "$type": "circular" } },
+            "type": "object"
+        }))
+        .unwrap();
+
+        assert_eq!(response.result["items"], json!([1, 2]));
+        assert_eq!(response.result["seen"]["$type"], "circular");
+        assert_eq!(response.result_type, "object");
+    }
+
+    #[test]
+    fn test_error_includes_stack() {
+        let error = parse_js_response(json!({
+            "result": null,
+            "type": "error",
+            "error": "TypeError: x is undefined",
+            "stack": "load@app.js:1:1"
+        }))
+        .unwrap_err();
+
+        assert_eq!(error.to_string(), "JavaScript error: TypeError: x is undefined\nload@app.js:1:1");
+
+        let info = ErrorInfo::from(error);
+        assert_eq!(info.code, ErrorCode::JsException);
+        assert_eq!(info.message, "JavaScript error: TypeError: x is undefined");
+        assert_eq!(info.details.unwrap()["stack"], "load@app.js:1:1");
+    }
+
+    #[test]
+    fn test_unsettled_promise_is_timeout() {
+        let error = parse_js_response(json!({
+            "result": null,
+            "type": "error",
+            "error": "WaitTimeoutError: Promise did not settle within 100 ms",
+            "stack": "settleWithin@init.js:1:1",
+            "details": { "code": "timeout", "timeoutMs": 100 }
+        }))
+        .unwrap_err();
+
+        let info = ErrorInfo::from(error);
+        assert_eq!(info.code, ErrorCode::Timeout);
+        assert!(info.retryable);
+        assert_eq!(info.message, "Operation timed out: WaitTimeoutError: Promise did not settle within 100 ms");
     }
 }
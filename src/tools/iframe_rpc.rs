@@ -1,19 +1,20 @@
 use serde::{Serialize, Serializer};
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Runtime};
+use tauri::{AppHandle, Emitter, Runtime};
 
-use crate::desktop::resolve_webview;
-use crate::error::Error;
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
 use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+use crate::tools::webview_request::{PendingResponse, ResponseTimeout};
 
 // Define a custom error type for iframe RPC operations
 #[derive(Debug)]
 pub enum IframeRpcError {
     WebviewOperation(String),
-    RpcError(String),
     Timeout(String),
 }
 
@@ -22,7 +23,6 @@ impl fmt::Display for IframeRpcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             IframeRpcError::WebviewOperation(s) => write!(f, "Iframe RPC error: {}", s),
-            IframeRpcError::RpcError(s) => write!(f, "RPC error: {}", s),
             IframeRpcError::Timeout(s) => write!(f, "Operation timed out: {}", s),
         }
     }
@@ -38,9 +38,19 @@ impl Serialize for IframeRpcError {
     }
 }
 
+impl From<IframeRpcError> for ErrorInfo {
+    fn from(error: IframeRpcError) -> Self {
+        let code = match error {
+            IframeRpcError::WebviewOperation(_) => ErrorCode::WebviewError,
+            IframeRpcError::Timeout(_) => ErrorCode::Timeout,
+        };
+        ErrorInfo::new(code, error.to_string())
+    }
+}
+
 // Support conversion from timeout error
-impl From<mpsc::RecvTimeoutError> for IframeRpcError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
+impl From<ResponseTimeout> for IframeRpcError {
+    fn from(err: ResponseTimeout) -> Self {
         IframeRpcError::Timeout(format!(
             "Timeout waiting for iframe RPC response: {}",
             err
@@ -48,20 +58,20 @@ impl From<mpsc::RecvTimeoutError> for IframeRpcError {
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
 pub struct IframeRpcRequest {
     /// The RPC method to call on the iframe
     pub method: String,
     /// Arguments to pass to the RPC method
     #[serde(default)]
     pub args: Vec<Value>,
-    /// Optional window label (defaults to "main")
+    /// Optional window label (defaults to the configured default target)
     pub window_label: Option<String>,
     /// Optional timeout in milliseconds (defaults to 10000ms)
     pub timeout_ms: Option<u64>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct IframeRpcResponse {
     pub success: bool,
     pub result: Option<Value>,
@@ -72,14 +82,10 @@ pub async fn handle_iframe_rpc<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let request: IframeRpcRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for iframe_rpc: {}", e)))?;
+    let request: IframeRpcRequest = parse_payload(commands::IFRAME_RPC, payload)?;
 
-    // Get the window label or use "main" as default
-    let window_label = request
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
 
     // Verify the webview exists using resolve_webview (supports multi-webview architecture)
     let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
@@ -102,12 +108,14 @@ pub async fn handle_iframe_rpc<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into()),
+            id: None,
         }),
     }
 }
@@ -118,29 +126,21 @@ async fn execute_iframe_rpc<R: Runtime>(
     params: IframeRpcRequest,
 ) -> Result<IframeRpcResponse, IframeRpcError> {
     // Get window label
-    let window_label = params
-        .window_label
-        .clone()
-        .unwrap_or_else(|| "main".to_string());
+    let window_label = target_label(&app, params.window_label.as_deref());
 
     // Get timeout or use default (10 seconds)
     let timeout = Duration::from_millis(params.timeout_ms.unwrap_or(10000));
 
+    // Register the correlated listener BEFORE emitting (avoid race condition)
+    let pending = PendingResponse::listen(&app, "iframe-rpc-response");
+
     // Create the RPC payload
     let rpc_payload = serde_json::json!({
+        "requestId": pending.request_id(),
         "method": params.method,
         "args": params.args
     });
 
-    // Set up a channel to receive the response BEFORE emitting (avoid race condition)
-    let (tx, rx) = mpsc::channel();
-
-    // Listen for response
-    app.once("iframe-rpc-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
-
     eprintln!("[TAURI_MCP] Emitting iframe-rpc event to webview: {}", window_label);
 
     app.emit_to(&window_label, "iframe-rpc", &rpc_payload)
@@ -149,13 +149,8 @@ async fn execute_iframe_rpc<R: Runtime>(
         })?;
 
     // Wait for the response with timeout
-    match rx.recv_timeout(timeout) {
-        Ok(result_string) => {
-            // Parse the response JSON
-            let response: Value = serde_json::from_str(&result_string).map_err(|e| {
-                IframeRpcError::RpcError(format!("Failed to parse response: {}", e))
-            })?;
-
+    match pending.wait(timeout).await {
+        Ok(response) => {
             // Check if result contains a real error (ignore null/empty values)
             if let Some(error) = response.get("error") {
                 let is_real_error = match error {
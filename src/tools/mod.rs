@@ -1,32 +1,99 @@
-use serde_json::Value;
-use tauri::{AppHandle, Runtime};
+use enigo::{Enigo, Settings};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Manager, Runtime};
 use log::info;
 
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::policy::CommandPolicy;
 use crate::shared::commands;
 use crate::socket_server::SocketResponse;
 
 // Export command modules
+pub mod accessibility;
+pub mod capabilities;
+pub mod click_element;
+pub mod console_logs;
+pub mod describe;
 pub mod execute_js;
 pub mod iframe_rpc;
-pub mod local_storage;
+pub mod list_windows;
 pub mod mouse_movement;
+pub mod network_requests;
 pub mod ping;
+pub mod pointer_actions;
+pub mod press_keys;
+pub mod storage;
 pub mod take_screenshot;
 pub mod text_input;
+pub mod wait_for;
 pub mod webview;
+pub mod webview_request;
 pub mod window_manager;
 
 // Re-export command handler functions
+pub use accessibility::handle_get_accessibility_snapshot;
+pub use capabilities::handle_get_capabilities;
+pub use click_element::handle_click_element;
+pub use console_logs::handle_get_console_logs;
+pub use describe::handle_describe;
 pub use execute_js::handle_execute_js;
 pub use iframe_rpc::handle_iframe_rpc;
-pub use local_storage::handle_get_local_storage;
+pub use list_windows::handle_list_windows;
 pub use mouse_movement::handle_simulate_mouse_movement;
+pub use network_requests::{handle_clear_network_requests, handle_get_network_requests};
 pub use ping::handle_ping;
+pub use pointer_actions::handle_pointer_action;
+pub use press_keys::handle_press_keys;
+pub use storage::handle_manage_storage;
 pub use take_screenshot::handle_take_screenshot;
 pub use text_input::handle_simulate_text_input;
+pub use wait_for::handle_wait_for;
 pub use webview::{handle_get_dom, handle_get_element_position, handle_send_text_to_element, handle_capture_screenshot};
 pub use window_manager::handle_manage_window;
 
+/// Parse a command's payload, naming the offending field in `details.path` when it doesn't fit
+pub(crate) fn parse_payload<T: DeserializeOwned>(command: &str, payload: Value) -> Result<T, Error> {
+    serde_path_to_error::deserialize(payload).map_err(|e| {
+        let path = e.path().to_string();
+        let info = ErrorInfo::new(ErrorCode::InvalidPayload, format!("Invalid payload for {}: {}", command, e.inner()));
+        // "." is the payload itself
+        Error::Command(if path == "." { info } else { info.with_details(json!({ "path": path })) })
+    })
+}
+
+/// Parse the `data` a guest script answered with, so results keep the shape their schema describes
+pub(crate) fn parse_guest_data<T: DeserializeOwned>(command: &str, data: Value) -> Result<T, ErrorInfo> {
+    serde_path_to_error::deserialize(data).map_err(|e| {
+        ErrorInfo::new(ErrorCode::WebviewError, format!("Invalid {} result from guest script: {}", command, e))
+    })
+}
+
+/// Run blocking OS input (enigo calls and the pauses between them) on the shared runtime's
+/// blocking pool, so long holds and drags don't tie up the workers serving other clients
+pub(crate) async fn run_blocking<T, F>(task: F) -> Result<T, ErrorInfo>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ErrorInfo> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| ErrorInfo::new(ErrorCode::Internal, format!("Input task failed: {}", e)))?
+}
+
+/// Start enigo. Without an X11 display (Linux) or the Accessibility permission (macOS) this
+/// fails, and no OS input is possible in this session.
+pub(crate) fn start_enigo() -> Result<Enigo, ErrorInfo> {
+    Enigo::new(&Settings::default()).map_err(|e| {
+        ErrorInfo::new(ErrorCode::UnsupportedPlatform, format!("Failed to initialize Enigo: {}", e))
+    })
+}
+
+/// A failed OS input call
+pub(crate) fn os_error(message: String) -> ErrorInfo {
+    ErrorInfo::new(ErrorCode::OsError, message)
+}
+
 /// Handle command routing for socket requests
 pub async fn handle_command<R: Runtime>(
     app: &AppHandle<R>,
@@ -41,23 +108,54 @@ pub async fn handle_command<R: Runtime>(
             .unwrap_or_else(|_| "[failed to serialize]".to_string())
     );
 
+    // Aliases run under the canonical name's policy: clients written against the old storage
+    // name keep working, and `hello` is `describe`
+    let command = match command {
+        commands::MANAGE_LOCAL_STORAGE => commands::MANAGE_STORAGE,
+        commands::HELLO => commands::DESCRIBE,
+        command => command,
+    };
+
+    // Enforce the command policy before routing
+    if let Some(policy) = app.try_state::<CommandPolicy>()
+        && let Err(denial) = policy.check(command, &payload)
+    {
+        info!("[TAURI_MCP] Command {} denied: {}", command, denial.reason);
+        return Ok(denial.into_response());
+    }
+
     let result = match command {
         commands::PING => handle_ping(app, payload),
         commands::TAKE_SCREENSHOT => handle_take_screenshot(app, payload).await,
         commands::CAPTURE_SCREENSHOT => handle_capture_screenshot(app, payload).await,
         commands::GET_DOM => handle_get_dom(app, payload).await,
-        commands::MANAGE_LOCAL_STORAGE => handle_get_local_storage(app, payload).await,
+        commands::GET_ACCESSIBILITY_SNAPSHOT => handle_get_accessibility_snapshot(app, payload).await,
+        commands::MANAGE_STORAGE => handle_manage_storage(app, payload).await,
         commands::EXECUTE_JS => handle_execute_js(app, payload).await,
+        commands::GET_CONSOLE_LOGS => handle_get_console_logs(app, payload).await,
+        commands::GET_NETWORK_REQUESTS => handle_get_network_requests(app, payload).await,
+        commands::CLEAR_NETWORK_REQUESTS => handle_clear_network_requests(app, payload).await,
         commands::IFRAME_RPC => handle_iframe_rpc(app, payload).await,
         commands::MANAGE_WINDOW => handle_manage_window(app, payload).await,
+        commands::LIST_WINDOWS => handle_list_windows(app, payload).await,
         commands::SIMULATE_TEXT_INPUT => handle_simulate_text_input(app, payload).await,
+        commands::PRESS_KEYS => handle_press_keys(app, payload).await,
         commands::SIMULATE_MOUSE_MOVEMENT => handle_simulate_mouse_movement(app, payload).await,
+        commands::POINTER_ACTION => handle_pointer_action(app, payload).await,
         commands::GET_ELEMENT_POSITION => handle_get_element_position(app, payload).await,
         commands::SEND_TEXT_TO_ELEMENT => handle_send_text_to_element(app, payload).await,
+        commands::CLICK_ELEMENT => handle_click_element(app, payload).await,
+        commands::WAIT_FOR => handle_wait_for(app, payload).await,
+        commands::GET_CAPABILITIES => handle_get_capabilities(app, payload),
+        commands::DESCRIBE => handle_describe(app, payload),
         _ => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(format!("Unknown command: {}", command)),
+            error: Some(
+                ErrorInfo::new(ErrorCode::UnknownCommand, format!("Unknown command: {}", command))
+                    .with_details(json!({ "command": command })),
+            ),
+            id: None,
         }),
     };
 
@@ -92,7 +190,7 @@ pub async fn handle_command<R: Runtime>(
         }
 
         if let Some(ref err) = response.error {
-            info!("[TAURI_MCP] Error: {}", err);
+            info!("[TAURI_MCP] Error ({:?}): {}", err.code, err.message);
         }
     } else if let Err(ref e) = result {
         info!(
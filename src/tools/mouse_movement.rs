@@ -1,12 +1,13 @@
 use serde_json::Value;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime};
 
-use crate::desktop::resolve_window;
-use crate::error::Error;
-use crate::models::MouseMovementRequest;
-use crate::shared::{MouseMovementParams, MouseMovementResult};
+use crate::desktop::{resolve_webview, resolve_window, target_label};
+use crate::error::{Error, ErrorCode};
+use crate::models::{CoordinateUnit, MouseMovementRequest};
+use crate::shared::{MouseMovementParams, MouseMovementResult, commands};
 use crate::socket_server::SocketResponse;
-use enigo::{Button, Coordinate, Direction, Enigo, Mouse, Settings};
+use crate::tools::{parse_payload, start_enigo};
+use enigo::{Button, Coordinate, Direction, Mouse};
 use std::time::Instant;
 use log::info;
 
@@ -19,57 +20,28 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
         params
     );
 
-    // Get the window reference (supports both single and multi-webview architectures)
-    let window = resolve_window(app, "main")?;
-
-    // Get window position (outer includes window borders/decorations)
-    let window_position = window
-        .outer_position()
-        .map_err(|e| Error::Anyhow(format!("Failed to get window position: {}", e)))?;
-    info!(
-        "[MOUSE_MOVEMENT] Window outer position: {:?}",
-        window_position
-    );
-
-    // Also get inner position for comparison
-    let window_inner_position = window
-        .inner_position()
-        .map_err(|e| Error::Anyhow(format!("Failed to get window inner position: {}", e)))?;
-    info!(
-        "[MOUSE_MOVEMENT] Window inner position: {:?}",
-        window_inner_position
-    );
-
-    // Get window size for reference
-    let window_size = window
-        .inner_size()
-        .map_err(|e| Error::Anyhow(format!("Failed to get window size: {}", e)))?;
-    info!("[MOUSE_MOVEMENT] Window inner size: {:?}", window_size);
-
-    // Get window scale factor for high DPI screens
-    let scale_factor = window
-        .scale_factor()
-        .map_err(|e| Error::Anyhow(format!("Failed to get scale factor: {}", e)))?;
-    info!("[MOUSE_MOVEMENT] Window scale factor: {}", scale_factor);
+    // Coordinates are relative to the requested webview, or the default target
+    let window_label = target_label(app, params.window_label.as_deref());
+    let origin = ScreenOrigin::resolve(app, &window_label)?;
 
     let x = params.x;
     let y = params.y;
+    let unit = params.unit.unwrap_or_default();
     let relative = params.relative.unwrap_or(false);
     let click = params.click.unwrap_or(false);
     let button_type = params.button.as_deref().unwrap_or("left");
 
     info!(
-        "[MOUSE_MOVEMENT] Input coordinates: x={}, y={}, relative={}",
-        x, y, relative
+        "[MOUSE_MOVEMENT] Input coordinates: x={}, y={} ({:?}), relative={}",
+        x, y, unit, relative
     );
 
     // Create Enigo instance
-    let mut enigo = Enigo::new(&Settings::default())
-        .map_err(|e| Error::Anyhow(format!("Failed to initialize Enigo: {}", e)))?;
+    let mut enigo = start_enigo().map_err(Error::Command)?;
 
     // Get current mouse position for reference
     let current_position = Mouse::location(&enigo)
-        .map_err(|e| Error::Anyhow(format!("Failed to get current mouse position: {}", e)))?;
+        .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to get current mouse position: {}", e)))?;
     info!(
         "[MOUSE_MOVEMENT] Current mouse position before move: ({}, {})",
         current_position.0, current_position.1
@@ -82,33 +54,7 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
         info!("[MOUSE_MOVEMENT] Using relative movement, no coordinate transformation");
         (x, y) // Keep as is for relative movements
     } else {
-        // Adjust for window position and scale factor
-        let scaled_x = (x as f64 * scale_factor) as i32;
-        let scaled_y = (y as f64 * scale_factor) as i32;
-
-        info!("[MOUSE_MOVEMENT] Coordinate transformation:");
-        info!("[MOUSE_MOVEMENT] 1. Original coordinates: ({}, {})", x, y);
-        info!(
-            "[MOUSE_MOVEMENT] 2. After scale factor ({}): ({}, {})",
-            scale_factor, scaled_x, scaled_y
-        );
-
-        let final_x = scaled_x + window_position.x;
-        let final_y = scaled_y + window_position.y;
-        info!(
-            "[MOUSE_MOVEMENT] 3. After adding window position ({}, {}): ({}, {})",
-            window_position.x, window_position.y, final_x, final_y
-        );
-
-        // Calculate what it would be with inner position for comparison
-        let inner_x = scaled_x + window_inner_position.x;
-        let inner_y = scaled_y + window_inner_position.y;
-        info!(
-            "[MOUSE_MOVEMENT] (Alternative with inner position: ({}, {}))",
-            inner_x, inner_y
-        );
-
-        (final_x, final_y)
+        origin.screen_point(f64::from(x), f64::from(y), unit)
     };
 
     info!(
@@ -124,7 +70,7 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
     };
 
     Mouse::move_mouse(&mut enigo, screen_x, screen_y, coordinate_type)
-        .map_err(|e| Error::Anyhow(format!("Failed to move mouse: {}", e)))?;
+        .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to move mouse: {}", e)))?;
 
     // Perform click if requested
     if click {
@@ -139,17 +85,17 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
 
         // Perform click (press and release)
         Mouse::button(&mut enigo, button, Direction::Press)
-            .map_err(|e| Error::Anyhow(format!("Failed to press mouse button: {}", e)))?;
+            .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to press mouse button: {}", e)))?;
 
         Mouse::button(&mut enigo, button, Direction::Release)
-            .map_err(|e| Error::Anyhow(format!("Failed to release mouse button: {}", e)))?;
+            .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to release mouse button: {}", e)))?;
     }
 
     let duration_ms = start_time.elapsed().as_millis() as u64;
 
     // Get current position after movement
     let position = Mouse::location(&enigo)
-        .map_err(|e| Error::Anyhow(format!("Failed to get mouse position: {}", e)))?;
+        .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to get mouse position: {}", e)))?;
     info!(
         "[MOUSE_MOVEMENT] Final mouse position after move: ({}, {})",
         position.0, position.1
@@ -162,6 +108,69 @@ pub async fn simulate_mouse_movement_async<R: Runtime>(
     })
 }
 
+/// Where a webview's content starts on screen, for turning webview coordinates into the
+/// physical screen pixels enigo moves the pointer in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenOrigin {
+    /// Screen position of the content's top-left corner, in physical pixels
+    pub position: PhysicalPosition<i32>,
+    pub scale_factor: f64,
+}
+
+impl ScreenOrigin {
+    /// Origin of the webview `label` resolves to: the window's inner (content) position, plus the
+    /// webview's offset when it is a child webview. A label naming a window with several webviews
+    /// falls back to the window's content area.
+    pub fn resolve<R: Runtime>(app: &AppHandle<R>, label: &str) -> crate::Result<Self> {
+        let (window, offset) = match resolve_webview(app, label) {
+            Ok((webview_label, webview)) => {
+                // A webview window fills its window; only child webviews sit at an offset
+                let offset = if app.get_webview_window(&webview_label).is_some() {
+                    PhysicalPosition::new(0, 0)
+                } else {
+                    webview
+                        .position()
+                        .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to get webview position: {}", e)))?
+                };
+                (webview.window(), offset)
+            }
+            Err(_) => (resolve_window(app, label)?, PhysicalPosition::new(0, 0)),
+        };
+
+        // Inner position excludes the title bar and borders, where page coordinates don't reach
+        let inner_position = window
+            .inner_position()
+            .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to get window inner position: {}", e)))?;
+
+        // Get window scale factor for high DPI screens
+        let scale_factor = window
+            .scale_factor()
+            .map_err(|e| Error::new(ErrorCode::OsError, format!("Failed to get scale factor: {}", e)))?;
+
+        let origin = Self {
+            position: PhysicalPosition::new(inner_position.x + offset.x, inner_position.y + offset.y),
+            scale_factor,
+        };
+        info!(
+            "[MOUSE_MOVEMENT] Origin of '{}': window inner position {:?} + webview offset {:?} = {:?}, scale factor {}",
+            label, inner_position, offset, origin.position, scale_factor
+        );
+        Ok(origin)
+    }
+
+    /// Convert a point relative to the origin into screen pixels
+    pub fn screen_point(&self, x: f64, y: f64, unit: CoordinateUnit) -> (i32, i32) {
+        let scale = match unit {
+            CoordinateUnit::Logical => self.scale_factor,
+            CoordinateUnit::Physical => 1.0,
+        };
+        (
+            self.position.x + (x * scale).round() as i32,
+            self.position.y + (y * scale).round() as i32,
+        )
+    }
+}
+
 pub fn simulate_mouse_movement_shared<R: Runtime>(
     app: &AppHandle<R>,
     params: MouseMovementParams,
@@ -172,8 +181,10 @@ pub fn simulate_mouse_movement_shared<R: Runtime>(
 
     // Convert shared params to internal type
     let request = MouseMovementRequest {
+        window_label: params.window_label,
         x: params.x,
         y: params.y,
+        unit: params.unit,
         relative: params.relative,
         click: params.click,
         button: params.button,
@@ -204,8 +215,7 @@ pub async fn handle_simulate_mouse_movement<R: Runtime>(
     payload: Value,
 ) -> Result<SocketResponse, Error> {
     // Parse the payload
-    let params: MouseMovementRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for simulateMouseMovement: {}", e)))?;
+    let params: MouseMovementRequest = parse_payload(commands::SIMULATE_MOUSE_MOVEMENT, payload)?;
 
     // Call the async method
     let result = simulate_mouse_movement_async(app, params).await;
@@ -218,12 +228,57 @@ pub async fn handle_simulate_mouse_movement<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into_info()),
+            id: None,
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_point_scales_logical_coordinates() {
+        let origin = ScreenOrigin {
+            position: PhysicalPosition::new(200, 150),
+            scale_factor: 2.0,
+        };
+
+        assert_eq!(origin.screen_point(0.0, 0.0, CoordinateUnit::Logical), (200, 150));
+        assert_eq!(origin.screen_point(100.0, 40.25, CoordinateUnit::Logical), (400, 231));
+        assert_eq!(origin.screen_point(100.0, 40.0, CoordinateUnit::Physical), (300, 190));
+    }
+
+    #[test]
+    fn test_screen_point_fractional_scale_and_negative_origin() {
+        // A window on a monitor left of the primary one, at 150% scaling
+        let origin = ScreenOrigin {
+            position: PhysicalPosition::new(-1920, 30),
+            scale_factor: 1.5,
+        };
+
+        assert_eq!(origin.screen_point(15.0, 11.0, CoordinateUnit::Logical), (-1897, 47));
+        assert_eq!(origin.screen_point(-10.0, 0.5, CoordinateUnit::Physical), (-1930, 31));
+    }
+
+    #[test]
+    fn test_mouse_movement_request_fields() {
+        let request: MouseMovementRequest = serde_json::from_value(serde_json::json!({
+            "window_label": "settings",
+            "x": 10,
+            "y": 20,
+            "unit": "physical"
+        }))
+        .unwrap();
+
+        assert_eq!(request.window_label.as_deref(), Some("settings"));
+        assert_eq!(request.unit, Some(CoordinateUnit::Physical));
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+use crate::tools::webview_request::PendingResponse;
+
+// How long to wait for the guest script to hand over its records
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkKind {
+    Fetch,
+    Xhr,
+    Invoke,
+}
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct GetNetworkRequestsRequest {
+    /// Label of the webview whose requests are read (defaults to the configured default target)
+    window_label: Option<String>,
+    /// Only return these kinds of request: "fetch", "xhr" or "invoke" (all when omitted)
+    #[serde(default)]
+    kinds: Vec<NetworkKind>,
+    /// Only return requests with this HTTP method (case-insensitive); invokes use "INVOKE"
+    method: Option<String>,
+    /// Only return requests whose URL, or command name for invokes, contains this text (case-insensitive)
+    url_pattern: Option<String>,
+    /// Only return requests that failed: network errors, rejected invokes and non-2xx statuses
+    #[serde(default)]
+    failed_only: bool,
+    /// Only return requests started at or after this time, in milliseconds since the Unix epoch
+    since: Option<u64>,
+    /// Return at most this many requests, keeping the most recent
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct ClearNetworkRequestsRequest {
+    /// Label of the webview whose records are cleared (defaults to the configured default target)
+    window_label: Option<String>,
+}
+
+/// One fetch, XMLHttpRequest or Tauri invoke made by the page
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkRecord {
+    id: u64,
+    kind: NetworkKind,
+    method: String,
+    /// Request URL, or the command name for invokes
+    url: String,
+    /// Request body as text, truncated; binary bodies are summarized
+    request_body: Option<String>,
+    /// HTTP status, or None while pending or when the request never got a response
+    status: Option<u16>,
+    /// Whether the request succeeded, or None while pending
+    ok: Option<bool>,
+    /// Response body as text, truncated
+    response_body: Option<String>,
+    /// Milliseconds since the Unix epoch
+    start_time: u64,
+    duration_ms: Option<f64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetNetworkRequestsResponse {
+    requests: Vec<NetworkRecord>,
+    /// Records in the buffer before filtering
+    buffered: usize,
+    /// Records evicted because the buffer was full since it was last cleared
+    dropped: u64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ClearNetworkRequestsResponse {
+    /// Records removed from the buffer
+    cleared: usize,
+}
+
+pub async fn handle_get_network_requests<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: GetNetworkRequestsRequest = parse_optional(commands::GET_NETWORK_REQUESTS, payload)?;
+
+    let response = match read_records(app, request.window_label.as_deref(), false).await {
+        Ok(response) => response,
+        Err(error) => return Ok(failure(error)),
+    };
+
+    let records: Vec<NetworkRecord> = serde_json::from_value(response["requests"].clone())
+        .map_err(|e| ErrorInfo::new(ErrorCode::WebviewError, format!("Invalid network records from guest script: {}", e)))?;
+    let buffered = records.len();
+
+    let data = serde_json::to_value(GetNetworkRequestsResponse {
+        requests: filter_records(records, &request),
+        buffered,
+        dropped: response["dropped"].as_u64().unwrap_or(0),
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+pub async fn handle_clear_network_requests<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: ClearNetworkRequestsRequest = parse_optional(commands::CLEAR_NETWORK_REQUESTS, payload)?;
+
+    let response = match read_records(app, request.window_label.as_deref(), true).await {
+        Ok(response) => response,
+        Err(error) => return Ok(failure(error)),
+    };
+
+    let data = serde_json::to_value(ClearNetworkRequestsResponse {
+        cleared: response["requests"].as_array().map_or(0, Vec::len),
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+fn parse_optional<T: Default + serde::de::DeserializeOwned>(command: &str, payload: Value) -> Result<T, Error> {
+    if payload.is_null() {
+        return Ok(T::default());
+    }
+    parse_payload(command, payload)
+}
+
+fn failure(error: ErrorInfo) -> SocketResponse {
+    SocketResponse {
+        success: false,
+        data: None,
+        error: Some(error),
+        id: None,
+    }
+}
+
+// Fetch the webview's records, emptying its buffer when `clear` is set
+async fn read_records<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: Option<&str>,
+    clear: bool,
+) -> Result<Value, ErrorInfo> {
+    // Fall back to the configured default target
+    let window_label = target_label(app, window_label);
+    let (resolved_label, _webview) = resolve_webview(app, &window_label).map_err(Error::into_info)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "get-network-requests-response");
+
+    let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
+        "clear": clear
+    });
+
+    app.emit_to(&resolved_label, "get-network-requests", &js_payload)
+        .map_err(|e| ErrorInfo::new(ErrorCode::WebviewError, format!("Failed to emit get-network-requests event: {}", e)))?;
+
+    pending
+        .wait(RESPONSE_TIMEOUT).await
+        .map_err(|e| e.into_info(&format!("Timeout waiting for network records from '{}'", resolved_label)))
+}
+
+// Apply the request's filters, then the limit
+fn filter_records(records: Vec<NetworkRecord>, request: &GetNetworkRequestsRequest) -> Vec<NetworkRecord> {
+    let url_pattern = request.url_pattern.as_deref().map(str::to_lowercase);
+
+    let mut records: Vec<NetworkRecord> = records
+        .into_iter()
+        .filter(|record| request.kinds.is_empty() || request.kinds.contains(&record.kind))
+        .filter(|record| {
+            request
+                .method
+                .as_deref()
+                .is_none_or(|method| record.method.eq_ignore_ascii_case(method))
+        })
+        .filter(|record| {
+            url_pattern
+                .as_deref()
+                .is_none_or(|pattern| record.url.to_lowercase().contains(pattern))
+        })
+        .filter(|record| !request.failed_only || record.ok == Some(false))
+        .filter(|record| request.since.is_none_or(|since| record.start_time >= since))
+        .collect();
+
+    if let Some(limit) = request.limit
+        && records.len() > limit
+    {
+        records.drain(..records.len() - limit);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Vec<NetworkRecord> {
+        let record = |id: u64, kind: &str, method: &str, url: &str, ok: Option<bool>| {
+            json!({
+                "id": id, "kind": kind, "method": method, "url": url,
+                "requestBody": null, "status": null, "ok": ok, "responseBody": null,
+                "startTime": id * 1000, "durationMs": null, "error": null
+            })
+        };
+        serde_json::from_value(json!([
+            record(1, "fetch", "GET", "https://api.example.com/users", Some(true)),
+            record(2, "invoke", "INVOKE", "save_document", Some(false)),
+            record(3, "xhr", "POST", "https://api.example.com/upload", Some(false)),
+            record(4, "fetch", "POST", "https://api.example.com/users", None),
+        ]))
+        .unwrap()
+    }
+
+    fn ids(records: &[NetworkRecord]) -> Vec<u64> {
+        records.iter().map(|record| record.id).collect()
+    }
+
+    #[test]
+    fn test_filter_by_kind_method_and_url() {
+        let request: GetNetworkRequestsRequest =
+            serde_json::from_value(json!({ "kinds": ["fetch", "xhr"], "method": "post" })).unwrap();
+        assert_eq!(ids(&filter_records(records(), &request)), vec![3, 4]);
+
+        let request: GetNetworkRequestsRequest = serde_json::from_value(json!({ "url_pattern": "USERS" })).unwrap();
+        assert_eq!(ids(&filter_records(records(), &request)), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_failed_only_since_and_limit() {
+        let request: GetNetworkRequestsRequest = serde_json::from_value(json!({ "failed_only": true })).unwrap();
+        assert_eq!(ids(&filter_records(records(), &request)), vec![2, 3]);
+
+        let request: GetNetworkRequestsRequest = serde_json::from_value(json!({ "since": 2000, "limit": 2 })).unwrap();
+        assert_eq!(ids(&filter_records(records(), &request)), vec![3, 4]);
+    }
+}
@@ -4,14 +4,15 @@ use tauri::{AppHandle, Runtime};
 use crate::TauriMcpExt;
 use crate::error::Error;
 use crate::models::PingRequest;
+use crate::shared::commands;
 use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
 
 pub fn handle_ping<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let payload: PingRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for ping: {}", e)))?;
+    let payload: PingRequest = parse_payload(commands::PING, payload)?;
 
     match app.tauri_mcp().ping(payload) {
         Ok(response) => {
@@ -21,12 +22,14 @@ pub fn handle_ping<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into_info()),
+            id: None,
         }),
     }
 }
@@ -0,0 +1,488 @@
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Mouse};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, resolve_window, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::selector::ElementSelector;
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::models::CoordinateUnit;
+use crate::tools::mouse_movement::ScreenOrigin;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::{os_error, run_blocking, start_enigo};
+use crate::tools::wait_for::check_wait_ms;
+use crate::tools::webview::FoundElement;
+use crate::tools::webview_request::PendingResponse;
+
+const DEFAULT_DRAG_STEPS: u32 = 10;
+const MAX_DRAG_STEPS: u32 = 500;
+const DEFAULT_HOVER_MS: u64 = 300;
+const DEFAULT_DRAG_HOLD_MS: u64 = 100;
+// Pause between the clicks of a double or triple click, well inside the OS double-click time
+const MULTI_CLICK_GAP: Duration = Duration::from_millis(40);
+// Pause between the intermediate moves of a drag, about one frame
+const DRAG_STEP_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PointerAction {
+    /// Move the pointer to the target
+    Move,
+    /// Move the pointer to the target and rest there for `hold_ms` (default 300)
+    Hover,
+    /// Click the target; `hold_ms` keeps the button pressed to make it a press-and-hold
+    Click,
+    /// Double-click the target
+    DoubleClick,
+    /// Triple-click the target, e.g. to select a paragraph
+    TripleClick,
+    /// Press the button, at the target if one is given, and keep it pressed
+    Down,
+    /// Release the button, at the target if one is given
+    Up,
+    /// Press at the target, move to the end target in `steps` moves, then release
+    Drag,
+    /// Turn the scroll wheel by `delta_x`/`delta_y` notches, over the target if one is given
+    Scroll,
+}
+
+impl PointerAction {
+    /// The action's name as it appears in requests
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PointerAction::Move => "move",
+            PointerAction::Hover => "hover",
+            PointerAction::Click => "click",
+            PointerAction::DoubleClick => "double_click",
+            PointerAction::TripleClick => "triple_click",
+            PointerAction::Down => "down",
+            PointerAction::Up => "up",
+            PointerAction::Drag => "drag",
+            PointerAction::Scroll => "scroll",
+        }
+    }
+}
+
+impl fmt::Display for PointerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerButton {
+    #[default]
+    Left,
+    Right,
+    Middle,
+}
+
+impl From<PointerButton> for Button {
+    fn from(button: PointerButton) -> Self {
+        match button {
+            PointerButton::Left => Button::Left,
+            PointerButton::Right => Button::Right,
+            PointerButton::Middle => Button::Middle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct PointerActionRequest {
+    /// Label of the window or webview the coordinates and selectors refer to (defaults to the configured default target)
+    window_label: Option<String>,
+    /// What to do with the pointer
+    action: PointerAction,
+    /// Target x from the webview's left edge; give x and y, or a selector
+    x: Option<f64>,
+    /// Target y from the webview's top edge
+    y: Option<f64>,
+    /// Element whose center is the target; it is scrolled into view first
+    selector: Option<ElementSelector>,
+    /// End x of a drag, from the webview's left edge
+    to_x: Option<f64>,
+    /// End y of a drag, from the webview's top edge
+    to_y: Option<f64>,
+    /// Element whose center is the end of a drag
+    to_selector: Option<ElementSelector>,
+    /// Unit of x, y, to_x and to_y: "logical" CSS pixels (default) or "physical" device pixels
+    #[serde(default)]
+    unit: CoordinateUnit,
+    /// Mouse button to use (default "left")
+    #[serde(default)]
+    button: PointerButton,
+    /// Number of intermediate moves of a drag (default 10)
+    steps: Option<u32>,
+    /// Horizontal scroll in wheel notches, positive to the right, for "scroll"
+    delta_x: Option<i32>,
+    /// Vertical scroll in wheel notches, positive down, for "scroll"
+    delta_y: Option<i32>,
+    /// How long to hold the button for "click" and before moving in "drag", or to rest for "hover", in milliseconds
+    hold_ms: Option<u64>,
+    /// Wait up to this many milliseconds for selector targets to be visible, stable and enabled
+    auto_wait_ms: Option<u64>,
+}
+
+/// Where an action happens: a point in the window, or an element's center
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Point { x: f64, y: f64 },
+    Element(ElementSelector),
+}
+
+impl Target {
+    // Build a target from the coordinate and selector fields sharing `prefix`
+    fn from_fields(
+        prefix: &str,
+        x: Option<f64>,
+        y: Option<f64>,
+        selector: Option<&ElementSelector>,
+    ) -> Result<Option<Self>, String> {
+        match (x, y, selector) {
+            (None, None, None) => Ok(None),
+            (None, None, Some(selector)) => Ok(Some(Target::Element(selector.clone()))),
+            (_, _, Some(_)) => Err(format!("give either {0}x and {0}y or {0}selector, not both", prefix)),
+            (Some(x), Some(y), None) if x.is_finite() && y.is_finite() => Ok(Some(Target::Point { x, y })),
+            (Some(_), Some(_), None) => Err(format!("{0}x and {0}y must be finite numbers", prefix)),
+            _ => Err(format!("{0}x and {0}y must be given together", prefix)),
+        }
+    }
+}
+
+impl PointerActionRequest {
+    // Check the fields against the action, returning its start and end targets
+    fn targets(&self) -> Result<(Option<Target>, Option<Target>), String> {
+        let action = self.action;
+        let start = Target::from_fields("", self.x, self.y, self.selector.as_ref())?;
+        let end = Target::from_fields("to_", self.to_x, self.to_y, self.to_selector.as_ref())?;
+
+        let target_optional = matches!(self.action, PointerAction::Down | PointerAction::Up | PointerAction::Scroll);
+        if start.is_none() && !target_optional {
+            return Err(format!("{} needs a target: x and y, or selector", action));
+        }
+        match (self.action, &end) {
+            (PointerAction::Drag, None) => return Err("drag needs an end: to_x and to_y, or to_selector".to_string()),
+            (PointerAction::Drag, Some(_)) | (_, None) => {}
+            (_, Some(_)) => return Err(format!("to_x, to_y and to_selector can only be used with drag, not {}", action)),
+        }
+
+        let has_delta = self.delta_x.is_some() || self.delta_y.is_some();
+        if self.action == PointerAction::Scroll {
+            if self.delta_x.unwrap_or(0) == 0 && self.delta_y.unwrap_or(0) == 0 {
+                return Err("scroll needs a non-zero delta_x or delta_y".to_string());
+            }
+        } else if has_delta {
+            return Err(format!("delta_x and delta_y can only be used with scroll, not {}", action));
+        }
+
+        if self.steps.is_some_and(|steps| steps == 0 || steps > MAX_DRAG_STEPS) {
+            return Err(format!("steps must be between 1 and {}", MAX_DRAG_STEPS));
+        }
+        check_wait_ms("hold_ms", self.hold_ms())?;
+        check_wait_ms("auto_wait_ms", self.auto_wait_ms.unwrap_or(0))?;
+        Ok((start, end))
+    }
+
+    fn hold_ms(&self) -> u64 {
+        self.hold_ms.unwrap_or(match self.action {
+            PointerAction::Hover => DEFAULT_HOVER_MS,
+            PointerAction::Drag => DEFAULT_DRAG_HOLD_MS,
+            _ => 0,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerActionResponse {
+    action: PointerAction,
+    /// Where the pointer ended up, in physical screen pixels
+    position: (i32, i32),
+    duration_ms: u64,
+    /// Element the action started at, when the target was a selector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<FoundElement>,
+    /// Element a drag ended at, when the end target was a selector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    drop_target: Option<FoundElement>,
+}
+
+/// A target resolved to webview coordinates, with the element it came from
+struct Located {
+    x: f64,
+    y: f64,
+    unit: CoordinateUnit,
+    element: Option<FoundElement>,
+}
+
+// The part of get-element-position's answer a pointer action needs
+#[derive(Deserialize)]
+struct ElementPosition {
+    x: f64,
+    y: f64,
+    element: FoundElement,
+}
+
+pub async fn handle_pointer_action<R: Runtime>(app: &AppHandle<R>, payload: Value) -> Result<SocketResponse, Error> {
+    let request: PointerActionRequest = parse_payload(commands::POINTER_ACTION, payload)?;
+    let (start, end) = request.targets().map_err(Error::invalid_payload)?;
+
+    let window_label = target_label(app, request.window_label.as_deref());
+    let origin = ScreenOrigin::resolve(app, &window_label)?;
+    let auto_wait_ms = request.auto_wait_ms.unwrap_or(0);
+
+    // Find element targets before touching the pointer, so a missing element changes nothing
+    let mut located = Vec::new();
+    for target in [start, end].into_iter().flatten() {
+        located.push(locate(app, &window_label, target, request.unit, auto_wait_ms).await?);
+    }
+    let points: Vec<(i32, i32)> = located
+        .iter()
+        .map(|location| origin.screen_point(location.x, location.y, location.unit))
+        .collect();
+    // Only drags have a second target, and they always have a first
+    let (start_point, end_point) = (points.first().copied(), points.get(1).copied());
+
+    // OS pointer events go to the window under the cursor, which should be ours
+    resolve_window(app, &window_label)?.set_focus()?;
+    let start_time = Instant::now();
+
+    let action = request.clone();
+    let position = match run_blocking(move || perform(&action, start_point, end_point)).await {
+        Ok(position) => position,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                id: None,
+            });
+        }
+    };
+
+    let mut elements = located.into_iter().map(|location| location.element);
+    let data = serde_json::to_value(PointerActionResponse {
+        action: request.action,
+        position,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+        target: elements.next().flatten(),
+        drop_target: elements.next().flatten(),
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+// Resolve a target to webview coordinates; elements are scrolled into view and measured by the guest script
+async fn locate<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    target: Target,
+    unit: CoordinateUnit,
+    auto_wait_ms: u64,
+) -> Result<Located, ErrorInfo> {
+    let selector = match target {
+        Target::Point { x, y } => return Ok(Located { x, y, unit, element: None }),
+        Target::Element(selector) => selector,
+    };
+
+    let (resolved_label, _webview) = resolve_webview(app, window_label).map_err(Error::into_info)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "get-element-position-response");
+
+    let js_payload = json!({
+        "requestId": pending.request_id(),
+        "selector": selector.to_js(),
+        "shouldClick": false,
+        "autoWaitMs": auto_wait_ms,
+        "scrollIntoView": true
+    });
+    app.emit_to(&resolved_label, "get-element-position", js_payload)
+        .map_err(|e| ErrorInfo::new(ErrorCode::WebviewError, format!("Failed to emit get-element-position event: {}", e)))?;
+
+    let result = pending
+        .wait(Duration::from_secs(5) + Duration::from_millis(auto_wait_ms)).await
+        .map_err(|e| e.into_info("Timeout waiting for element position"))?;
+    if result["success"].as_bool() != Some(true) {
+        return Err(ErrorInfo::from_guest(&result, ErrorCode::WebviewError, "Unknown error finding element"));
+    }
+
+    let position: ElementPosition = parse_guest_data(commands::GET_ELEMENT_POSITION, result["data"].clone())?;
+    // The page measures in CSS pixels
+    Ok(Located {
+        x: position.x,
+        y: position.y,
+        unit: CoordinateUnit::Logical,
+        element: Some(position.element),
+    })
+}
+
+// Run the action with enigo at screen coordinates, returning where the pointer ends up
+fn perform(
+    request: &PointerActionRequest,
+    start: Option<(i32, i32)>,
+    end: Option<(i32, i32)>,
+) -> Result<(i32, i32), ErrorInfo> {
+    let mut enigo = start_enigo()?;
+    let button = Button::from(request.button);
+    let hold = Duration::from_millis(request.hold_ms());
+
+    if let Some((x, y)) = start {
+        enigo
+            .move_mouse(x, y, Coordinate::Abs)
+            .map_err(|e| os_error(format!("Failed to move mouse: {}", e)))?;
+    }
+
+    let result = match request.action {
+        PointerAction::Move => Ok(()),
+        PointerAction::Hover => {
+            thread::sleep(hold);
+            Ok(())
+        }
+        PointerAction::Click if hold.is_zero() => enigo.button(button, Direction::Click),
+        PointerAction::Click => press_and_release(&mut enigo, button, |_| {
+            thread::sleep(hold);
+            Ok(())
+        }),
+        PointerAction::DoubleClick => click_times(&mut enigo, button, 2),
+        PointerAction::TripleClick => click_times(&mut enigo, button, 3),
+        PointerAction::Down => enigo.button(button, Direction::Press),
+        PointerAction::Up => enigo.button(button, Direction::Release),
+        PointerAction::Drag => {
+            let (Some(from), Some(to)) = (start, end) else {
+                return Err(ErrorInfo::new(ErrorCode::InvalidPayload, "drag needs a start and an end"));
+            };
+            let steps = request.steps.unwrap_or(DEFAULT_DRAG_STEPS);
+            press_and_release(&mut enigo, button, |enigo| {
+                thread::sleep(hold);
+                for (x, y) in drag_path(from, to, steps) {
+                    thread::sleep(DRAG_STEP_INTERVAL);
+                    enigo.move_mouse(x, y, Coordinate::Abs)?;
+                }
+                Ok(())
+            })
+        }
+        PointerAction::Scroll => {
+            let mut result = Ok(());
+            if let Some(delta) = request.delta_y.filter(|delta| *delta != 0) {
+                result = enigo.scroll(delta, Axis::Vertical);
+            }
+            if let Some(delta) = request.delta_x.filter(|delta| *delta != 0) {
+                result = result.and_then(|()| enigo.scroll(delta, Axis::Horizontal));
+            }
+            result
+        }
+    };
+    result.map_err(|e| os_error(format!("Failed to {}: {}", request.action, e)))?;
+
+    enigo
+        .location()
+        .map_err(|e| os_error(format!("Failed to get mouse position: {}", e)))
+}
+
+/// Move the pointer to a screen point and click `clicks` times, returning where the pointer ends up
+pub fn os_click(point: (i32, i32), button: PointerButton, clicks: u32) -> Result<(i32, i32), ErrorInfo> {
+    let mut enigo = start_enigo()?;
+    enigo
+        .move_mouse(point.0, point.1, Coordinate::Abs)
+        .map_err(|e| os_error(format!("Failed to move mouse: {}", e)))?;
+    click_times(&mut enigo, button.into(), clicks).map_err(|e| os_error(format!("Failed to click: {}", e)))?;
+    enigo
+        .location()
+        .map_err(|e| os_error(format!("Failed to get mouse position: {}", e)))
+}
+
+// Click in quick succession, so the OS counts a double or triple click
+fn click_times(enigo: &mut Enigo, button: Button, clicks: u32) -> enigo::InputResult<()> {
+    (0..clicks).try_for_each(|index| {
+        if index > 0 {
+            thread::sleep(MULTI_CLICK_GAP);
+        }
+        enigo.button(button, Direction::Click)
+    })
+}
+
+// Press the button, run `held`, and release the button even when `held` fails
+fn press_and_release(
+    enigo: &mut Enigo,
+    button: Button,
+    held: impl FnOnce(&mut Enigo) -> enigo::InputResult<()>,
+) -> enigo::InputResult<()> {
+    enigo.button(button, Direction::Press)?;
+    let result = held(enigo);
+    let released = enigo.button(button, Direction::Release);
+    result.and(released)
+}
+
+// Evenly spaced points from `from` (exclusive) to `to` (inclusive)
+fn drag_path(from: (i32, i32), to: (i32, i32), steps: u32) -> Vec<(i32, i32)> {
+    (1..=steps)
+        .map(|step| {
+            let t = f64::from(step) / f64::from(steps);
+            let lerp = |a: i32, b: i32| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as i32;
+            (lerp(from.0, to.0), lerp(from.1, to.1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(value: Value) -> Result<(Option<Target>, Option<Target>), String> {
+        serde_json::from_value::<PointerActionRequest>(value).unwrap().targets()
+    }
+
+    #[test]
+    fn test_targets_follow_action() {
+        let (start, end) = targets(json!({
+            "action": "drag",
+            "selector": { "selector_type": "test_id", "selector_value": "card" },
+            "to_x": 400.0,
+            "to_y": 120.5
+        }))
+        .unwrap();
+        assert!(matches!(start, Some(Target::Element(_))));
+        assert_eq!(end, Some(Target::Point { x: 400.0, y: 120.5 }));
+
+        assert_eq!(targets(json!({ "action": "scroll", "delta_y": 3 })).unwrap(), (None, None));
+        assert_eq!(targets(json!({ "action": "up" })).unwrap(), (None, None));
+
+        let invalid = [
+            json!({ "action": "click" }),
+            json!({ "action": "click", "x": 10.0 }),
+            json!({ "action": "hover", "x": 1.0, "y": 2.0, "selector": { "selector_type": "id", "selector_value": "a" } }),
+            json!({ "action": "drag", "x": 1.0, "y": 2.0 }),
+            json!({ "action": "click", "x": 1.0, "y": 2.0, "to_x": 3.0, "to_y": 4.0 }),
+            json!({ "action": "scroll", "delta_x": 0 }),
+            json!({ "action": "move", "x": 1.0, "y": 2.0, "delta_y": 1 }),
+            json!({ "action": "drag", "x": 1.0, "y": 2.0, "to_x": 3.0, "to_y": 4.0, "steps": 0 }),
+        ];
+        for case in invalid {
+            assert!(targets(case.clone()).is_err(), "accepted {}", case);
+        }
+
+        assert_eq!(
+            targets(json!({ "action": "double_click" })).unwrap_err(),
+            "double_click needs a target: x and y, or selector"
+        );
+    }
+
+    #[test]
+    fn test_drag_path_ends_at_target() {
+        assert_eq!(drag_path((0, 0), (100, -50), 4), vec![(25, -13), (50, -25), (75, -38), (100, -50)]);
+        assert_eq!(drag_path((5, 5), (6, 6), 1), vec![(6, 6)]);
+    }
+}
@@ -0,0 +1,497 @@
+use enigo::{Direction, Key, Keyboard};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, resolve_window, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::selector::{ElementRef, ElementSelector};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::{os_error, run_blocking, start_enigo};
+use crate::tools::wait_for::check_wait_ms;
+use crate::tools::webview_request::PendingResponse;
+
+const DEFAULT_HOLD_MS: u64 = 30;
+const DEFAULT_DELAY_MS: u64 = 50;
+// "Mod" is Cmd on macOS and Ctrl everywhere else
+const MOD_IS_META: bool = cfg!(target_os = "macos");
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PressKeysRequest {
+    /// Label of the window (or, with `dom`, the webview) that receives the keys
+    /// (defaults to the configured default target)
+    window_label: Option<String>,
+    /// Chords to press in order, e.g. ["Mod+Shift+P", "Enter"]. Modifiers are Ctrl, Shift, Alt (Option),
+    /// Meta (Cmd, Win, Super) and Mod (Cmd on macOS, Ctrl elsewhere); keys are single characters, Enter,
+    /// Escape, Tab, Backspace, Delete, Space, ArrowUp/Down/Left/Right, Home, End, PageUp, PageDown and F1-F12
+    keys: Vec<String>,
+    /// How long each chord is held down, in milliseconds (default 30)
+    hold_ms: Option<u64>,
+    /// Pause between chords, in milliseconds (default 50)
+    delay_ms: Option<u64>,
+    /// Dispatch DOM KeyboardEvents in the webview instead of OS key presses. Works without window focus,
+    /// but the browser performs no default action (no text insertion or focus change)
+    #[serde(default)]
+    dom: bool,
+    /// Element that receives the DOM events (defaults to the focused element); only used with `dom`
+    selector: Option<ElementSelector>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PressKeysResponse {
+    mode: KeyMode,
+    /// The chords pressed, as given
+    chords: Vec<String>,
+    duration_ms: u64,
+    /// Element that received the events; "dom" mode only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<ElementRef>,
+    /// Per chord, whether a listener cancelled its keydown; "dom" mode only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cancelled: Option<Vec<bool>>,
+}
+
+/// "os" key presses or "dom" KeyboardEvents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyMode {
+    Os,
+    Dom,
+}
+
+// What the guest script reports after dispatching DOM key events
+#[derive(Debug, Deserialize)]
+struct DomKeysResult {
+    target: ElementRef,
+    cancelled: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    Meta,
+}
+
+impl Modifier {
+    // KeyboardEvent.key of the modifier itself
+    fn dom_key(self) -> &'static str {
+        match self {
+            Modifier::Control => "Control",
+            Modifier::Shift => "Shift",
+            Modifier::Alt => "Alt",
+            Modifier::Meta => "Meta",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordKey {
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Space,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Function(u8),
+    Char(char),
+}
+
+/// One chord: modifiers held in order around an optional key ("Shift" alone has no key)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    modifiers: Vec<Modifier>,
+    key: Option<ChordKey>,
+}
+
+const NAMED_KEYS: &[(&str, ChordKey)] = &[
+    ("enter", ChordKey::Enter),
+    ("return", ChordKey::Enter),
+    ("escape", ChordKey::Escape),
+    ("esc", ChordKey::Escape),
+    ("tab", ChordKey::Tab),
+    ("backspace", ChordKey::Backspace),
+    ("delete", ChordKey::Delete),
+    ("del", ChordKey::Delete),
+    ("space", ChordKey::Space),
+    ("arrowup", ChordKey::ArrowUp),
+    ("up", ChordKey::ArrowUp),
+    ("arrowdown", ChordKey::ArrowDown),
+    ("down", ChordKey::ArrowDown),
+    ("arrowleft", ChordKey::ArrowLeft),
+    ("left", ChordKey::ArrowLeft),
+    ("arrowright", ChordKey::ArrowRight),
+    ("right", ChordKey::ArrowRight),
+    ("home", ChordKey::Home),
+    ("end", ChordKey::End),
+    ("pageup", ChordKey::PageUp),
+    ("pagedown", ChordKey::PageDown),
+    ("plus", ChordKey::Char('+')),
+];
+
+fn parse_modifier(name: &str, mod_is_meta: bool) -> Option<Modifier> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(Modifier::Control),
+        "shift" => Some(Modifier::Shift),
+        "alt" | "option" | "opt" => Some(Modifier::Alt),
+        "meta" | "cmd" | "command" | "super" | "win" => Some(Modifier::Meta),
+        "mod" if mod_is_meta => Some(Modifier::Meta),
+        "mod" => Some(Modifier::Control),
+        _ => None,
+    }
+}
+
+fn parse_key(name: &str) -> Option<ChordKey> {
+    let lower = name.to_ascii_lowercase();
+    if let Some((_, key)) = NAMED_KEYS.iter().find(|(alias, _)| *alias == lower) {
+        return Some(*key);
+    }
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok())
+        && (1..=12).contains(&number)
+    {
+        return Some(ChordKey::Function(number));
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_whitespace() => Some(ChordKey::Char(c)),
+        _ => None,
+    }
+}
+
+impl KeyChord {
+    /// Parse a chord such as "Mod+Shift+P", "Escape" or "Ctrl++"
+    pub fn parse(text: &str, mod_is_meta: bool) -> Result<Self, String> {
+        let text = text.trim();
+        // A trailing "+" is the plus key itself
+        let (modifier_part, key_part) = if text == "+" {
+            ("", "+")
+        } else if let Some(rest) = text.strip_suffix("++") {
+            (rest, "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text))
+        };
+        let key_part = key_part.trim();
+        if key_part.is_empty() {
+            return Err(format!("\"{}\" has no key", text));
+        }
+
+        let mut modifiers = Vec::new();
+        let mut add = |modifier: Modifier| {
+            if !modifiers.contains(&modifier) {
+                modifiers.push(modifier);
+            }
+        };
+        for name in modifier_part.split('+').map(str::trim).filter(|name| !name.is_empty()) {
+            add(parse_modifier(name, mod_is_meta).ok_or_else(|| format!("Unknown modifier \"{}\" in \"{}\"", name, text))?);
+        }
+
+        // A chord may be a lone modifier, like "Shift"
+        if let Some(modifier) = parse_modifier(key_part, mod_is_meta) {
+            add(modifier);
+            return Ok(Self { modifiers, key: None });
+        }
+        let key = parse_key(key_part).ok_or_else(|| format!("Unknown key \"{}\" in \"{}\"", key_part, text))?;
+        Ok(Self { modifiers, key: Some(key) })
+    }
+
+    fn has(&self, modifier: Modifier) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+
+    // Letters are pressed as their lowercase key; Shift, when held, makes them uppercase
+    fn enigo_key(&self) -> Option<Key> {
+        self.key.map(|key| match key {
+            ChordKey::Enter => Key::Return,
+            ChordKey::Escape => Key::Escape,
+            ChordKey::Tab => Key::Tab,
+            ChordKey::Backspace => Key::Backspace,
+            ChordKey::Delete => Key::Delete,
+            ChordKey::Space => Key::Space,
+            ChordKey::ArrowUp => Key::UpArrow,
+            ChordKey::ArrowDown => Key::DownArrow,
+            ChordKey::ArrowLeft => Key::LeftArrow,
+            ChordKey::ArrowRight => Key::RightArrow,
+            ChordKey::Home => Key::Home,
+            ChordKey::End => Key::End,
+            ChordKey::PageUp => Key::PageUp,
+            ChordKey::PageDown => Key::PageDown,
+            ChordKey::Function(number) => FUNCTION_KEYS[usize::from(number) - 1],
+            ChordKey::Char(c) if self.modifiers.is_empty() => Key::Unicode(c),
+            ChordKey::Char(c) => Key::Unicode(c.to_ascii_lowercase()),
+        })
+    }
+
+    /// KeyboardEvent `key`/`code` and modifier flags for the guest script's DOM path
+    fn dom_event(&self) -> Value {
+        let (key, code) = match self.key {
+            None => (None, None),
+            Some(ChordKey::Char(c)) => {
+                let key = if c.is_ascii_alphabetic() && self.has(Modifier::Shift) {
+                    c.to_ascii_uppercase()
+                } else if c.is_ascii_alphabetic() && !self.modifiers.is_empty() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                };
+                let code = if c.is_ascii_alphabetic() {
+                    format!("Key{}", c.to_ascii_uppercase())
+                } else if c.is_ascii_digit() {
+                    format!("Digit{}", c)
+                } else {
+                    String::new()
+                };
+                (Some(key.to_string()), Some(code))
+            }
+            Some(ChordKey::Space) => (Some(" ".to_string()), Some("Space".to_string())),
+            Some(ChordKey::Function(number)) => (Some(format!("F{}", number)), Some(format!("F{}", number))),
+            Some(named) => {
+                let name = match named {
+                    ChordKey::Enter => "Enter",
+                    ChordKey::Escape => "Escape",
+                    ChordKey::Tab => "Tab",
+                    ChordKey::Backspace => "Backspace",
+                    ChordKey::Delete => "Delete",
+                    ChordKey::ArrowUp => "ArrowUp",
+                    ChordKey::ArrowDown => "ArrowDown",
+                    ChordKey::ArrowLeft => "ArrowLeft",
+                    ChordKey::ArrowRight => "ArrowRight",
+                    ChordKey::Home => "Home",
+                    ChordKey::End => "End",
+                    ChordKey::PageUp => "PageUp",
+                    _ => "PageDown",
+                };
+                (Some(name.to_string()), Some(name.to_string()))
+            }
+        };
+
+        json!({
+            "key": key,
+            "code": code,
+            "modifiers": self.modifiers.iter().map(|modifier| modifier.dom_key()).collect::<Vec<_>>(),
+            "ctrlKey": self.has(Modifier::Control),
+            "shiftKey": self.has(Modifier::Shift),
+            "altKey": self.has(Modifier::Alt),
+            "metaKey": self.has(Modifier::Meta)
+        })
+    }
+}
+
+const FUNCTION_KEYS: [Key; 12] = [
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+];
+
+fn modifier_key(modifier: Modifier) -> Key {
+    match modifier {
+        Modifier::Control => Key::Control,
+        Modifier::Shift => Key::Shift,
+        Modifier::Alt => Key::Alt,
+        Modifier::Meta => Key::Meta,
+    }
+}
+
+pub async fn handle_press_keys<R: Runtime>(app: &AppHandle<R>, payload: Value) -> Result<SocketResponse, Error> {
+    let request: PressKeysRequest = parse_payload(commands::PRESS_KEYS, payload)?;
+
+    // Parse every chord before pressing anything
+    if request.keys.is_empty() {
+        return Err(Error::invalid_payload("keys must contain at least one chord"));
+    }
+    let chords = request
+        .keys
+        .iter()
+        .map(|text| KeyChord::parse(text, MOD_IS_META))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::invalid_payload)?;
+    if request.selector.is_some() && !request.dom {
+        return Err(Error::invalid_payload("selector can only be used with dom"));
+    }
+
+    let hold_ms = request.hold_ms.unwrap_or(DEFAULT_HOLD_MS);
+    let delay_ms = request.delay_ms.unwrap_or(DEFAULT_DELAY_MS);
+    check_wait_ms("hold_ms", hold_ms).map_err(Error::invalid_payload)?;
+    check_wait_ms("delay_ms", delay_ms).map_err(Error::invalid_payload)?;
+
+    let window_label = target_label(app, request.window_label.as_deref());
+    let start_time = Instant::now();
+
+    let result = if request.dom {
+        dispatch_dom_keys(app, &window_label, &request, &chords, hold_ms, delay_ms)
+            .await
+            .map(|dom| (KeyMode::Dom, Some(dom)))
+    } else {
+        // Key presses go to the focused window, so bring the target forward first
+        resolve_window(app, &window_label)?.set_focus()?;
+        let chords = chords.clone();
+        run_blocking(move || press_os_keys(&chords, hold_ms, delay_ms))
+            .await
+            .map(|()| (KeyMode::Os, None))
+    };
+
+    let (mode, dom) = match result {
+        Ok(result) => result,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                id: None,
+            });
+        }
+    };
+    let (target, cancelled) = dom.map_or((None, None), |dom| (Some(dom.target), Some(dom.cancelled)));
+    let data = serde_json::to_value(PressKeysResponse {
+        mode,
+        chords: request.keys,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+        target,
+        cancelled,
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        id: None,
+    })
+}
+
+// Press each chord with enigo: modifiers down in order, the key held for hold_ms, then
+// everything released in reverse. Modifiers are released even when a press fails.
+fn press_os_keys(chords: &[KeyChord], hold_ms: u64, delay_ms: u64) -> Result<(), ErrorInfo> {
+    let mut enigo = start_enigo()?;
+
+    for (index, chord) in chords.iter().enumerate() {
+        if index > 0 {
+            thread::sleep(Duration::from_millis(delay_ms));
+        }
+
+        let mut held = Vec::new();
+        let mut result = Ok(());
+        for key in chord.modifiers.iter().copied().map(modifier_key) {
+            result = enigo.key(key, Direction::Press);
+            if result.is_err() {
+                break;
+            }
+            held.push(key);
+        }
+        if result.is_ok() {
+            result = match chord.enigo_key() {
+                Some(key) => enigo.key(key, Direction::Press).and_then(|()| {
+                    thread::sleep(Duration::from_millis(hold_ms));
+                    enigo.key(key, Direction::Release)
+                }),
+                None => {
+                    thread::sleep(Duration::from_millis(hold_ms));
+                    Ok(())
+                }
+            };
+        }
+        for key in held.into_iter().rev() {
+            let _ = enigo.key(key, Direction::Release);
+        }
+        result.map_err(|e| os_error(format!("Failed to press chord {}: {}", index + 1, e)))?;
+    }
+
+    Ok(())
+}
+
+// Hand the chords to the guest script, which dispatches keydown/keyup events to the target element
+async fn dispatch_dom_keys<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    request: &PressKeysRequest,
+    chords: &[KeyChord],
+    hold_ms: u64,
+    delay_ms: u64,
+) -> Result<DomKeysResult, ErrorInfo> {
+    let (resolved_label, _webview) = resolve_webview(app, window_label).map_err(Error::into_info)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "press-keys-response");
+
+    let js_payload = json!({
+        "requestId": pending.request_id(),
+        "selector": request.selector.as_ref().map(ElementSelector::to_js),
+        "chords": chords.iter().map(KeyChord::dom_event).collect::<Vec<_>>(),
+        "holdMs": hold_ms,
+        "delayMs": delay_ms
+    });
+    app.emit_to(&resolved_label, "press-keys", js_payload)
+        .map_err(|e| ErrorInfo::new(ErrorCode::WebviewError, format!("Failed to emit press-keys event: {}", e)))?;
+
+    let timeout = Duration::from_secs(5) + Duration::from_millis((hold_ms + delay_ms) * chords.len() as u64);
+    let result = pending
+        .wait(timeout).await
+        .map_err(|e| e.into_info(&format!("Timeout waiting for key events in '{}'", resolved_label)))?;
+    if result["success"].as_bool() == Some(true) {
+        parse_guest_data(commands::PRESS_KEYS, result["data"].clone())
+    } else {
+        Err(ErrorInfo::from_guest(&result, ErrorCode::WebviewError, "Unknown error dispatching key events"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!(
+            KeyChord::parse("Mod+Shift+P", true).unwrap(),
+            KeyChord { modifiers: vec![Modifier::Meta, Modifier::Shift], key: Some(ChordKey::Char('P')) }
+        );
+        assert_eq!(KeyChord::parse("mod+s", false).unwrap().modifiers, vec![Modifier::Control]);
+        assert_eq!(KeyChord::parse("esc", false).unwrap().key, Some(ChordKey::Escape));
+        assert_eq!(KeyChord::parse("Ctrl++", false).unwrap().key, Some(ChordKey::Char('+')));
+        assert_eq!(KeyChord::parse("F5", false).unwrap().key, Some(ChordKey::Function(5)));
+        assert_eq!(
+            KeyChord::parse("Shift", false).unwrap(),
+            KeyChord { modifiers: vec![Modifier::Shift], key: None }
+        );
+
+        for invalid in ["", "Ctrl+", "Hyper+A", "Ctrl+Enterr", "F13"] {
+            assert!(KeyChord::parse(invalid, false).is_err(), "accepted {:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_dom_event() {
+        let event = KeyChord::parse("Ctrl+Shift+p", false).unwrap().dom_event();
+        assert_eq!(event["key"], "P");
+        assert_eq!(event["code"], "KeyP");
+        assert_eq!(event["modifiers"], json!(["Control", "Shift"]));
+        assert_eq!(event["ctrlKey"], true);
+        assert_eq!(event["metaKey"], false);
+
+        let event = KeyChord::parse("ArrowDown", false).unwrap().dom_event();
+        assert_eq!(event["key"], "ArrowDown");
+        assert_eq!(event["code"], "ArrowDown");
+        assert_eq!(KeyChord::parse("Space", false).unwrap().dom_event()["key"], " ");
+    }
+}
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::fmt;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime, Webview};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
+use crate::tools::webview_request::{PendingResponse, ResponseTimeout};
+
+// IndexedDB opens and cursors can be slow on large databases
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RECORD_LIMIT: usize = 10_000;
+
+/// Which of the webview's stores a storage request works on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageArea {
+    /// window.localStorage
+    #[default]
+    LocalStorage,
+    /// window.sessionStorage
+    SessionStorage,
+    /// document.cookie, plus the webview's cookie store (including HttpOnly cookies) for get and keys
+    Cookies,
+    /// IndexedDB databases, object stores and records
+    IndexedDb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageAction {
+    Get,
+    Set,
+    Remove,
+    Clear,
+    Keys,
+    Databases,
+    ObjectStores,
+}
+
+/// The store for actions that only exist on IndexedDB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexedDbOnly {
+    IndexedDb,
+}
+
+impl From<IndexedDbOnly> for StorageArea {
+    fn from(_: IndexedDbOnly) -> Self {
+        StorageArea::IndexedDb
+    }
+}
+
+/// Attributes of a cookie written by set, or matched by remove and clear
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CookieOptions {
+    /// Cookie path (default "/")
+    path: Option<String>,
+    /// Cookie domain (defaults to the page's host)
+    domain: Option<String>,
+    /// Lifetime in seconds; a session cookie when omitted
+    max_age: Option<i64>,
+    /// Only send the cookie over HTTPS
+    #[serde(default)]
+    secure: bool,
+    /// "strict", "lax" or "none"
+    same_site: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StorageRequest {
+    /// Label of the webview whose storage is managed (defaults to the configured default target)
+    #[serde(alias = "windowLabel")]
+    window_label: Option<String>,
+    #[serde(flatten)]
+    operation: StorageOperation,
+}
+
+/// What to do, with the arguments the action takes
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StorageOperation {
+    /// Read `key`, or every item (IndexedDB: the first `limit` records) without one
+    Get {
+        #[serde(flatten)]
+        location: StorageLocation,
+        /// Item key, cookie name or IndexedDB record key (JSON numbers and arrays keep their type)
+        key: Option<String>,
+        /// Most IndexedDB records to return (default 100)
+        limit: Option<usize>,
+    },
+    /// Write `value` under `key`; IndexedDB puts a record, with `key` only for stores without a keyPath
+    Set {
+        #[serde(flatten)]
+        location: StorageLocation,
+        /// Item key, cookie name or IndexedDB record key; required except for IndexedDB
+        key: Option<String>,
+        /// Value to write; IndexedDB values are parsed as JSON when they are valid JSON
+        value: String,
+        /// Cookie attributes, for the cookies store
+        cookie: Option<CookieOptions>,
+    },
+    /// Delete `key`
+    Remove {
+        #[serde(flatten)]
+        location: StorageLocation,
+        /// Item key, cookie name or IndexedDB record key
+        key: String,
+        /// Attributes of the cookie to remove, for the cookies store
+        cookie: Option<CookieOptions>,
+    },
+    /// Delete everything in the store (cookies: every cookie document.cookie can see)
+    Clear {
+        #[serde(flatten)]
+        location: StorageLocation,
+        /// Attributes of the cookies to remove, for the cookies store
+        cookie: Option<CookieOptions>,
+    },
+    /// List the keys (cookie names, IndexedDB record keys)
+    Keys {
+        #[serde(flatten)]
+        location: StorageLocation,
+        /// Most IndexedDB keys to return (default 100)
+        limit: Option<usize>,
+    },
+    /// List IndexedDB databases with their versions
+    Databases { store: IndexedDbOnly },
+    /// List the object stores of an IndexedDB `database` with key paths, indexes and record counts
+    ObjectStores {
+        store: IndexedDbOnly,
+        /// IndexedDB database name
+        database: String,
+    },
+}
+
+/// The store an action works on; IndexedDB records live in a database's object store
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StorageLocation {
+    /// Store to work on (default "local_storage")
+    #[serde(default)]
+    store: StorageArea,
+    /// IndexedDB database name; required for indexed_db
+    database: Option<String>,
+    /// IndexedDB object store name; required for indexed_db
+    object_store: Option<String>,
+}
+
+impl StorageLocation {
+    // The IndexedDB arguments are required for indexed_db and refused elsewhere
+    fn validate(&self) -> Result<(), String> {
+        if self.store != StorageArea::IndexedDb {
+            if self.database.is_some() || self.object_store.is_some() {
+                return Err("database and object_store can only be used with the indexed_db store".to_string());
+            }
+            return Ok(());
+        }
+        match (&self.database, &self.object_store) {
+            (None, _) => Err("database is required for the indexed_db store".to_string()),
+            (_, None) => Err("object_store is required for the indexed_db store".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl StorageRequest {
+    fn action(&self) -> StorageAction {
+        match self.operation {
+            StorageOperation::Get { .. } => StorageAction::Get,
+            StorageOperation::Set { .. } => StorageAction::Set,
+            StorageOperation::Remove { .. } => StorageAction::Remove,
+            StorageOperation::Clear { .. } => StorageAction::Clear,
+            StorageOperation::Keys { .. } => StorageAction::Keys,
+            StorageOperation::Databases { .. } => StorageAction::Databases,
+            StorageOperation::ObjectStores { .. } => StorageAction::ObjectStores,
+        }
+    }
+
+    fn location(&self) -> Option<&StorageLocation> {
+        match &self.operation {
+            StorageOperation::Get { location, .. }
+            | StorageOperation::Set { location, .. }
+            | StorageOperation::Remove { location, .. }
+            | StorageOperation::Clear { location, .. }
+            | StorageOperation::Keys { location, .. } => Some(location),
+            StorageOperation::Databases { .. } | StorageOperation::ObjectStores { .. } => None,
+        }
+    }
+
+    fn store(&self) -> StorageArea {
+        match &self.operation {
+            StorageOperation::Databases { store } | StorageOperation::ObjectStores { store, .. } => (*store).into(),
+            _ => self.location().map_or(StorageArea::default(), |location| location.store),
+        }
+    }
+
+    fn key(&self) -> Option<&str> {
+        match &self.operation {
+            StorageOperation::Get { key, .. } | StorageOperation::Set { key, .. } => key.as_deref(),
+            StorageOperation::Remove { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    fn cookie(&self) -> Option<&CookieOptions> {
+        match &self.operation {
+            StorageOperation::Set { cookie, .. }
+            | StorageOperation::Remove { cookie, .. }
+            | StorageOperation::Clear { cookie, .. } => cookie.as_ref(),
+            _ => None,
+        }
+    }
+
+    // Check the arguments that depend on the store; deserialization has checked the rest
+    fn validate(&self) -> Result<(), String> {
+        if let Some(location) = self.location() {
+            location.validate()?;
+        }
+        if self.cookie().is_some() && self.store() != StorageArea::Cookies {
+            return Err("cookie can only be used with the cookies store".to_string());
+        }
+
+        match &self.operation {
+            // IndexedDB stores with a keyPath take the key from the value
+            StorageOperation::Set { location, key: None, .. } if location.store != StorageArea::IndexedDb => {
+                Err(format!("key is required for \"set\" on {}", json!(location.store)))
+            }
+            StorageOperation::Get { limit: Some(limit), .. } | StorageOperation::Keys { limit: Some(limit), .. }
+                if *limit == 0 || *limit > MAX_RECORD_LIMIT =>
+            {
+                Err(format!("limit must be between 1 and {}", MAX_RECORD_LIMIT))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // The request as the guest script reads it
+    fn js_payload(&self, request_id: &str) -> Value {
+        let (value, limit) = match &self.operation {
+            StorageOperation::Set { value, .. } => (Some(value.as_str()), None),
+            StorageOperation::Get { limit, .. } | StorageOperation::Keys { limit, .. } => (None, *limit),
+            _ => (None, None),
+        };
+        let (database, object_store) = match &self.operation {
+            StorageOperation::ObjectStores { database, .. } => (Some(database.as_str()), None),
+            _ => self.location().map_or((None, None), |location| {
+                (location.database.as_deref(), location.object_store.as_deref())
+            }),
+        };
+
+        json!({
+            "requestId": request_id,
+            "store": self.store(),
+            "action": self.action(),
+            "key": self.key(),
+            "value": value,
+            "database": database,
+            "objectStore": object_store,
+            "cookie": self.cookie().map(|cookie| json!({
+                "path": cookie.path,
+                "domain": cookie.domain,
+                "maxAge": cookie.max_age,
+                "secure": cookie.secure,
+                "sameSite": cookie.same_site
+            })),
+            "limit": limit
+        })
+    }
+}
+
+// Define a custom error type for storage operations
+#[derive(Debug)]
+pub enum StorageError {
+    WebviewOperation(String),
+    JavaScriptError(String),
+    Timeout(String),
+}
+
+// Implement Display for the error
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::WebviewOperation(s) => {
+                write!(f, "Storage operation error: {}", s)
+            }
+            StorageError::JavaScriptError(s) => write!(f, "JavaScript error: {}", s),
+            StorageError::Timeout(s) => write!(f, "Operation timed out: {}", s),
+        }
+    }
+}
+
+// Make the error serializable
+impl Serialize for StorageError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<StorageError> for ErrorInfo {
+    fn from(error: StorageError) -> Self {
+        let code = match error {
+            StorageError::WebviewOperation(_) => ErrorCode::WebviewError,
+            StorageError::JavaScriptError(_) => ErrorCode::JsException,
+            StorageError::Timeout(_) => ErrorCode::Timeout,
+        };
+        ErrorInfo::new(code, error.to_string())
+    }
+}
+
+// Support conversion from timeout error
+impl From<ResponseTimeout> for StorageError {
+    fn from(err: ResponseTimeout) -> Self {
+        StorageError::Timeout(format!("Timeout waiting for storage response: {}", err))
+    }
+}
+
+// Handler function for the socket server
+pub async fn handle_manage_storage<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    // Parse params from payload
+    let params: StorageRequest = parse_payload(commands::MANAGE_STORAGE, payload)?;
+
+    // Validate input parameters
+    params.validate().map_err(Error::invalid_payload)?;
+
+    // Fall back to the configured default target
+    let window_label = target_label(app, params.window_label.as_deref());
+    let (resolved_label, webview) = resolve_webview(app, &window_label)?;
+
+    let mut result = perform_storage_operation(app, &resolved_label, &params).await;
+
+    // document.cookie hides HttpOnly cookies; the webview's cookie store has them all
+    if params.store() == StorageArea::Cookies
+        && matches!(params.action(), StorageAction::Get | StorageAction::Keys)
+        && let Ok(data) = result
+    {
+        result = Ok(with_native_cookies(&webview, &params, data));
+    }
+
+    // Handle the result
+    match result {
+        Ok(data) => Ok(SocketResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+            id: None,
+        }),
+        Err(e) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.into()),
+            id: None,
+        }),
+    }
+}
+
+// Implementation function
+async fn perform_storage_operation<R: Runtime>(
+    app: &AppHandle<R>,
+    resolved_label: &str,
+    params: &StorageRequest,
+) -> Result<Value, StorageError> {
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "manage-storage-response");
+
+    // Emit event to the webview
+    app.emit_to(resolved_label, "manage-storage", params.js_payload(pending.request_id()))
+        .map_err(|e| StorageError::WebviewOperation(format!("Failed to emit event: {}", e)))?;
+
+    // Wait for response with timeout
+    let response = pending.wait(RESPONSE_TIMEOUT).await?;
+    if response["success"].as_bool() != Some(true) {
+        return Err(StorageError::JavaScriptError(
+            response["error"].as_str().unwrap_or("Unknown error").to_string(),
+        ));
+    }
+    Ok(response.get("data").cloned().unwrap_or(Value::Null))
+}
+
+// Combine the document.cookie view with the cookies the webview stores for its current URL
+fn with_native_cookies<R: Runtime>(webview: &Webview<R>, params: &StorageRequest, document: Value) -> Value {
+    let native = webview
+        .url()
+        .and_then(|url| webview.cookies_for_url(url))
+        .map(|cookies| {
+            cookies
+                .iter()
+                .filter(|cookie| params.key().is_none_or(|key| cookie.name() == key))
+                .map(|cookie| {
+                    if params.action() == StorageAction::Keys {
+                        return json!(cookie.name());
+                    }
+                    json!({
+                        "name": cookie.name(),
+                        "value": cookie.value(),
+                        "domain": cookie.domain(),
+                        "path": cookie.path(),
+                        "secure": cookie.secure(),
+                        "httpOnly": cookie.http_only(),
+                        "sameSite": cookie.same_site().map(|same_site| same_site.to_string()),
+                        "expires": cookie.expires_datetime().map(|expires| expires.unix_timestamp())
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+    match native {
+        Ok(native) => json!({ "document": document, "native": native }),
+        // Custom schemes such as tauri:// have no cookie store to read
+        Err(e) => json!({ "document": document, "native": null, "nativeError": e.to_string() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: Value) -> StorageRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_actions_per_store() {
+        // Payloads from before the store parameter still address localStorage
+        let legacy = parse(json!({ "action": "set", "key": "theme", "value": "dark", "windowLabel": "main" }));
+        assert_eq!(legacy.store(), StorageArea::LocalStorage);
+        assert_eq!(legacy.window_label.as_deref(), Some("main"));
+        assert!(legacy.validate().is_ok());
+
+        assert!(parse(json!({ "store": "indexed_db", "action": "databases" })).validate().is_ok());
+        assert!(
+            parse(json!({ "store": "indexed_db", "action": "set", "database": "app", "object_store": "notes", "value": "{}" }))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            parse(json!({ "store": "cookies", "action": "set", "key": "sid", "value": "1", "cookie": { "max_age": 60 } }))
+                .validate()
+                .is_ok()
+        );
+
+        // Arguments every store needs are checked while parsing
+        let unparsable = [
+            json!({ "action": "set", "key": "theme" }),
+            json!({ "store": "session_storage", "action": "remove" }),
+            json!({ "store": "session_storage", "action": "databases" }),
+            json!({ "store": "indexed_db", "action": "object_stores" }),
+            json!({ "action": "rename", "key": "theme" }),
+        ];
+        for case in unparsable {
+            assert!(serde_json::from_value::<StorageRequest>(case.clone()).is_err(), "parsed {}", case);
+        }
+
+        let invalid = [
+            json!({ "action": "set", "value": "dark" }),
+            json!({ "store": "local_storage", "action": "get", "database": "app" }),
+            json!({ "store": "local_storage", "action": "set", "key": "k", "value": "v", "cookie": {} }),
+            json!({ "store": "indexed_db", "action": "get", "database": "app" }),
+            json!({ "store": "indexed_db", "action": "keys", "database": "app", "object_store": "notes", "limit": 0 }),
+        ];
+        for case in invalid {
+            assert!(parse(case.clone()).validate().is_err(), "accepted {}", case);
+        }
+    }
+
+    #[test]
+    fn test_js_payload_uses_guest_option_names() {
+        let request = parse(json!({
+            "store": "cookies",
+            "action": "remove",
+            "key": "sid",
+            "cookie": { "path": "/app", "same_site": "lax" }
+        }));
+
+        let payload = request.js_payload("req-1");
+        assert_eq!(payload["store"], "cookies");
+        assert_eq!(payload["action"], "remove");
+        assert_eq!(payload["objectStore"], Value::Null);
+        assert_eq!(
+            payload["cookie"],
+            json!({ "path": "/app", "domain": null, "maxAge": null, "secure": false, "sameSite": "lax" })
+        );
+    }
+}
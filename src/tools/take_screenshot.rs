@@ -8,6 +8,8 @@ use log::info;
 use crate::TauriMcpExt;
 use crate::models::ScreenshotRequest;
 use crate::socket_server::SocketResponse;
+use crate::shared::commands;
+use crate::tools::parse_payload;
 
 /// Common function to process and compress an image - used by platform implementations
 pub fn process_image(mut dynamic_image: DynamicImage, params: &ScreenshotParams) -> Result<String> {
@@ -151,8 +153,7 @@ pub async fn handle_take_screenshot<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse> {
-    let payload: ScreenshotRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for takeScreenshot: {}", e)))?;
+    let payload: ScreenshotRequest = parse_payload(commands::TAKE_SCREENSHOT, payload)?;
 
     // Call the async method
     let result = app.tauri_mcp().take_screenshot_async(payload).await;
@@ -164,12 +165,14 @@ pub async fn handle_take_screenshot<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into_info()),
+            id: None,
         }),
     }
 }
@@ -4,15 +4,16 @@ use tauri::{AppHandle, Runtime};
 use crate::TauriMcpExt;
 use crate::error::Error;
 use crate::models::TextInputRequest;
+use crate::shared::commands;
 use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
 
 pub async fn handle_simulate_text_input<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
     // Parse the payload
-    let params: TextInputRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for simulateTextInput: {}", e)))?;
+    let params: TextInputRequest = parse_payload(commands::SIMULATE_TEXT_INPUT, payload)?;
 
     // Call the async method
     let result = app.tauri_mcp().simulate_text_input_async(params).await;
@@ -25,12 +26,14 @@ pub async fn handle_simulate_text_input<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into_info()),
+            id: None,
         }),
     }
 }
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{Error, ErrorCode, ErrorInfo};
+use crate::selector::ElementSelector;
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::webview_request::PendingResponse;
+
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+/// Longest wait a request, or an element command's auto-wait, may ask for
+pub const MAX_WAIT_MS: u64 = 120_000;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 100;
+const MIN_POLL_INTERVAL_MS: u64 = 10;
+const DEFAULT_IDLE_MS: u64 = 500;
+// Extra time for the guest script to report its own timeout before we give up on it
+const RESPONSE_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitCondition {
+    /// The selector's element is rendered with a non-empty box
+    Visible,
+    /// The selector matches nothing, or only elements that aren't visible
+    Hidden,
+    /// The selector's element is visible and not disabled
+    Enabled,
+    /// The page (or the selector's element) contains `text`
+    Text,
+    /// The page URL contains `url`, or matches it as a regular expression with `url_regex`
+    Url,
+    /// The JavaScript expression or function body in `predicate` returns a truthy value
+    Predicate,
+    /// No fetch, XHR or invoke call has been in flight for `idle_ms`
+    NetworkIdle,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WaitForRequest {
+    /// Label of the webview to watch (defaults to the configured default target)
+    window_label: Option<String>,
+    /// What to wait for
+    condition: WaitCondition,
+    /// Element to watch; required for "visible", "hidden" and "enabled", and scopes "text" to the element
+    selector: Option<ElementSelector>,
+    /// Text to wait for, for the "text" condition (case-insensitive substring)
+    text: Option<String>,
+    /// URL fragment to wait for, for the "url" condition
+    url: Option<String>,
+    /// Treat `url` as a JavaScript regular expression
+    #[serde(default)]
+    url_regex: bool,
+    /// JavaScript expression or function body, for the "predicate" condition; may return a Promise
+    predicate: Option<String>,
+    /// How long the network must stay quiet, for the "network_idle" condition (default 500)
+    idle_ms: Option<u64>,
+    /// Give up after this many milliseconds (default 5000, at most 120000)
+    timeout_ms: Option<u64>,
+    /// How often the condition is checked, in milliseconds (default 100)
+    poll_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitForResponse {
+    condition: WaitCondition,
+    /// How long the wait took
+    elapsed_ms: u64,
+    /// What the condition observed when it was met; its shape depends on the condition,
+    /// e.g. `{ url }` for "url", `{ quietMs }` for "network_idle" or the predicate's value
+    #[serde(default)]
+    value: Value,
+}
+
+impl WaitForRequest {
+    // Check that the condition's own argument was given and the timings are in range
+    fn validate(&self) -> Result<(), String> {
+        let missing = match self.condition {
+            WaitCondition::Visible | WaitCondition::Hidden | WaitCondition::Enabled if self.selector.is_none() => {
+                Some("selector")
+            }
+            WaitCondition::Text if self.text.as_deref().is_none_or(str::is_empty) => Some("text"),
+            WaitCondition::Url if self.url.as_deref().is_none_or(str::is_empty) => Some("url"),
+            WaitCondition::Predicate if self.predicate.as_deref().is_none_or(|code| code.trim().is_empty()) => {
+                Some("predicate")
+            }
+            _ => None,
+        };
+        if let Some(field) = missing {
+            return Err(format!("{} is required for the {} condition", field, json!(self.condition)));
+        }
+
+        check_wait_ms("timeout_ms", self.timeout_ms())?;
+        check_wait_ms("idle_ms", self.idle_ms.unwrap_or(DEFAULT_IDLE_MS))?;
+        Ok(())
+    }
+
+    fn timeout_ms(&self) -> u64 {
+        self.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)
+    }
+
+    // The request as the guest script reads it
+    fn js_payload(&self, request_id: &str) -> Value {
+        json!({
+            "requestId": request_id,
+            "condition": self.condition,
+            "selector": self.selector.as_ref().map(ElementSelector::to_js),
+            "text": self.text,
+            "url": self.url,
+            "urlRegex": self.url_regex,
+            "predicate": self.predicate,
+            "idleMs": self.idle_ms.unwrap_or(DEFAULT_IDLE_MS),
+            "timeoutMs": self.timeout_ms(),
+            "pollIntervalMs": self.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS).max(MIN_POLL_INTERVAL_MS)
+        })
+    }
+}
+
+/// Reject waits longer than `MAX_WAIT_MS`
+pub fn check_wait_ms(field: &str, ms: u64) -> Result<(), String> {
+    if ms > MAX_WAIT_MS {
+        return Err(format!("{} must be at most {} (got {})", field, MAX_WAIT_MS, ms));
+    }
+    Ok(())
+}
+
+pub async fn handle_wait_for<R: Runtime>(app: &AppHandle<R>, payload: Value) -> Result<SocketResponse, Error> {
+    let request: WaitForRequest = parse_payload(commands::WAIT_FOR, payload)?;
+    request.validate().map_err(Error::invalid_payload)?;
+
+    // Fall back to the configured default target
+    let window_label = target_label(app, request.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
+
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(app, "wait-for-response");
+
+    app.emit_to(&resolved_label, "wait-for", request.js_payload(pending.request_id()))
+        .map_err(|e| Error::new(ErrorCode::WebviewError, format!("Failed to emit wait-for event: {}", e)))?;
+
+    // The guest script enforces the timeout; ours only covers a webview that stopped answering
+    let response_timeout = Duration::from_millis(request.timeout_ms()) + RESPONSE_GRACE;
+    match pending.wait(response_timeout).await {
+        Ok(mut result) if result["success"].as_bool() == Some(true) => {
+            let response: WaitForResponse = parse_guest_data(commands::WAIT_FOR, result["data"].take())?;
+            let data = serde_json::to_value(response)
+                .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+
+            Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+                id: None,
+            })
+        }
+        // Timeouts carry the condition's last observed state
+        Ok(result) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(ErrorInfo::from_guest(&result, ErrorCode::WebviewError, "Unknown error while waiting")),
+            id: None,
+        }),
+        Err(e) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.into_info(&format!("No answer from '{}' while waiting", resolved_label))),
+            id: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: Value) -> WaitForRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_requires_condition_argument() {
+        assert!(parse(json!({ "condition": "visible" })).validate().is_err());
+        assert!(parse(json!({ "condition": "text", "text": "" })).validate().is_err());
+        assert!(parse(json!({ "condition": "predicate", "predicate": "  " })).validate().is_err());
+        assert!(parse(json!({ "condition": "network_idle", "timeout_ms": MAX_WAIT_MS + 1 })).validate().is_err());
+
+        assert!(parse(json!({ "condition": "network_idle" })).validate().is_ok());
+        assert!(
+            parse(json!({ "condition": "hidden", "selector": { "selector_type": "css", "selector_value": ".spinner" } }))
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_invalid_payload_names_the_field() {
+        let payload = json!({ "condition": "hidden", "selector": { "selector_type": "css", "selector_value": 1 } });
+        let error = parse_payload::<WaitForRequest>(commands::WAIT_FOR, payload).unwrap_err().into_info();
+
+        assert_eq!(error.code, ErrorCode::InvalidPayload);
+        assert!(!error.retryable);
+        assert_eq!(error.details.unwrap()["path"], "selector.selector_value");
+    }
+
+    #[test]
+    fn test_js_payload_fills_defaults() {
+        let request = parse(json!({ "condition": "url", "url": "/settings", "poll_interval_ms": 1 }));
+
+        assert_eq!(
+            request.js_payload("req-1"),
+            json!({
+                "requestId": "req-1",
+                "condition": "url",
+                "selector": null,
+                "text": null,
+                "url": "/settings",
+                "urlRegex": false,
+                "predicate": null,
+                "idleMs": DEFAULT_IDLE_MS,
+                "timeoutMs": DEFAULT_TIMEOUT_MS,
+                "pollIntervalMs": MIN_POLL_INTERVAL_MS
+            })
+        );
+    }
+}
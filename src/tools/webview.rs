@@ -1,18 +1,26 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer}; // Add Deserialize for parsing payload
 use serde_json::Value;
 use std::fmt;
-use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Error as TauriError, Emitter, Listener, Manager, Runtime, WebviewWindow};
-
-use crate::desktop::resolve_webview;
-
-// Custom error enum for the get_dom_text command
+use tauri::{AppHandle, Error as TauriError, Emitter, Runtime};
+
+use crate::desktop::{resolve_webview, target_label};
+use crate::error::{ErrorCode, ErrorInfo};
+use crate::models::ScreenshotResponse;
+use crate::selector::ElementSelector;
+use crate::shared::commands;
+use crate::tools::{parse_guest_data, parse_payload};
+use crate::tools::wait_for::check_wait_ms;
+use crate::tools::webview_request::{PendingResponse, ResponseTimeout};
+
+// Custom error enum for DOM retrieval
 #[derive(Debug)] // Add Serialize for the enum itself if it needs to be directly serialized
 // For now, we serialize its string representation
 pub enum GetDomError {
     WebviewOperation(String),
     JavaScriptError(String),
+    Timeout(String),
     DomIsEmpty,
 }
 
@@ -22,6 +30,7 @@ impl fmt::Display for GetDomError {
         match self {
             GetDomError::WebviewOperation(s) => write!(f, "Webview operation error: {}", s),
             GetDomError::JavaScriptError(s) => write!(f, "JavaScript execution error: {}", s),
+            GetDomError::Timeout(s) => write!(f, "Operation timed out: {}", s),
             GetDomError::DomIsEmpty => write!(f, "Retrieved DOM string is empty"),
         }
     }
@@ -37,13 +46,105 @@ impl Serialize for GetDomError {
     }
 }
 
+impl From<GetDomError> for ErrorInfo {
+    fn from(error: GetDomError) -> Self {
+        let code = match error {
+            GetDomError::WebviewOperation(_) | GetDomError::DomIsEmpty => ErrorCode::WebviewError,
+            GetDomError::JavaScriptError(_) => ErrorCode::JsException,
+            GetDomError::Timeout(_) => ErrorCode::Timeout,
+        };
+        ErrorInfo::new(code, error.to_string())
+    }
+}
+
 // Automatically convert tauri::Error into GetDomError::WebviewOperation or JavaScriptError
 impl From<TauriError> for GetDomError {
     fn from(err: TauriError) -> Self {
-        // Basic differentiation, could be more sophisticated if TauriError variants allow
-        match err {
-            _ => GetDomError::JavaScriptError(err.to_string()), // Default to JS error as eval is involved
-        }
+        // Default to JS error as eval is involved
+        GetDomError::JavaScriptError(err.to_string())
+    }
+}
+
+/// Payload structure for get_dom (a bare label string is also accepted).
+/// Without any of the filter or paging options the full HTML comes back as a plain string,
+/// as it always has; with any of them the result is a page object.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct GetDomPayload {
+    /// Label of the webview whose DOM is returned (defaults to the configured default target)
+    window_label: Option<String>,
+    /// CSS selector of the element to return (the whole document when omitted)
+    root: Option<String>,
+    /// Element nesting depth below the root to include; deeper children are replaced by a comment
+    max_depth: Option<u32>,
+    /// Content to remove: "scripts", "styles" (style elements, stylesheets and style attributes),
+    /// "svg" (SVG contents) and "attributes" (all but id, class, name, type, role, aria-*,
+    /// href, src, alt, title, placeholder, value, for and data-testid)
+    #[serde(default)]
+    strip: Vec<DomStrip>,
+    /// Drop elements that are not rendered (display: none, visibility: hidden, hidden attribute)
+    #[serde(default)]
+    visible_only: bool,
+    /// Maximum UTF-8 bytes of HTML per page; the rest is fetched with `cursor`
+    max_bytes: Option<usize>,
+    /// `nextCursor` from the previous page. The page continues the same serialization, so the
+    /// other options are ignored
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DomStrip {
+    Scripts,
+    Styles,
+    Svg,
+    Attributes,
+}
+
+/// get_dom's result: the whole HTML as a string when no filter or paging option is given,
+/// otherwise one page of the filtered HTML
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum GetDomResponse {
+    Html(String),
+    Page(DomPage),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DomPage {
+    html: String,
+    /// Byte offset of this page in the serialized document
+    offset: usize,
+    /// UTF-8 bytes in the whole serialized document
+    total_bytes: usize,
+    /// Pass as `cursor` to fetch the next page; null on the last page
+    next_cursor: Option<String>,
+}
+
+// Smallest page size accepted, so every page makes progress
+const MIN_DOM_PAGE_BYTES: usize = 1024;
+
+impl GetDomPayload {
+    // Only a label was given, so the caller expects the legacy plain-string result
+    fn is_legacy(&self) -> bool {
+        self.root.is_none()
+            && self.max_depth.is_none()
+            && self.strip.is_empty()
+            && !self.visible_only
+            && self.max_bytes.is_none()
+            && self.cursor.is_none()
+    }
+
+    // The filter and paging options as the guest script reads them
+    fn js_options(&self) -> Value {
+        serde_json::json!({
+            "root": self.root,
+            "maxDepth": self.max_depth,
+            "strip": self.strip,
+            "visibleOnly": self.visible_only,
+            "maxBytes": self.max_bytes.map(|bytes| bytes.max(MIN_DOM_PAGE_BYTES)),
+            "cursor": self.cursor
+        })
     }
 }
 
@@ -52,53 +153,49 @@ pub async fn handle_get_dom<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<crate::socket_server::SocketResponse, crate::error::Error> {
-    // Parse the window label from the payload - handle both string and object formats
-    let window_label = if payload.is_string() {
+    // Parse the payload - handle both string and object formats
+    let request = if payload.is_string() {
         // Direct string format
-        payload
-            .as_str()
-            .ok_or_else(|| {
-                crate::error::Error::Anyhow("Invalid string payload for getDom".to_string())
-            })?
-            .to_string()
+        GetDomPayload {
+            window_label: payload.as_str().map(str::to_string),
+            ..GetDomPayload::default()
+        }
     } else if payload.is_object() {
-        // Object with window_label property
-        payload
-            .get("window_label")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| {
-                crate::error::Error::Anyhow(
-                    "Missing or invalid window_label in payload object".to_string(),
-                )
-            })?
+        // Object with window_label and optional filter/paging options
+        parse_payload::<GetDomPayload>(commands::GET_DOM, payload)?
     } else {
-        return Err(crate::error::Error::Anyhow(format!(
+        return Err(crate::error::Error::invalid_payload(format!(
             "Invalid payload format for getDom: expected string or object with window_label, got {}",
             payload
         )));
     };
 
     // Use resolve_webview to support both single and multi-webview architectures
+    let window_label = target_label(app, request.window_label.as_deref());
     let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
 
     // Get DOM content using the resolved webview label
-    let result = get_dom_text_for_label(app.clone(), &resolved_label).await;
+    let result = if request.is_legacy() {
+        get_dom_text_for_label(app.clone(), &resolved_label).await.map(GetDomResponse::Html)
+    } else {
+        get_dom_page_for_label(app.clone(), &resolved_label, &request).await.map(GetDomResponse::Page)
+    };
     match result {
-        Ok(dom_text) => {
-            let data = serde_json::to_value(dom_text).map_err(|e| {
-                crate::error::Error::Anyhow(format!("Failed to serialize response: {}", e))
-            })?;
+        Ok(response) => {
+            let data = serde_json::to_value(response)
+                .map_err(|e| crate::error::Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
             Ok(crate::socket_server::SocketResponse {
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into()),
+            id: None,
         }),
     }
 }
@@ -110,24 +207,24 @@ pub async fn get_dom_text_for_label<R: Runtime>(
 ) -> Result<String, GetDomError> {
     eprintln!("[TAURI_MCP] Getting DOM from webview: {}", webview_label);
 
-    // Set up channel and listener BEFORE emitting to avoid race condition
-    let (tx, rx) = mpsc::channel();
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(&app, "got-dom-content-response");
 
-    app.once("got-dom-content-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
+    let js_payload = serde_json::json!({
+        "requestId": pending.request_id()
     });
 
-    app.emit_to(webview_label, "got-dom-content", "test")
+    app.emit_to(webview_label, "got-dom-content", js_payload)
         .map_err(|e| GetDomError::WebviewOperation(format!("Failed to emit to {}: {}", webview_label, e)))?;
 
     // Wait for the content
-    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
-        Ok(dom_string) => {
+    match pending.wait(std::time::Duration::from_secs(5)).await {
+        Ok(response) => {
+            let dom_string = response.get("data").and_then(|v| v.as_str()).unwrap_or_default();
             if dom_string.is_empty() {
                 Err(GetDomError::DomIsEmpty)
             } else {
-                Ok(dom_string)
+                Ok(dom_string.to_string())
             }
         }
         Err(e) => {
@@ -136,32 +233,93 @@ pub async fn get_dom_text_for_label<R: Runtime>(
     }
 }
 
-#[tauri::command]
-pub async fn get_dom_text<R: Runtime>(
+/// Get one page of filtered DOM HTML from a webview
+pub async fn get_dom_page_for_label<R: Runtime>(
     app: AppHandle<R>,
-    _window: WebviewWindow<R>,
-) -> Result<String, GetDomError> {
-    // Legacy function - calls the new implementation with "main" label
-    get_dom_text_for_label(app, "main").await
+    webview_label: &str,
+    request: &GetDomPayload,
+) -> Result<DomPage, GetDomError> {
+    // Register the correlated listener BEFORE emitting to avoid race condition
+    let pending = PendingResponse::listen(&app, "got-dom-content-response");
+
+    let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
+        "options": request.js_options()
+    });
+
+    app.emit_to(webview_label, "got-dom-content", js_payload)
+        .map_err(|e| GetDomError::WebviewOperation(format!("Failed to emit to {}: {}", webview_label, e)))?;
+
+    // Serializing with filters walks the whole document, so allow longer than a plain read
+    let mut response = pending.wait(Duration::from_secs(10)).await?;
+    if let Some(error) = response.get("error").and_then(Value::as_str) {
+        return Err(GetDomError::JavaScriptError(error.to_string()));
+    }
+    parse_guest_data(commands::GET_DOM, response["data"].take())
+        .map_err(|e| GetDomError::WebviewOperation(e.message))
 }
 
-// Second fix: add From implementation for RecvTimeoutError
-impl From<mpsc::RecvTimeoutError> for GetDomError {
-    fn from(err: mpsc::RecvTimeoutError) -> Self {
-        GetDomError::WebviewOperation(format!("Timeout waiting for DOM: {}", err))
+// Second fix: add From implementation for ResponseTimeout
+impl From<ResponseTimeout> for GetDomError {
+    fn from(err: ResponseTimeout) -> Self {
+        GetDomError::Timeout(format!("Timeout waiting for DOM: {}", err))
     }
 }
 
 // Define the structure for get_element_position payload
-#[derive(Debug, Deserialize)]
-struct GetElementPositionPayload {
-    window_label: String,
-    selector_type: String,
-    selector_value: String,
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetElementPositionPayload {
+    /// Label of the webview to search in (defaults to the configured default target)
+    window_label: Option<String>,
+    #[serde(flatten)]
+    selector: ElementSelector,
+    /// Click the element once found
     #[serde(default)]
     should_click: bool,
+    /// Return coordinates without converting them to screen space
     #[serde(default)]
     raw_coordinates: bool,
+    /// Retry for up to this many milliseconds until the element is visible, stable, enabled and,
+    /// when clicking, not covered by another element (fails on the first lookup when omitted)
+    auto_wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetElementPositionResponse {
+    /// Center of the element in logical pixels from the webview's top-left corner,
+    /// the coordinates the mouse commands take
+    x: f64,
+    y: f64,
+    element: FoundElement,
+    /// Whether the element was clicked
+    clicked: bool,
+    /// Outcome of the click when `should_click` was set, otherwise null
+    click_result: Value,
+    /// Rectangles and scroll offsets used to compute the position, for troubleshooting
+    debug: Value,
+}
+
+/// The element a selector found
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FoundElement {
+    /// Tag name, upper-case for HTML elements
+    tag: String,
+    /// The class attribute
+    #[serde(deserialize_with = "class_name")]
+    classes: String,
+    id: String,
+    /// Text content, trimmed
+    text: String,
+    /// Placeholder of an input element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    placeholder: Option<String>,
+}
+
+// SVG elements report className as an object rather than a string
+fn class_name<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    Ok(value.as_str().unwrap_or_default().to_string())
 }
 
 // Handle getting element position
@@ -170,92 +328,107 @@ pub async fn handle_get_element_position<R: Runtime>(
     payload: Value,
 ) -> Result<crate::socket_server::SocketResponse, crate::error::Error> {
     // Parse the payload
-    let payload = serde_json::from_value::<GetElementPositionPayload>(payload).map_err(|e| {
-        crate::error::Error::Anyhow(format!("Invalid payload for get_element_position: {}", e))
-    })?;
+    let payload = parse_payload::<GetElementPositionPayload>(commands::GET_ELEMENT_POSITION, payload)?;
+    let auto_wait_ms = payload.auto_wait_ms.unwrap_or(0);
+    check_wait_ms("auto_wait_ms", auto_wait_ms).map_err(crate::error::Error::invalid_payload)?;
 
-    // Resolve webview label (supports multi-webview architecture, e.g. "main" -> "preview")
-    let (resolved_label, _webview) = resolve_webview(app, &payload.window_label)?;
-
-    // Create a channel to receive the result
-    let (tx, rx) = mpsc::channel();
-
-    // Event name for the response
-    let event_name = "get-element-position-response";
+    // Fall back to the configured default target, then resolve through the target map
+    let window_label = target_label(app, payload.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
 
-    // Set up the listener for the response
-    app.once(event_name, move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    // Register the correlated listener for the response before emitting
+    let pending = PendingResponse::listen(app, "get-element-position-response");
 
     // Prepare the request payload with selector information
     let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
         "windowLabel": resolved_label,
-        "selectorType": payload.selector_type,
-        "selectorValue": payload.selector_value,
+        "selector": payload.selector.to_js(),
         "shouldClick": payload.should_click,
-        "rawCoordinates": payload.raw_coordinates
+        "rawCoordinates": payload.raw_coordinates,
+        "autoWaitMs": auto_wait_ms
     });
 
     // Emit the event to the resolved webview
     app.emit_to(&resolved_label, "get-element-position", js_payload)
         .map_err(|e| {
-            crate::error::Error::Anyhow(format!("Failed to emit get-element-position event: {}", e))
+            crate::error::Error::new(ErrorCode::WebviewError, format!("Failed to emit get-element-position event: {}", e))
         })?;
 
-    // Wait for the response with a timeout
-    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
-        Ok(result) => {
-            // Parse the result
-            let result_value: Value = serde_json::from_str(&result).map_err(|e| {
-                crate::error::Error::Anyhow(format!("Failed to parse result: {}", e))
-            })?;
-
+    // Wait for the response with a timeout, on top of any time spent waiting for the element
+    match pending.wait(Duration::from_secs(5) + Duration::from_millis(auto_wait_ms)).await {
+        Ok(result_value) => {
             let success = result_value
                 .get("success")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
 
             if success {
+                let data = guest_result::<GetElementPositionResponse>(commands::GET_ELEMENT_POSITION, result_value)?;
                 Ok(crate::socket_server::SocketResponse {
                     success: true,
-                    data: Some(result_value.get("data").cloned().unwrap_or(Value::Null)),
+                    data: Some(data),
                     error: None,
+                    id: None,
                 })
             } else {
-                let error = result_value
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error occurred");
-
+                // Selector failures carry a code, and the candidates when the selector was ambiguous
                 Ok(crate::socket_server::SocketResponse {
                     success: false,
                     data: None,
-                    error: Some(error.to_string()),
+                    error: Some(ErrorInfo::from_guest(&result_value, ErrorCode::WebviewError, "Unknown error occurred")),
+                    id: None,
                 })
             }
         }
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(format!(
-                "Timeout waiting for element position result: {}",
-                e
-            )),
+            error: Some(e.into_info("Timeout waiting for element position result")),
+            id: None,
         }),
     }
 }
 
 // Define the structure for send_text_to_element payload
-#[derive(Debug, Deserialize)]
-struct SendTextToElementPayload {
-    window_label: String,
-    selector_type: String,
-    selector_value: String,
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SendTextToElementPayload {
+    /// Label of the webview to search in (defaults to the configured default target)
+    window_label: Option<String>,
+    #[serde(flatten)]
+    selector: ElementSelector,
+    /// Text to type into the element
     text: String,
+    /// Delay between keystrokes in milliseconds
     #[serde(default = "default_delay_ms")]
     delay_ms: u32,
+    /// Retry for up to this many milliseconds until the element is visible, stable, enabled and
+    /// editable (fails on the first lookup when omitted)
+    auto_wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SendTextToElementResponse {
+    element: TypedElement,
+}
+
+/// The element text was sent to
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedElement {
+    /// Tag name, upper-case for HTML elements
+    tag: String,
+    /// The class attribute
+    #[serde(deserialize_with = "class_name")]
+    classes: String,
+    id: String,
+    /// Type of an input element, otherwise null
+    #[serde(rename = "type")]
+    input_type: Option<String>,
+    /// The text that was sent
+    text: String,
+    /// Whether the element takes text input; text sent to other elements replaces their content
+    is_editable: bool,
 }
 
 // Default delay_ms value
@@ -269,113 +442,114 @@ pub async fn handle_send_text_to_element<R: Runtime>(
     payload: Value,
 ) -> Result<crate::socket_server::SocketResponse, crate::error::Error> {
     // Parse the payload
-    let payload = serde_json::from_value::<SendTextToElementPayload>(payload).map_err(|e| {
-        crate::error::Error::Anyhow(format!("Invalid payload for send_text_to_element: {}", e))
-    })?;
-
-    // Resolve webview label (supports multi-webview architecture, e.g. "main" -> "preview")
-    let (resolved_label, _webview) = resolve_webview(app, &payload.window_label)?;
+    let payload = parse_payload::<SendTextToElementPayload>(commands::SEND_TEXT_TO_ELEMENT, payload)?;
+    let auto_wait_ms = payload.auto_wait_ms.unwrap_or(0);
+    check_wait_ms("auto_wait_ms", auto_wait_ms).map_err(crate::error::Error::invalid_payload)?;
 
-    // Create a channel to receive the result
-    let (tx, rx) = mpsc::channel();
-
-    // Event name for the response
-    let event_name = "send-text-to-element-response";
+    // Fall back to the configured default target, then resolve through the target map
+    let window_label = target_label(app, payload.window_label.as_deref());
+    let (resolved_label, _webview) = resolve_webview(app, &window_label)?;
 
-    // Set up the listener for the response
-    app.once(event_name, move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    // Register the correlated listener for the response before emitting
+    let pending = PendingResponse::listen(app, "send-text-to-element-response");
 
     // Prepare the request payload
     let js_payload = serde_json::json!({
-        "selectorType": payload.selector_type,
-        "selectorValue": payload.selector_value,
+        "requestId": pending.request_id(),
+        "selector": payload.selector.to_js(),
         "text": payload.text,
-        "delayMs": payload.delay_ms
+        "delayMs": payload.delay_ms,
+        "autoWaitMs": auto_wait_ms
     });
 
     // Emit the event to the resolved webview
     app.emit_to(&resolved_label, "send-text-to-element", js_payload)
         .map_err(|e| {
-            crate::error::Error::Anyhow(format!("Failed to emit send-text-to-element event: {}", e))
+            crate::error::Error::new(ErrorCode::WebviewError, format!("Failed to emit send-text-to-element event: {}", e))
         })?;
 
-    // Wait for the response with a timeout
-    match rx.recv_timeout(std::time::Duration::from_secs(30)) {
+    // Wait for the response with a timeout, on top of any time spent waiting for the element
+    match pending.wait(Duration::from_secs(30) + Duration::from_millis(auto_wait_ms)).await {
         // Longer timeout for typing text
-        Ok(result) => {
-            // Parse the result
-            let result_value: Value = serde_json::from_str(&result).map_err(|e| {
-                crate::error::Error::Anyhow(format!("Failed to parse result: {}", e))
-            })?;
-
+        Ok(result_value) => {
             let success = result_value
                 .get("success")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
 
             if success {
+                let data = guest_result::<SendTextToElementResponse>(commands::SEND_TEXT_TO_ELEMENT, result_value)?;
                 Ok(crate::socket_server::SocketResponse {
                     success: true,
-                    data: Some(result_value.get("data").cloned().unwrap_or(Value::Null)),
+                    data: Some(data),
                     error: None,
+                    id: None,
                 })
             } else {
-                let error = result_value
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error occurred");
-
+                // Selector failures carry a code, and the candidates when the selector was ambiguous
                 Ok(crate::socket_server::SocketResponse {
                     success: false,
                     data: None,
-                    error: Some(error.to_string()),
+                    error: Some(ErrorInfo::from_guest(&result_value, ErrorCode::WebviewError, "Unknown error occurred")),
+                    id: None,
                 })
             }
         }
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(format!("Timeout waiting for text input completion: {}", e)),
+            error: Some(e.into_info("Timeout waiting for text input completion")),
+            id: None,
         }),
     }
 }
 
+// Check a guest script's `data` against the command's result type
+fn guest_result<T: Serialize + serde::de::DeserializeOwned>(
+    command: &str,
+    mut result: Value,
+) -> Result<Value, crate::error::Error> {
+    let data: T = parse_guest_data(command, result["data"].take())?;
+    serde_json::to_value(data).map_err(|e| crate::error::Error::Anyhow(format!("Failed to serialize response: {}", e)))
+}
+
 // ========== JS-Based Screenshot Capture ==========
 // This captures the webview's content using JavaScript (similar to Playwright).
 // It doesn't require Screen Recording permissions or window focus.
 
-/// Payload structure for JS-based screenshot capture
-#[derive(Debug, Deserialize)]
+/// Payload structure for JS-based screenshot capture (a bare label string is also accepted)
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct CaptureScreenshotPayload {
+    /// Label of the webview to capture (defaults to the configured default target)
     window_label: Option<String>,
+    /// JPEG quality from 1 to 100 (defaults to 85)
     quality: Option<u8>,
+    /// Maximum width in pixels; wider captures are scaled down (defaults to 1920)
     max_width: Option<u32>,
 }
 
+impl CaptureScreenshotPayload {
+    // Objects must fit the payload; anything else is read as a bare window label
+    fn parse(payload: Value) -> Result<Self, crate::error::Error> {
+        if payload.is_object() {
+            return parse_payload(commands::CAPTURE_SCREENSHOT, payload);
+        }
+        Ok(CaptureScreenshotPayload {
+            window_label: payload.as_str().map(str::to_string),
+            ..CaptureScreenshotPayload::default()
+        })
+    }
+}
+
 /// Handler for JS-based screenshot capture
 pub async fn handle_capture_screenshot<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<crate::socket_server::SocketResponse, crate::error::Error> {
     // Parse payload
-    let parsed: CaptureScreenshotPayload = if payload.is_object() {
-        serde_json::from_value(payload.clone()).unwrap_or(CaptureScreenshotPayload {
-            window_label: None,
-            quality: None,
-            max_width: None,
-        })
-    } else {
-        CaptureScreenshotPayload {
-            window_label: payload.as_str().map(|s| s.to_string()),
-            quality: None,
-            max_width: None,
-        }
-    };
+    let parsed = CaptureScreenshotPayload::parse(payload)?;
 
-    let window_label = parsed.window_label.unwrap_or_else(|| "main".to_string());
+    let window_label = target_label(app, parsed.window_label.as_deref());
     let quality = parsed.quality.unwrap_or(85);
     let max_width = parsed.max_width.unwrap_or(1920);
 
@@ -386,17 +560,12 @@ pub async fn handle_capture_screenshot<R: Runtime>(
 
     eprintln!("[TAURI_MCP] Resolved to webview: {}", resolved_label);
 
-    // Create channel to receive the result
-    let (tx, rx) = mpsc::channel();
-
-    // Set up listener for the response
-    app.once("capture-screenshot-response", move |event| {
-        let payload = event.payload().to_string();
-        let _ = tx.send(payload);
-    });
+    // Register the correlated listener for the response before emitting
+    let pending = PendingResponse::listen(app, "capture-screenshot-response");
 
     // Prepare the payload for the JS handler
     let js_payload = serde_json::json!({
+        "requestId": pending.request_id(),
         "quality": quality,
         "maxWidth": max_width
     });
@@ -406,34 +575,33 @@ pub async fn handle_capture_screenshot<R: Runtime>(
 
     app.emit_to(&resolved_label, "capture-screenshot", js_payload)
         .map_err(|e| {
-            crate::error::Error::Anyhow(format!("Failed to emit capture-screenshot event: {}", e))
+            crate::error::Error::new(ErrorCode::WebviewError, format!("Failed to emit capture-screenshot event: {}", e))
         })?;
 
     // Wait for the response with a timeout (longer timeout for rendering)
-    match rx.recv_timeout(Duration::from_secs(30)) {
-        Ok(result_string) => {
-            // Parse the result
-            let result: Value = serde_json::from_str(&result_string).map_err(|e| {
-                crate::error::Error::Anyhow(format!("Failed to parse screenshot result: {}", e))
-            })?;
-
+    match pending.wait(Duration::from_secs(30)).await {
+        Ok(result) => {
             let success = result.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
 
             if success {
                 // Extract the data URL
-                let data = result.get("data").cloned().unwrap_or(Value::Null);
+                let data = result.get("data").and_then(Value::as_str).map(str::to_string);
 
                 eprintln!("[TAURI_MCP] JS-based screenshot capture successful");
 
                 // Return in the same format as the native screenshot
+                let response = ScreenshotResponse {
+                    data,
+                    success: true,
+                    error: None,
+                };
+                let data = serde_json::to_value(response)
+                    .map_err(|e| crate::error::Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
                 Ok(crate::socket_server::SocketResponse {
                     success: true,
-                    data: Some(serde_json::json!({
-                        "data": data,
-                        "success": true,
-                        "error": null
-                    })),
+                    data: Some(data),
                     error: None,
+                    id: None,
                 })
             } else {
                 let error = result
@@ -446,7 +614,8 @@ pub async fn handle_capture_screenshot<R: Runtime>(
                 Ok(crate::socket_server::SocketResponse {
                     success: false,
                     data: None,
-                    error: Some(error.to_string()),
+                    error: Some(ErrorInfo::from_guest(&result, ErrorCode::WebviewError, error)),
+                    id: None,
                 })
             }
         }
@@ -455,7 +624,8 @@ pub async fn handle_capture_screenshot<R: Runtime>(
             Ok(crate::socket_server::SocketResponse {
                 success: false,
                 data: None,
-                error: Some(format!("Timeout waiting for screenshot capture: {}", e)),
+                error: Some(e.into_info("Timeout waiting for screenshot capture")),
+                id: None,
             })
         }
     }
@@ -464,8 +634,26 @@ pub async fn handle_capture_screenshot<R: Runtime>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::selector::SelectorType;
     use serde_json::json;
 
+    #[test]
+    fn test_guest_results_keep_their_shape() {
+        let page = json!({ "html": "<main>", "offset": 0, "totalBytes": 2048, "nextCursor": "1:1024" });
+        let page: DomPage = parse_guest_data(commands::GET_DOM, page).unwrap();
+        assert_eq!(serde_json::to_value(GetDomResponse::Page(page)).unwrap()["nextCursor"], "1:1024");
+        assert_eq!(serde_json::to_value(GetDomResponse::Html("<html>".into())).unwrap(), "<html>");
+
+        // SVG elements report their className as an object
+        let element: FoundElement =
+            parse_guest_data(commands::GET_ELEMENT_POSITION, json!({ "tag": "svg", "classes": {}, "id": "", "text": "" }))
+                .unwrap();
+        assert_eq!(serde_json::to_value(element).unwrap(), json!({ "tag": "svg", "classes": "", "id": "", "text": "" }));
+
+        let error = parse_guest_data::<SendTextToElementResponse>(commands::SEND_TEXT_TO_ELEMENT, json!({})).unwrap_err();
+        assert_eq!(error.code, ErrorCode::WebviewError);
+    }
+
     // ========== CaptureScreenshotPayload Parsing Tests ==========
 
     #[test]
@@ -545,26 +733,14 @@ mod tests {
 
     #[test]
     fn test_parse_payload_as_object() {
-        // Simulate the parsing logic from handle_capture_screenshot for object payloads
+        // Object payloads as handle_capture_screenshot parses them
         let payload = json!({
             "window_label": "test_window",
             "quality": 75,
             "max_width": 1600
         });
 
-        let parsed: CaptureScreenshotPayload = if payload.is_object() {
-            serde_json::from_value(payload.clone()).unwrap_or(CaptureScreenshotPayload {
-                window_label: None,
-                quality: None,
-                max_width: None,
-            })
-        } else {
-            CaptureScreenshotPayload {
-                window_label: payload.as_str().map(|s| s.to_string()),
-                quality: None,
-                max_width: None,
-            }
-        };
+        let parsed = CaptureScreenshotPayload::parse(payload).unwrap();
 
         assert_eq!(parsed.window_label, Some("test_window".to_string()));
         assert_eq!(parsed.quality, Some(75));
@@ -573,22 +749,10 @@ mod tests {
 
     #[test]
     fn test_parse_payload_as_string() {
-        // Simulate the parsing logic from handle_capture_screenshot for string payloads
+        // Bare label payloads as handle_capture_screenshot parses them
         let payload = json!("my_window");
 
-        let parsed: CaptureScreenshotPayload = if payload.is_object() {
-            serde_json::from_value(payload.clone()).unwrap_or(CaptureScreenshotPayload {
-                window_label: None,
-                quality: None,
-                max_width: None,
-            })
-        } else {
-            CaptureScreenshotPayload {
-                window_label: payload.as_str().map(|s| s.to_string()),
-                quality: None,
-                max_width: None,
-            }
-        };
+        let parsed = CaptureScreenshotPayload::parse(payload).unwrap();
 
         assert_eq!(parsed.window_label, Some("my_window".to_string()));
         assert_eq!(parsed.quality, None);
@@ -600,25 +764,21 @@ mod tests {
         // Test parsing null payload (edge case)
         let payload = json!(null);
 
-        let parsed: CaptureScreenshotPayload = if payload.is_object() {
-            serde_json::from_value(payload.clone()).unwrap_or(CaptureScreenshotPayload {
-                window_label: None,
-                quality: None,
-                max_width: None,
-            })
-        } else {
-            CaptureScreenshotPayload {
-                window_label: payload.as_str().map(|s| s.to_string()),
-                quality: None,
-                max_width: None,
-            }
-        };
+        let parsed = CaptureScreenshotPayload::parse(payload).unwrap();
 
         assert_eq!(parsed.window_label, None);
         assert_eq!(parsed.quality, None);
         assert_eq!(parsed.max_width, None);
     }
 
+    #[test]
+    fn test_parse_payload_rejects_bad_fields() {
+        let error = CaptureScreenshotPayload::parse(json!({ "quality": "high" })).unwrap_err().into_info();
+
+        assert_eq!(error.code, ErrorCode::InvalidPayload);
+        assert_eq!(error.details.unwrap()["path"], "quality");
+    }
+
     // ========== Default Value Tests ==========
 
     #[test]
@@ -689,6 +849,7 @@ mod tests {
             success: true,
             data: Some(data.clone()),
             error: None,
+            id: None,
         };
 
         assert!(response.success);
@@ -707,27 +868,32 @@ mod tests {
         let response = crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some("Failed to capture screenshot".to_string()),
+            error: Some(ErrorInfo::new(ErrorCode::WebviewError, "Failed to capture screenshot")),
+            id: None,
         };
 
         assert!(!response.success);
         assert!(response.data.is_none());
-        assert_eq!(response.error, Some("Failed to capture screenshot".to_string()));
+        assert_eq!(response.error.unwrap().message, "Failed to capture screenshot");
     }
 
     #[test]
     fn test_timeout_error_response() {
         // Test timeout error response format
-        let error_msg = format!("Timeout waiting for screenshot capture: {}", "channel is empty and sending half is closed");
+        let error = ResponseTimeout(Duration::from_secs(30)).into_info("Timeout waiting for screenshot capture");
 
         let response = crate::socket_server::SocketResponse {
             success: false,
             data: None,
-            error: Some(error_msg.clone()),
+            error: Some(error),
+            id: None,
         };
 
         assert!(!response.success);
-        assert!(response.error.as_ref().unwrap().contains("Timeout"));
+        let error = response.error.unwrap();
+        assert_eq!(error.code, ErrorCode::Timeout);
+        assert!(error.retryable);
+        assert!(error.message.starts_with("Timeout waiting for screenshot capture: no reply"));
     }
 
     // ========== GetDomError Tests ==========
@@ -751,6 +917,40 @@ mod tests {
         assert_eq!(serialized, "\"Retrieved DOM string is empty\"");
     }
 
+    // ========== GetDomPayload Tests ==========
+
+    #[test]
+    fn test_get_dom_label_only_is_legacy() {
+        let parsed: GetDomPayload = serde_json::from_value(json!({ "window_label": "main" })).unwrap();
+        assert!(parsed.is_legacy());
+
+        let parsed: GetDomPayload = serde_json::from_value(json!({ "visible_only": true })).unwrap();
+        assert!(!parsed.is_legacy());
+        assert_eq!(parsed.window_label, None);
+    }
+
+    #[test]
+    fn test_get_dom_js_options() {
+        let parsed: GetDomPayload = serde_json::from_value(json!({
+            "root": "#app",
+            "strip": ["scripts", "svg"],
+            "max_bytes": 10
+        }))
+        .unwrap();
+
+        assert_eq!(
+            parsed.js_options(),
+            json!({
+                "root": "#app",
+                "maxDepth": null,
+                "strip": ["scripts", "svg"],
+                "visibleOnly": false,
+                "maxBytes": MIN_DOM_PAGE_BYTES,
+                "cursor": null
+            })
+        );
+    }
+
     // ========== GetElementPositionPayload Tests ==========
 
     #[test]
@@ -764,9 +964,9 @@ mod tests {
         });
 
         let parsed: GetElementPositionPayload = serde_json::from_value(payload).unwrap();
-        assert_eq!(parsed.window_label, "main");
-        assert_eq!(parsed.selector_type, "css");
-        assert_eq!(parsed.selector_value, "#my-button");
+        assert_eq!(parsed.window_label.as_deref(), Some("main"));
+        assert_eq!(parsed.selector.selector_type, SelectorType::Css);
+        assert_eq!(parsed.selector.selector_value, "#my-button");
         assert!(parsed.should_click);
         assert!(!parsed.raw_coordinates);
     }
@@ -780,9 +980,9 @@ mod tests {
         });
 
         let parsed: GetElementPositionPayload = serde_json::from_value(payload).unwrap();
-        assert_eq!(parsed.window_label, "main");
-        assert_eq!(parsed.selector_type, "xpath");
-        assert_eq!(parsed.selector_value, "//button");
+        assert_eq!(parsed.window_label.as_deref(), Some("main"));
+        assert_eq!(parsed.selector.selector_type, SelectorType::Xpath);
+        assert_eq!(parsed.selector.selector_value, "//button");
         // Default values for optional boolean fields
         assert!(!parsed.should_click);
         assert!(!parsed.raw_coordinates);
@@ -801,9 +1001,9 @@ mod tests {
         });
 
         let parsed: SendTextToElementPayload = serde_json::from_value(payload).unwrap();
-        assert_eq!(parsed.window_label, "main");
-        assert_eq!(parsed.selector_type, "css");
-        assert_eq!(parsed.selector_value, "#input-field");
+        assert_eq!(parsed.window_label.as_deref(), Some("main"));
+        assert_eq!(parsed.selector.selector_type, SelectorType::Css);
+        assert_eq!(parsed.selector.selector_value, "#input-field");
         assert_eq!(parsed.text, "Hello World");
         assert_eq!(parsed.delay_ms, 50);
     }
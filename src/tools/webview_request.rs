@@ -0,0 +1,117 @@
+use serde_json::Value;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, EventId, Listener, Runtime};
+use tokio::sync::oneshot;
+
+use crate::error::{ErrorCode, ErrorInfo};
+
+// Monotonic counter used to build per-request correlation tokens
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A pending round trip to the guest script.
+///
+/// The listener is registered BEFORE the request is emitted (to avoid a race with
+/// fast replies) and only accepts response events whose `requestId` matches this
+/// request's token. Replies for other requests - including late replies for
+/// requests that already timed out - are ignored. The listener is removed when
+/// the value is dropped, so a reply that arrives after the timeout is discarded.
+///
+/// Waiting is async: the reply arrives on a oneshot channel, so no runtime thread
+/// is blocked while the guest script works.
+pub struct PendingResponse<R: Runtime> {
+    app: AppHandle<R>,
+    event_id: EventId,
+    request_id: String,
+    rx: oneshot::Receiver<Value>,
+}
+
+/// No matching reply arrived within the timeout
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseTimeout(pub Duration);
+
+impl fmt::Display for ResponseTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no reply from the webview within {} ms", self.0.as_millis())
+    }
+}
+
+impl std::error::Error for ResponseTimeout {}
+
+impl ResponseTimeout {
+    /// A `timeout` error whose message starts with what was being waited for
+    pub fn into_info(self, waiting_for: &str) -> ErrorInfo {
+        ErrorInfo::new(ErrorCode::Timeout, format!("{}: {}", waiting_for, self))
+    }
+}
+
+impl<R: Runtime> PendingResponse<R> {
+    /// Start listening for `response_event` replies carrying a fresh correlation token.
+    pub fn listen(app: &AppHandle<R>, response_event: &str) -> Self {
+        let request_id = next_request_id(response_event);
+        let (tx, rx) = oneshot::channel();
+        // The listener may fire more than once; only the first matching reply is delivered
+        let tx = Mutex::new(Some(tx));
+
+        let expected_id = request_id.clone();
+        let event_id = app.listen(response_event, move |event| {
+            let payload: Value = match serde_json::from_str(event.payload()) {
+                Ok(payload) => payload,
+                Err(_) => return,
+            };
+
+            if payload.get("requestId").and_then(|v| v.as_str()) == Some(expected_id.as_str())
+                && let Some(tx) = tx.lock().unwrap().take()
+            {
+                let _ = tx.send(payload);
+            }
+        });
+
+        PendingResponse {
+            app: app.clone(),
+            event_id,
+            request_id,
+            rx,
+        }
+    }
+
+    /// The correlation token the guest script must echo back as `requestId`.
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// Wait for the matching reply, consuming the pending request.
+    pub async fn wait(mut self, timeout: Duration) -> Result<Value, ResponseTimeout> {
+        match tokio::time::timeout(timeout, &mut self.rx).await {
+            Ok(Ok(reply)) => Ok(reply),
+            // The sender only goes away with the listener, which lives as long as we do
+            Ok(Err(_)) | Err(_) => Err(ResponseTimeout(timeout)),
+        }
+    }
+}
+
+impl<R: Runtime> Drop for PendingResponse<R> {
+    fn drop(&mut self) {
+        self.app.unlisten(self.event_id);
+    }
+}
+
+fn next_request_id(response_event: &str) -> String {
+    let counter = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}-{}", response_event, std::process::id(), counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_ids_are_unique() {
+        let first = next_request_id("execute-js-response");
+        let second = next_request_id("execute-js-response");
+        assert_ne!(first, second);
+        assert!(first.starts_with("execute-js-response-"));
+    }
+}
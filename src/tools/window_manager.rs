@@ -4,14 +4,15 @@ use tauri::{AppHandle, Runtime};
 use crate::TauriMcpExt;
 use crate::error::Error;
 use crate::models::WindowManagerRequest;
+use crate::shared::commands;
 use crate::socket_server::SocketResponse;
+use crate::tools::parse_payload;
 
 pub async fn handle_manage_window<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> Result<SocketResponse, Error> {
-    let payload: WindowManagerRequest = serde_json::from_value(payload)
-        .map_err(|e| Error::Anyhow(format!("Invalid payload for manageWindow: {}", e)))?;
+    let payload: WindowManagerRequest = parse_payload(commands::MANAGE_WINDOW, payload)?;
 
     // Call the async method
     let result = app.tauri_mcp().manage_window_async(payload).await;
@@ -23,12 +24,14 @@ pub async fn handle_manage_window<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                id: None,
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(e.into_info()),
+            id: None,
         }),
     }
 }